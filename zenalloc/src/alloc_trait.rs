@@ -4,19 +4,31 @@ use core::ptr::NonNull;
 pub trait Allocator {
     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
     fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by this allocator for `layout`.
     unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by this allocator for `old_layout`.
     unsafe fn grow(
         &self,
         ptr: NonNull<u8>,
         old_layout: Layout,
         new_layout: Layout,
     ) -> Result<NonNull<[u8]>, AllocError>;
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by this allocator for `old_layout`.
     unsafe fn grow_zeroed(
         &self,
         ptr: NonNull<u8>,
         old_layout: Layout,
         new_layout: Layout,
     ) -> Result<NonNull<[u8]>, AllocError>;
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by this allocator for `old_layout`.
     unsafe fn shrink(
         &self,
         ptr: NonNull<u8>,
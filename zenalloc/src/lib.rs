@@ -1,33 +1,127 @@
 #![no_std]
-#![feature(alloc_error_handler)]
+#![cfg_attr(not(any(test, feature = "std")), feature(alloc_error_handler))]
 #![feature(allocator_api)]
+/* Stable since 1.95; kept so older nightlies still build. */
+#![allow(stable_features)]
 #![feature(alloc_layout_extra)]
+#![feature(dropck_eyepatch)]
 
 extern crate alloc;
 use alloc::alloc::{alloc, alloc_zeroed, dealloc, realloc};
 
-use crate::alloc_trait::Allocator;
 use core::alloc::{AllocError, Layout};
 use core::ptr::NonNull;
 use core::sync::atomic::{AtomicPtr, Ordering};
 use core::{mem, ptr};
 
 mod alloc_trait;
+mod macros;
+mod try_clone;
+mod try_collect;
 mod zen_arc;
+mod zen_arena;
+mod zen_array_vec;
+mod zen_binary_heap;
+mod zen_bit_vec;
 mod zen_box;
+mod zen_btree_map;
 mod zen_cow;
+mod zen_error;
+mod zen_hash_map;
+mod zen_intrusive;
 mod zen_rc;
+#[cfg(feature = "serde")]
+mod zen_serde;
+mod zen_slab;
+mod zen_small_vec;
 mod zen_string;
 mod zen_vec;
+mod zen_vec_deque;
+
+// The modules above stay private; this is the public API. Types are
+// re-exported at the crate root so paths don't track the file layout.
+pub use crate::alloc_trait::Allocator;
+pub use crate::try_clone::TryClone;
+pub use crate::try_collect::{TryCollect, TryExtend, TryFromIterator};
+pub use crate::zen_arc::zen_arc::ZenArc;
+pub use crate::zen_arena::zen_arena::ZenArena;
+pub use crate::zen_array_vec::zen_array_vec::{CapacityError, InsertError, ZenArrayVec};
+pub use crate::zen_binary_heap::zen_binary_heap::{PeekMut, ZenBinaryHeap};
+pub use crate::zen_bit_vec::zen_bit_set::ZenBitSet;
+pub use crate::zen_bit_vec::zen_bit_vec::ZenBitVec;
+pub use crate::zen_box::zen_box::ZenBox;
+pub use crate::zen_btree_map::zen_btree_map::ZenBTreeMap;
+pub use crate::zen_btree_map::zen_btree_set::ZenBTreeSet;
+pub use crate::zen_cow::zen_cow::ZenCow;
+pub use crate::zen_error::zen_error::{AsciiError, TryReserveError, ZenError};
+pub use crate::zen_hash_map::sip_hasher::{
+    DefaultHashBuilder, SipHasher, SipHasher13, SipHasher24, ZenRandomState,
+};
+pub use crate::zen_hash_map::zen_hash_map::{Entry, OccupiedEntry, VacantEntry, ZenHashMap};
+pub use crate::zen_hash_map::zen_hash_set::ZenHashSet;
+pub use crate::zen_intrusive::adapter::{
+    Adapter, AlreadyLinked, IntrusivePointer, KeyAdapter, Target,
+};
+pub use crate::zen_intrusive::linked_list::{LinkedList, LinkedListLink};
+pub use crate::zen_intrusive::rb_tree::{RBTree, RBTreeLink};
+pub use crate::zen_intrusive::singly_linked_list::{SinglyLinkedList, SinglyLinkedListLink};
+pub use crate::zen_rc::zen_rc::ZenRc;
+#[cfg(feature = "serde")]
+pub use crate::zen_serde::zen_serde::{MaxPrealloc, DEFAULT_MAX_PREALLOC_BYTES};
+pub use crate::zen_slab::zen_slab::{SlabKey, ZenSlab};
+pub use crate::zen_small_vec::zen_small_vec::ZenSmallVec;
+pub use crate::zen_string::ascii_str::AsciiStr;
+pub use crate::zen_string::try_to_string::TryToString;
+pub use crate::zen_string::zen_ascii_char::ZenAsciiChar;
+pub use crate::zen_string::zen_ascii_string::{FromStringError, ZenAsciiString};
+pub use crate::zen_string::zen_string::{FromUtf8Error, ZenString};
+pub use crate::zen_vec::zen_vec::ZenVec;
+pub use crate::zen_vec_deque::zen_vec_deque::ZenVecDeque;
+
+// Helpers the exported macros expand to. Not part of the API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::macros::try_box;
+    pub use crate::zen_string::try_to_string::try_format;
+}
 
 pub struct System;
 
+#[cfg(test)]
+std::thread_local! {
+    // Allocations this thread may still make before they start failing.
+    static ALLOCS_LEFT: core::cell::Cell<Option<usize>> = const { core::cell::Cell::new(None) };
+}
+
+// Makes every allocation on this thread after the next `n` fail, or lifts
+// the limit for `None`, so tests can reach each failure path.
+#[cfg(test)]
+fn fail_allocs_after(n: Option<usize>) {
+    ALLOCS_LEFT.with(|left| left.set(n));
+}
+
+#[cfg(test)]
+fn injected_alloc_failure() -> bool {
+    ALLOCS_LEFT.with(|left| match left.get() {
+        Some(0) => true,
+        Some(n) => {
+            left.set(Some(n - 1));
+            false
+        }
+        None => false,
+    })
+}
+
 impl System {
     #[inline]
     fn alloc_impl(&self, layout: Layout, zeroed: bool) -> Result<NonNull<[u8]>, AllocError> {
         if layout.size() == 0 {
             return Ok(NonNull::slice_from_raw_parts(NonNull::dangling(), 0));
         }
+        #[cfg(test)]
+        if injected_alloc_failure() {
+            return Err(AllocError);
+        }
 
         let raw_ptr: *mut u8 = if zeroed {
             unsafe { alloc_zeroed(layout) }
@@ -59,6 +153,10 @@ impl System {
         if old_layout.size() == 0 {
             return self.alloc_impl(new_layout, zeroed);
         }
+        #[cfg(test)]
+        if injected_alloc_failure() {
+            return Err(AllocError);
+        }
 
         let new_size = new_layout.size();
         let raw_ptr = realloc(ptr.as_ptr(), old_layout, new_size);
@@ -155,7 +253,7 @@ pub fn take_alloc_error_hook() -> fn(Layout) {
     if hook.is_null() {
         default_alloc_error_hook
     } else {
-        unsafe { mem::transmute(hook) }
+        unsafe { mem::transmute::<*mut (), fn(Layout)>(hook) }
     }
 }
 
@@ -164,35 +262,83 @@ fn default_alloc_error_hook(_layout: Layout) {
     // Printing might not be available in all no_std environments
 }
 
-#[cfg_attr(not(test), alloc_error_handler)]
+// With `std` linked, std's handler is the one in charge.
+#[cfg_attr(not(any(test, feature = "std")), alloc_error_handler)]
+#[cfg_attr(any(test, feature = "std"), allow(dead_code))]
 fn rust_oom(layout: Layout) -> ! {
     let hook = HOOK.load(Ordering::Acquire);
     let hook: fn(Layout) = if hook.is_null() {
         default_alloc_error_hook
     } else {
-        unsafe { mem::transmute(hook) }
+        unsafe { mem::transmute::<*mut (), fn(Layout)>(hook) }
     };
     hook(layout);
-    loop {}
+    loop {
+        core::hint::spin_loop();
+    }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 extern crate std;
 
 #[cfg(test)]
+#[allow(useless_ptr_null_checks)]
 mod tests {
-    use zen_vec::zen_vec::VecError;
 
     use super::*;
     use crate::alloc_trait::Allocator;
     use crate::zen_arc::zen_arc::ZenArc;
+    use crate::zen_arena::zen_arena::ZenArena;
+    use crate::zen_array_vec::zen_array_vec::ZenArrayVec;
+    use crate::zen_binary_heap::zen_binary_heap::{PeekMut, ZenBinaryHeap};
+    use crate::zen_bit_vec::zen_bit_set::ZenBitSet;
+    use crate::zen_bit_vec::zen_bit_vec::ZenBitVec;
     use crate::zen_box::zen_box::ZenBox;
+    use crate::zen_btree_map::zen_btree_map::ZenBTreeMap;
+    use crate::zen_btree_map::zen_btree_set::ZenBTreeSet;
     use crate::zen_cow::zen_cow::ZenCow;
+    use crate::zen_error::zen_error::{TryReserveError, ZenError};
+    use crate::zen_hash_map::sip_hasher::SipHasher24;
+    use crate::zen_hash_map::zen_hash_map::ZenHashMap;
+    use crate::zen_hash_map::zen_hash_set::ZenHashSet;
     use crate::zen_rc::zen_rc::ZenRc;
+    use crate::zen_slab::zen_slab::{SlabKey, ZenSlab};
+    use crate::zen_small_vec::zen_small_vec::ZenSmallVec;
+    use crate::zen_string::ascii_str::AsciiStr;
+    use crate::zen_string::zen_ascii_char::ZenAsciiChar;
     use crate::zen_string::zen_ascii_string::ZenAsciiString;
+    use crate::zen_string::zen_string::ZenString;
     use crate::zen_vec::raw_vec::RawVec;
     use crate::zen_vec::zen_vec::ZenVec;
+    use crate::zen_vec_deque::zen_vec_deque::ZenVecDeque;
     use core::alloc::Layout;
+    use core::cell::Cell;
+
+    // Bumps a shared counter when dropped, for checking that containers drop
+    // each element exactly once.
+    struct Counted<'a>(&'a Cell<usize>);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    // Yields the wrapped items but claims far more than can ever be
+    // reserved, which forces the failure path of a collection's `try_extend`.
+    struct Unreservable<I>(I);
+
+    impl<I: Iterator> Iterator for Unreservable<I> {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<I::Item> {
+            self.0.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (usize::MAX, None)
+        }
+    }
 
     #[test]
     fn test_basic_allocation() {
@@ -297,7 +443,7 @@ mod tests {
         assert!(vec.push(2) == Ok(()), "ZenVec push() #3 return failed");
         assert!(vec == [0, 1, 2], "ZenVec multi-push failed");
 
-        assert!(vec.remove(3) == Err(VecError::IndexOutOfBounds), "ZenVec index checking failed");
+        assert!(vec.remove(3) == Err(ZenError::IndexOutOfBounds { index: 3, len: 3 }), "ZenVec index checking failed");
         
         assert!(vec.remove(1) == Ok(1), "ZenVec remove() return failed");
         assert!(vec == [0, 2], "ZenVec remove() failed");
@@ -306,6 +452,115 @@ mod tests {
         assert!(vec == [0, 1, 2], "ZenVec insert() failed");
     }
 
+    #[test]
+    fn test_zen_vec_drain() {
+        let mut vec: ZenVec<u8> = ZenVec::new();
+        for i in 0..6 {
+            vec.push(i).unwrap();
+        }
+
+        assert!(vec.drain(4..7).is_err(), "ZenVec drain() range checking failed");
+        let (start, end) = (3, 1);
+        assert!(vec.drain(start..end).is_err(), "ZenVec drain() reversed range accepted");
+
+        let mut drain = vec.drain(1..4).unwrap();
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next_back(), Some(3));
+        assert_eq!(drain.next(), Some(1));
+        drop(drain);
+        assert!(vec == [0, 4, 5], "ZenVec drain() tail restore failed");
+
+        assert_eq!(vec.drain(..).unwrap().count(), 3);
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_zen_vec_retain_dedup() {
+        let mut vec: ZenVec<u8> = ZenVec::new();
+        for i in [1, 1, 2, 3, 3, 3, 4, 5, 5] {
+            vec.push(i).unwrap();
+        }
+
+        vec.dedup();
+        assert!(vec == [1, 2, 3, 4, 5], "ZenVec dedup() failed");
+
+        vec.retain(|&x| x % 2 == 1);
+        assert!(vec == [1, 3, 5], "ZenVec retain() failed");
+
+        vec.retain_mut(|x| {
+            *x += 1;
+            *x != 4
+        });
+        assert!(vec == [2, 6], "ZenVec retain_mut() failed");
+
+        vec.push(7).unwrap();
+        vec.dedup_by_key(|x| *x / 4);
+        assert!(vec == [2, 6], "ZenVec dedup_by_key() failed");
+    }
+
+    #[test]
+    fn test_zen_vec_extract_if() {
+        let mut vec: ZenVec<u8> = ZenVec::new();
+        for i in 0..8 {
+            vec.push(i).unwrap();
+        }
+
+        let mut evens: ZenVec<u8> = ZenVec::new();
+        for x in vec.extract_if(|x| *x % 2 == 0) {
+            evens.push(x).unwrap();
+        }
+        assert!(evens == [0, 2, 4, 6], "ZenVec extract_if() yield failed");
+        assert!(vec == [1, 3, 5, 7], "ZenVec extract_if() compaction failed");
+
+        let mut iter = vec.extract_if(|x| *x > 2);
+        assert_eq!(iter.next(), Some(3));
+        drop(iter);
+        assert!(vec == [1, 5, 7], "ZenVec extract_if() early drop failed");
+    }
+
+    #[test]
+    fn test_zen_vec_split_append_splice() {
+        let mut vec: ZenVec<u8> = try_vec![0, 1, 2, 3, 4, 5].unwrap();
+
+        assert_eq!(vec.swap_remove(6), Err(ZenError::IndexOutOfBounds { index: 6, len: 6 }));
+        assert_eq!(vec.swap_remove(0), Ok(0));
+        assert!(vec == [5, 1, 2, 3, 4], "ZenVec swap_remove() failed");
+
+        assert!(vec.try_split_off(6).is_err(), "ZenVec try_split_off() index checking failed");
+        let mut tail = vec.try_split_off(3).unwrap();
+        assert!(vec == [5, 1, 2] && tail == [3, 4], "ZenVec try_split_off() failed");
+
+        vec.try_append(&mut tail).unwrap();
+        assert!(vec == [5, 1, 2, 3, 4] && tail.is_empty(), "ZenVec try_append() failed");
+
+        assert!(vec.try_splice(2..9, [0]).is_err(), "ZenVec try_splice() range checking failed");
+        let removed = vec.try_splice(1..3, [7, 8, 9]).unwrap();
+        assert!(removed == [1, 2], "ZenVec try_splice() removed elements failed");
+        assert!(vec == [5, 7, 8, 9, 3, 4], "ZenVec try_splice() failed");
+
+        let removed = vec.try_splice(..5, []).unwrap();
+        assert_eq!(removed.len(), 5);
+        assert!(vec == [4], "ZenVec try_splice() shrinking failed");
+    }
+
+    #[test]
+    fn test_zen_vec_drain_drops() {
+        let drops = Cell::new(0);
+        let mut vec = ZenVec::new();
+        for _ in 0..5 {
+            vec.push(Counted(&drops)).unwrap();
+        }
+
+        let mut drain = vec.drain(1..4).unwrap();
+        drop(drain.next());
+        drop(drain);
+        assert_eq!(drops.get(), 3);
+        assert_eq!(vec.len(), 2);
+
+        vec.retain(|_| false);
+        assert_eq!(drops.get(), 5);
+    }
+
     #[test]
     fn test_zen_vec_iter() {
         let mut vec: ZenVec<u8> = ZenVec::new();
@@ -334,9 +589,104 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn test_zen_vec_double_ended_iter() {
+        let mut vec: ZenVec<u8> = ZenVec::new();
+        for i in 1..5 {
+            vec.push(i).unwrap();
+        }
+
+        let mut iter = vec.iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next_back(), Some(&2));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+
+        for x in vec.iter_mut().rev().take(2) {
+            *x *= 10;
+        }
+        assert!(vec == [1, 2, 30, 40], "ZenVec iter_mut().rev() failed");
+
+        let mut sum = 0;
+        for x in &vec {
+            sum += *x;
+        }
+        assert_eq!(sum, 73);
+
+        for x in &mut vec {
+            *x += 1;
+        }
+        assert!(vec == [2, 3, 31, 41], "ZenVec &mut IntoIterator failed");
+    }
+
+    #[test]
+    fn test_zen_vec_into_iter() {
+        let mut vec: ZenVec<u8> = ZenVec::new();
+        for i in 0..4 {
+            vec.push(i).unwrap();
+        }
+
+        let mut iter = vec.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.as_slice(), &[1, 2]);
+        assert_eq!(iter.rev().fold(0, |acc, x| acc * 10 + x), 21);
+
+        let drops = Cell::new(0);
+        let mut vec = ZenVec::new();
+        for _ in 0..4 {
+            vec.push(Counted(&drops)).unwrap();
+        }
+
+        let mut iter = vec.into_iter();
+        drop(iter.next());
+        assert_eq!(drops.get(), 1);
+        drop(iter);
+        assert_eq!(drops.get(), 4);
+    }
+
+    #[test]
+    fn test_try_collect() {
+        use crate::try_collect::{TryCollect, TryExtend};
+
+        let mut vec = (0u8..4).try_collect::<ZenVec<_>>().unwrap();
+        assert!(vec == [0, 1, 2, 3], "try_collect() failed");
+        assert_eq!(vec.cap(), 4);
+
+        vec.try_extend([4, 5].iter()).unwrap();
+        assert!(vec == [0, 1, 2, 3, 4, 5], "try_extend() failed");
+
+        struct Huge;
+        impl Iterator for Huge {
+            type Item = u8;
+            fn next(&mut self) -> Option<u8> {
+                Some(0)
+            }
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (usize::MAX, None)
+            }
+        }
+
+        assert!(vec.try_extend(Huge).is_err(), "try_extend() ignored allocation failure");
+        assert!(vec == [0, 1, 2, 3, 4, 5], "try_extend() did not roll back");
+        assert!(Huge.try_collect::<ZenVec<u8>>().is_err());
+
+        let string = "abc"
+            .chars()
+            .filter_map(ZenAsciiChar::new)
+            .try_collect::<ZenAsciiString>()
+            .unwrap();
+        assert_eq!(string.len(), 3);
+    }
+
     #[test]
     fn test_raw_vec_iter() {
-        let mut raw_vec: RawVec<i32> = RawVec::with_capacity(4).unwrap();
+        let raw_vec: RawVec<i32> = RawVec::with_capacity(4).unwrap();
         unsafe {
             for i in 0..4 {
                 ptr::write(raw_vec.ptr().as_ptr().add(i), i as i32);
@@ -368,7 +718,7 @@ mod tests {
             vec1.push(i).unwrap();
             vec2.push(i).unwrap();
         }
-        assert_eq!(vec1.get(0), Some(&0));
+        assert_eq!(vec1.first(), Some(&0));
         assert!(vec1 == vec2, "PartialEq #1 fail for ZenVec");
 
         vec2.pop();
@@ -376,6 +726,1864 @@ mod tests {
         assert!(vec1 == [0, 1, 2, 3]);
     }
 
+    #[test]
+    fn test_zen_small_vec_spill() {
+        let mut vec: ZenSmallVec<u32, 4> = ZenSmallVec::new();
+        for i in 0..4 {
+            vec.push(i).unwrap();
+        }
+        assert!(!vec.spilled(), "ZenSmallVec spilled before reaching N");
+        assert_eq!(vec.cap(), 4);
+
+        vec.push(4).unwrap();
+        assert!(vec.spilled(), "ZenSmallVec did not spill past N");
+        assert!(vec == [0, 1, 2, 3, 4], "ZenSmallVec spill lost elements");
+
+        assert_eq!(vec.insert(6, 0), Err(ZenError::IndexOutOfBounds { index: 6, len: 5 }));
+        vec.insert(0, 9).unwrap();
+        assert_eq!(vec.remove(1), Ok(0));
+        assert_eq!(vec.swap_remove(0), Ok(9));
+        vec.retain(|&x| x != 2);
+        assert!(vec == [4, 1, 3], "ZenSmallVec mutation failed");
+        assert_eq!(vec.iter().rev().copied().collect::<std::vec::Vec<_>>(), [3, 1, 4]);
+    }
+
+    #[test]
+    fn test_zen_small_vec_into_iter() {
+        use crate::try_collect::TryCollect;
+
+        let inline: ZenSmallVec<u8, 8> = (0..3).try_collect().unwrap();
+        assert!(!inline.spilled());
+        let mut iter = inline.into_iter();
+        assert_eq!(iter.next_back(), Some(2));
+        assert_eq!(iter.as_slice(), &[0, 1]);
+
+        let mut heap: ZenSmallVec<ZenBox<u8>, 2> = ZenSmallVec::new();
+        for i in 0..5 {
+            heap.push(ZenBox::new(i).unwrap()).unwrap();
+        }
+        let mut iter = heap.into_iter();
+        assert_eq!(iter.next().map(|b| *b), Some(0));
+        assert_eq!(iter.len(), 4);
+    }
+
+    #[test]
+    fn test_zen_small_vec_range_ops() {
+        let mut vec: ZenSmallVec<u32, 4> = ZenSmallVec::new();
+        for i in 0..6 {
+            vec.push(i).unwrap();
+        }
+        let drained: std::vec::Vec<_> = vec.drain(1..3).unwrap().collect();
+        assert_eq!(drained, [1, 2]);
+        assert!(vec == [0, 3, 4, 5], "ZenSmallVec drain() lost the tail");
+        assert!(vec.drain(..7).is_err());
+
+        let removed = vec.try_splice(1..3, [7, 7, 8]).unwrap();
+        assert!(removed == [3, 4] && !removed.spilled());
+        assert!(vec == [0, 7, 7, 8, 5], "ZenSmallVec try_splice() failed");
+        vec.dedup();
+        assert!(vec == [0, 7, 8, 5]);
+
+        let mut tail = vec.try_split_off(2).unwrap();
+        assert!(vec == [0, 7] && tail == [8, 5]);
+        assert!(vec.try_split_off(3).is_err());
+        vec.try_append(&mut tail).unwrap();
+        assert!(vec == [0, 7, 8, 5] && tail.is_empty());
+
+        let mut exact: ZenSmallVec<u32, 2> = ZenSmallVec::new();
+        exact.try_reserve_exact(5).unwrap();
+        assert!(exact.spilled() && exact.cap() == 5);
+        assert_eq!(exact.try_reserve_exact(usize::MAX), Err(TryReserveError::CapacityOverflow));
+
+        // A panicking predicate leaves the kept prefix and drops the rest once.
+        let drops = Cell::new(0);
+        let mut counted: ZenSmallVec<(u32, Counted<'_>), 2> = ZenSmallVec::new();
+        for i in 0..6 {
+            counted.push((i, Counted(&drops))).unwrap();
+        }
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            counted.retain(|(i, _)| {
+                assert!(*i != 4, "predicate failed");
+                i % 2 == 0
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+        assert!(counted.iter().map(|(i, _)| *i).eq([0, 2, 4, 5]));
+        drop(counted);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn test_zen_array_vec() {
+        use crate::zen_array_vec::zen_array_vec::{CapacityError, InsertError};
+
+        static EMPTY: ZenArrayVec<u32, 4> = ZenArrayVec::new();
+        assert!(EMPTY.is_empty());
+
+        let mut vec: ZenArrayVec<u32, 4> = ZenArrayVec::new();
+        assert_eq!(vec.extend([1, 2, 3]), Ok(()));
+        assert_eq!(vec.insert(0, 0), Ok(()));
+        assert!(vec.is_full());
+        assert_eq!(vec.push(4), Err(CapacityError(4)));
+        assert_eq!(vec.insert(1, 7).unwrap_err().element(), 7);
+        assert!(vec == [0, 1, 2, 3], "ZenArrayVec contents changed by a rejected insert");
+
+        let mut drained: ZenArrayVec<u32, 4> = ZenArrayVec::new();
+        drained.extend(vec.drain(1..3).unwrap()).unwrap();
+        assert!(drained == [1, 2] && vec == [0, 3], "ZenArrayVec drain() failed");
+        assert!(vec.drain(..5).is_err());
+
+        // A rejected extend takes back what it pushed.
+        assert_eq!(vec.extend(10..), Err(CapacityError(12)));
+        assert!(vec == [0, 3]);
+        vec.clear();
+        assert_eq!(vec.extend(10..14), Ok(()));
+        assert_eq!(vec.into_iter().next_back(), Some(13));
+
+        // A bad index is reported ahead of a full vector, and both hand the
+        // element back.
+        let mut pair: ZenArrayVec<u32, 2> = ZenArrayVec::new();
+        assert_eq!(
+            pair.insert(1, 9),
+            Err(InsertError::OutOfBounds { index: 1, len: 0, elem: 9 })
+        );
+        pair.extend([1, 2]).unwrap();
+        assert_eq!(pair.insert(2, 9), Err(InsertError::Full(9)));
+        assert_eq!(
+            pair.insert(3, 9),
+            Err(InsertError::OutOfBounds { index: 3, len: 2, elem: 9 })
+        );
+        assert!(pair == [1, 2]);
+        assert_eq!(
+            std::format!("{}", pair.insert(3, 9).unwrap_err()),
+            "insertion index 3 out of bounds for length 2"
+        );
+        assert_eq!(std::format!("{}", pair.push(3).unwrap_err()), "insufficient capacity");
+
+        assert_eq!(std::format!("{:?}", pair), "[1, 2]");
+        let mut smaller: ZenArrayVec<u32, 2> = ZenArrayVec::new();
+        smaller.extend([1, 1]).unwrap();
+        assert!(smaller < pair);
+        let mut keys = std::collections::HashSet::new();
+        assert!(keys.insert(pair) && keys.insert(smaller));
+        let mut same: ZenArrayVec<u32, 2> = ZenArrayVec::new();
+        same.extend([1, 2]).unwrap();
+        assert!(!keys.insert(same), "equal ZenArrayVecs hashed apart");
+
+        // A panicking predicate leaves the kept prefix and drops the rest once.
+        let drops = Cell::new(0);
+        let mut counted: ZenArrayVec<(u32, Counted<'_>), 6> = ZenArrayVec::new();
+        for i in 0..6 {
+            let _ = counted.push((i, Counted(&drops)));
+        }
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            counted.retain(|(i, _)| {
+                assert!(*i != 4, "predicate failed");
+                i % 2 == 0
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+        assert_eq!(counted.len(), 4);
+        assert!(counted.iter().map(|(i, _)| *i).eq([0, 2, 4, 5]));
+        drop(counted);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn test_zen_vec_deque() {
+        let mut deque: ZenVecDeque<u32> = ZenVecDeque::with_capacity(4).unwrap();
+        deque.try_push_back(2).unwrap();
+        deque.try_push_back(3).unwrap();
+        deque.try_push_front(1).unwrap();
+        deque.try_push_front(0).unwrap();
+        assert_eq!(deque.as_slices(), (&[0, 1][..], &[2, 3][..]));
+
+        /* Grow while the ring is wrapped. */
+        deque.try_push_back(4).unwrap();
+        assert!(deque == [0, 1, 2, 3, 4], "ZenVecDeque growth reordered elements");
+        assert_eq!(deque.iter().next_back(), Some(&4));
+
+        assert_eq!(deque.pop_front(), Some(0));
+        assert_eq!(deque.pop_back(), Some(4));
+        deque.try_push_front(9).unwrap();
+        assert_eq!(deque.front(), Some(&9));
+        assert_eq!(deque.back(), Some(&3));
+        assert_eq!(deque.make_contiguous(), &[9, 1, 2, 3]);
+
+        let drained: ZenVec<u32> = {
+            use crate::try_collect::TryCollect;
+            deque.drain(1..3).unwrap().try_collect().unwrap()
+        };
+        assert!(drained == [1, 2], "ZenVecDeque drain() failed");
+        assert!(deque == [9, 3], "ZenVecDeque drain() tail restore failed");
+        assert!(deque.drain(1..3).is_err());
+
+        for x in &mut deque {
+            *x += 1;
+        }
+        assert_eq!(deque.into_iter().next_back(), Some(4));
+    }
+
+    #[test]
+    fn test_zen_vec_deque_matches_std() {
+        use std::collections::VecDeque;
+
+        let mut deque: ZenVecDeque<u32> = ZenVecDeque::new();
+        let mut model: VecDeque<u32> = VecDeque::new();
+        let mut seed = 0x2545_f491_u32;
+        for i in 0..2000 {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            match seed % 6 {
+                0 | 1 => {
+                    deque.try_push_back(i).unwrap();
+                    model.push_back(i);
+                }
+                2 | 3 => {
+                    deque.try_push_front(i).unwrap();
+                    model.push_front(i);
+                }
+                4 => assert_eq!(deque.pop_front(), model.pop_front()),
+                _ => assert_eq!(deque.pop_back(), model.pop_back()),
+            }
+            if i % 97 == 0 {
+                deque.make_contiguous();
+            }
+            assert_eq!(deque.len(), model.len());
+        }
+        assert!(deque.iter().eq(model.iter()));
+        assert!(deque.iter().rev().eq(model.iter().rev()));
+    }
+
+    #[test]
+    fn test_zen_binary_heap() {
+        use core::cmp::Reverse;
+
+        let mut heap: ZenBinaryHeap<u32> = ZenBinaryHeap::new();
+        for x in [5, 1, 8, 3, 9, 2] {
+            heap.try_push(x).unwrap();
+        }
+        assert_eq!(heap.peek(), Some(&9));
+
+        if let Some(mut top) = heap.peek_mut() {
+            *top = 0;
+        }
+        assert_eq!(heap.peek(), Some(&8), "PeekMut did not sift on drop");
+        assert_eq!(PeekMut::pop(heap.peek_mut().unwrap()), 8);
+
+        let drained: std::vec::Vec<u32> = heap.drain_sorted().take(2).collect();
+        assert_eq!(drained, [5, 3]);
+        assert!(heap.is_empty(), "DrainSorted left elements behind");
+
+        let mut vec: ZenVec<Reverse<u32>> = ZenVec::new();
+        for x in [4, 7, 1, 6] {
+            vec.push(Reverse(x)).unwrap();
+        }
+        let mut min_heap = ZenBinaryHeap::from(vec);
+        assert_eq!(min_heap.pop(), Some(Reverse(1)));
+        let sorted = min_heap.into_sorted_vec();
+        assert!(sorted == [Reverse(7), Reverse(6), Reverse(4)], "into_sorted_vec() failed");
+    }
+
+    #[test]
+    fn test_zen_binary_heap_bad_ord() {
+        use core::cmp::Ordering;
+        use core::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        /* An `Ord` that answers at random must not cause a panic or lose elements. */
+        #[derive(PartialEq, Eq)]
+        struct Chaos(u32);
+        impl PartialOrd for Chaos {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Chaos {
+            fn cmp(&self, _other: &Self) -> Ordering {
+                match CALLS.fetch_add(7, AtomicOrdering::Relaxed) % 3 {
+                    0 => Ordering::Less,
+                    1 => Ordering::Equal,
+                    _ => Ordering::Greater,
+                }
+            }
+        }
+
+        let mut heap = ZenBinaryHeap::new();
+        for x in 0..100 {
+            heap.try_push(Chaos(x)).unwrap();
+        }
+        if let Some(mut top) = heap.peek_mut() {
+            top.0 += 1000;
+        }
+        let mut seen = 0;
+        while heap.pop().is_some() {
+            seen += 1;
+        }
+        assert_eq!(seen, 100);
+    }
+
+    #[test]
+    fn test_zen_hash_map() {
+        use crate::try_clone::TryClone;
+        use crate::try_collect::TryExtend;
+
+        let mut map: ZenHashMap<u32, u32> = ZenHashMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.try_insert(1, 10).unwrap(), None);
+        assert_eq!(map.try_insert(2, 20).unwrap(), None);
+        assert_eq!(map.try_insert(1, 11).unwrap(), Some(10));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&1), Some(&11));
+        *map.get_mut(&2).unwrap() += 1;
+        assert_eq!(map.remove(&2), Some(21));
+        assert_eq!(map.remove(&2), None);
+
+        *map.entry(5).unwrap().or_insert(0) += 5;
+        *map.entry(5).unwrap().or_insert(0) += 5;
+        map.entry(1).unwrap().and_modify(|v| *v = 0).or_default();
+        assert_eq!(map.get(&5), Some(&10));
+        assert_eq!(map.get(&1), Some(&0));
+
+        /* A failed reserve must leave the map untouched. */
+        assert!(map.try_reserve(usize::MAX).is_err());
+        assert!(map.try_reserve(usize::MAX / 8).is_err());
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&5), Some(&10));
+
+        // An extend that can't be reserved for adds nothing and replaces nothing.
+        assert!(map.try_extend(Unreservable([(3, 30), (1, 99), (4, 40)].into_iter())).is_err());
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&1), Some(&0));
+        assert!(!map.contains_key(&3));
+
+        let mut clone = map.try_clone().unwrap();
+        assert!(clone == map);
+        clone.retain(|k, _| *k == 5);
+        assert_eq!(clone.len(), 1);
+        let drained: std::vec::Vec<_> = map.drain().collect();
+        assert_eq!(drained.len(), 2);
+        assert!(map.is_empty());
+        assert_eq!(map.try_insert(7, 7).unwrap(), None);
+    }
+
+    #[test]
+    fn test_zen_hash_map_matches_std() {
+        use std::collections::HashMap;
+
+        /* Owned values so leaks and double drops show up. */
+        let mut map: ZenHashMap<u64, ZenVec<u64>> = ZenHashMap::new();
+        let mut model: HashMap<u64, u64> = HashMap::new();
+        let mut state = 0x9e37_79b9_u64;
+        for _ in 0..5000 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let key = (state >> 33) % 512;
+            if state & 3 == 0 {
+                assert_eq!(
+                    map.remove(&key).map(|v| v[0]),
+                    model.remove(&key)
+                );
+            } else {
+                let mut value = ZenVec::new();
+                value.push(state).unwrap();
+                let old = map.try_insert(key, value).unwrap();
+                assert_eq!(old.map(|v| v[0]), model.insert(key, state));
+            }
+            assert_eq!(map.len(), model.len());
+        }
+        assert!(map.capacity() >= map.len());
+        for (key, value) in model.iter() {
+            assert_eq!(map.get(key).map(|v| v[0]), Some(*value));
+        }
+        let mut count = 0;
+        for (key, value) in map {
+            assert_eq!(model.get(&key), Some(&value[0]));
+            count += 1;
+        }
+        assert_eq!(count, model.len());
+    }
+
+    #[test]
+    fn test_zen_hash_set() {
+        use crate::try_collect::{TryExtend, TryFromIterator};
+
+        let mut set: ZenHashSet<u32> = ZenHashSet::try_from_iter(0..10).unwrap();
+        assert!(!set.try_insert(3).unwrap());
+        assert!(set.try_insert(10).unwrap());
+        assert!(set.contains(&10));
+        assert!(set.remove(&0));
+        assert_eq!(set.take(&1), Some(1));
+        set.retain(|v| v % 2 == 0);
+        assert_eq!(set.len(), 5);
+        let small: ZenHashSet<u32> = ZenHashSet::try_from_iter([2, 4]).unwrap();
+        assert!(small.is_subset(&set));
+        assert!(set.try_extend(Unreservable([4, 20, 30].into_iter())).is_err());
+        assert!(set.len() == 5 && !set.contains(&20));
+        let mut values: std::vec::Vec<u32> = set.into_iter().collect();
+        values.sort();
+        assert_eq!(values, [2, 4, 6, 8, 10]);
+    }
+
+    #[test]
+    fn test_sip_hasher() {
+        use core::hash::Hasher;
+
+        for len in 0..64u8 {
+            let bytes: std::vec::Vec<u8> = (0..len).collect();
+            #[allow(deprecated)]
+            let mut reference = std::hash::SipHasher::new_with_keys(7, 11);
+            let mut ours = SipHasher24::new_with_keys(7, 11);
+            reference.write(&bytes);
+            ours.write(&bytes);
+            assert_eq!(ours.finish(), reference.finish());
+        }
+    }
+
+    #[test]
+    fn test_zen_btree_map_matches_std() {
+        use std::collections::BTreeMap;
+
+        let mut map: ZenBTreeMap<u32, ZenVec<u64>> = ZenBTreeMap::new();
+        let mut model: BTreeMap<u32, u64> = BTreeMap::new();
+        let mut state = 0x2545_f491_u64;
+        for round in 0..6000 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let key = ((state >> 33) % 700) as u32;
+            if round > 4000 || state & 3 == 0 {
+                assert_eq!(map.remove(&key).map(|v| v[0]), model.remove(&key));
+            } else {
+                let mut value = ZenVec::new();
+                value.push(state).unwrap();
+                let old = map.try_insert(key, value).unwrap();
+                assert_eq!(old.map(|v| v[0]), model.insert(key, state));
+            }
+            if round % 97 == 0 {
+                map.assert_valid();
+            }
+        }
+        map.assert_valid();
+        assert_eq!(map.len(), model.len());
+        assert!(map.iter().map(|(k, v)| (*k, v[0])).eq(model.iter().map(|(k, v)| (*k, *v))));
+        assert!(map.keys().rev().eq(model.keys().rev()));
+
+        for (start, end) in [(0, 700), (10, 20), (350, 351), (690, 10_000), (5, 5)] {
+            assert!(map.range(start..end).map(|(k, _)| *k).eq(model.range(start..end).map(|(k, _)| *k)));
+            assert!(map.range(start..=end).rev().map(|(k, _)| *k).eq(model.range(start..=end).rev().map(|(k, _)| *k)));
+        }
+        let (start, end) = (20, 10);
+        assert_eq!(map.range(start..end).count(), 0);
+
+        while let Some((key, value)) = map.pop_first() {
+            assert_eq!(model.pop_first(), Some((key, value[0])));
+        }
+        map.assert_valid();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_zen_btree_map_cursor() {
+        use core::ops::Bound;
+        use crate::try_collect::TryCollect;
+
+        let mut map: ZenBTreeMap<u32, u32> = (0..100).map(|x| (x * 2, x)).try_collect().unwrap();
+        let cursor = map.lower_bound(Bound::Included(&7));
+        assert_eq!(cursor.key(), Some(&8));
+        assert_eq!(cursor.peek_prev(), Some((&6, &3)));
+        let mut cursor = map.upper_bound(Bound::Excluded(&0));
+        assert_eq!(cursor.key(), None);
+        cursor.move_next();
+        assert_eq!(cursor.key(), Some(&0));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.key(), Some(&198));
+
+        let mut cursor = map.lower_bound_mut(Bound::Excluded(&50));
+        while let Some(key) = cursor.key().copied() {
+            if key % 4 == 0 {
+                assert_eq!(cursor.remove_current().map(|(k, _)| k), Some(key));
+            } else {
+                *cursor.value_mut().unwrap() += 1000;
+                cursor.move_next();
+            }
+        }
+        map.assert_valid();
+        assert_eq!(map.len(), 26 + 37);
+        assert_eq!(map.get(&54), Some(&1027));
+        assert_eq!(map.get(&52), None);
+
+        map.retain(|k, _| *k < 100);
+        map.assert_valid();
+        assert_eq!(map.last_key_value(), Some((&98, &1049)));
+    }
+
+    #[test]
+    fn test_zen_btree_map_split_append() {
+        use crate::try_collect::TryCollect;
+
+        for n in [0u32, 1, 11, 12, 100, 1000] {
+            for at in [0, 1, n / 3, n / 2, n.saturating_sub(1), n, n + 5] {
+                let mut left: ZenBTreeMap<u32, ZenBox<u32>> =
+                    (0..n).map(|x| (x, ZenBox::new(x).unwrap())).try_collect().unwrap();
+                let mut right = left.split_off(&at).unwrap();
+                left.assert_valid();
+                right.assert_valid();
+                assert!(left.keys().copied().eq(0..at.min(n)));
+                assert!(right.keys().copied().eq(at.min(n)..n));
+
+                left.append(&mut right).unwrap();
+                left.assert_valid();
+                right.assert_valid();
+                assert!(right.is_empty());
+                assert!(left.iter().all(|(k, v)| **v == *k));
+                assert_eq!(left.len(), n as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn test_zen_btree_map_extend() {
+        use crate::try_collect::{TryCollect, TryExtend};
+        use std::collections::BTreeMap;
+
+        let mut state = 0x9e37_79b9_u64;
+        for (base_len, base_step, extra_len, spread) in
+            [(1, 1, 500, 1), (11, 1, 1, 1), (200, 3, 400, 1), (1000, 1, 1000, 2), (5000, 7, 300, 97)]
+        {
+            let mut map: ZenBTreeMap<u32, ZenBox<u64>> =
+                (0..base_len).map(|x| (x * base_step, ZenBox::new(0).unwrap())).try_collect().unwrap();
+            let mut model: BTreeMap<u32, u64> = (0..base_len).map(|x| (x * base_step, 0)).collect();
+
+            let mut extra = std::vec::Vec::new();
+            for _ in 0..extra_len {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let key = ((state >> 33) as u32 % (extra_len * spread)) * 2;
+                extra.push((key, state));
+            }
+            map.try_extend(extra.iter().map(|&(k, v)| (k, ZenBox::new(v).unwrap()))).unwrap();
+            model.extend(extra.iter().copied());
+
+            map.assert_valid();
+            assert!(map.iter().map(|(k, v)| (*k, **v)).eq(model.iter().map(|(k, v)| (*k, *v))));
+        }
+
+        /* Fail each allocation of an extend and an append in turn; the maps
+        must come out untouched every time. */
+        fn pairs(map: &ZenBTreeMap<u32, u64>) -> std::vec::Vec<(u32, u64)> {
+            map.iter().map(|(k, v)| (*k, *v)).collect()
+        }
+        let mut map: ZenBTreeMap<u32, u64> = (0..300).map(|x| (x * 3, 0)).try_collect().unwrap();
+        let before = pairs(&map);
+        let extra: std::vec::Vec<(u32, u64)> = (0..300).map(|x| (x * 2, 1)).collect();
+        let mut failures = 0;
+        loop {
+            fail_allocs_after(Some(failures));
+            let result = map.try_extend(extra.iter().copied());
+            fail_allocs_after(None);
+            if result.is_ok() {
+                break;
+            }
+            map.assert_valid();
+            assert!(pairs(&map) == before, "failed try_extend() changed the map");
+            failures += 1;
+        }
+        assert!(failures > 0);
+        let mut model: BTreeMap<u32, u64> = before.iter().copied().collect();
+        model.extend(extra.iter().copied());
+        map.assert_valid();
+        let merged = pairs(&map);
+        assert!(merged.iter().copied().eq(model.into_iter()));
+
+        let mut map: ZenBTreeMap<u32, u64> = (0..300).map(|x| (x * 3, 0)).try_collect().unwrap();
+        let mut other: ZenBTreeMap<u32, u64> = extra.iter().copied().try_collect().unwrap();
+        let other_before = pairs(&other);
+        let mut failures = 0;
+        loop {
+            fail_allocs_after(Some(failures));
+            let result = map.append(&mut other);
+            fail_allocs_after(None);
+            if result.is_ok() {
+                break;
+            }
+            map.assert_valid();
+            other.assert_valid();
+            assert!(pairs(&map) == before, "failed append() changed the map");
+            assert!(pairs(&other) == other_before, "failed append() drained the other map");
+            failures += 1;
+        }
+        assert!(failures > 0);
+        assert!(other.is_empty());
+        map.assert_valid();
+        assert!(pairs(&map) == merged, "append() and try_extend() disagree");
+    }
+
+    #[test]
+    fn test_zen_btree_set() {
+        use crate::try_clone::TryClone;
+        use crate::try_collect::TryFromIterator;
+
+        let mut set = ZenBTreeSet::try_from_iter([5, 1, 9, 3, 7]).unwrap();
+        assert!(!set.try_insert(3).unwrap());
+        assert!(set.try_insert(4).unwrap());
+        assert!(set.iter().copied().eq([1, 3, 4, 5, 7, 9]));
+        assert!(set.range(3..7).copied().eq([3, 4, 5]));
+        assert_eq!(set.lower_bound(core::ops::Bound::Excluded(&5)), Some(&7));
+        assert!(set.remove(&1));
+        let high = set.split_off(&5).unwrap();
+        assert!(set.iter().copied().eq([3, 4]));
+        assert!(high.iter().copied().eq([5, 7, 9]));
+        let copy = high.try_clone().unwrap();
+        assert!(copy == high);
+        assert!(copy.into_iter().rev().eq([9, 7, 5]));
+    }
+
+    #[test]
+    fn test_intrusive_linked_list() {
+        use crate::zen_intrusive::adapter::AlreadyLinked;
+        use crate::zen_intrusive::linked_list::{LinkedList, LinkedListLink};
+        use core::pin::Pin;
+
+        struct Task {
+            id: u32,
+            link: LinkedListLink,
+        }
+        crate::intrusive_adapter!(ArcTasks = ZenArc<Task>: Task { link: LinkedListLink });
+        crate::intrusive_adapter!(BoxTasks = Pin<ZenBox<Task>>: Task { link: LinkedListLink });
+        crate::intrusive_adapter!(StaticTasks = &'static Task: Task { link: LinkedListLink });
+
+        let tasks: std::vec::Vec<ZenArc<Task>> = (0..5)
+            .map(|id| ZenArc::new(Task { id, link: LinkedListLink::new() }).unwrap())
+            .collect();
+        let mut list = LinkedList::new(ArcTasks);
+        for task in &tasks {
+            list.push_back(task.clone()).unwrap();
+        }
+        assert!(matches!(list.push_front(tasks[2].clone()), Err(AlreadyLinked(_))));
+        assert!(list.iter().map(|t| t.id).eq([0, 1, 2, 3, 4]));
+        assert!(list.iter().rev().map(|t| t.id).eq([4, 3, 2, 1, 0]));
+
+        /* Unlink from the middle given only the value's address. */
+        let removed = unsafe { list.cursor_mut_from_ptr(&*tasks[2]).remove() }.unwrap();
+        assert_eq!(removed.id, 2);
+        assert!(!removed.link.is_linked());
+        drop(removed);
+        let mut cursor = list.front_mut();
+        cursor.move_next();
+        cursor.insert_after(tasks[2].clone()).unwrap();
+        cursor.insert_before(ZenArc::new(Task { id: 9, link: LinkedListLink::new() }).unwrap()).unwrap();
+        assert!(list.iter().map(|t| t.id).eq([0, 9, 1, 2, 3, 4]));
+        assert_eq!(list.pop_back().map(|t| t.id), Some(4));
+        list.clear();
+        assert!(list.is_empty());
+        assert!(tasks.iter().all(|t| ZenArc::strong_count(t) == 1 && !t.link.is_linked()));
+
+        let mut boxed = LinkedList::new(BoxTasks);
+        for id in 0..3 {
+            boxed.push_front(ZenBox::pin(Task { id, link: LinkedListLink::new() }).unwrap()).unwrap();
+        }
+        assert!(boxed.iter().map(|t| t.id).eq([2, 1, 0]));
+        assert_eq!(boxed.pop_front().map(|t| t.id), Some(2));
+
+        /* The nodes are only touched from this thread. */
+        unsafe impl Sync for Task {}
+        static NODES: [Task; 2] = [
+            Task { id: 7, link: LinkedListLink::new() },
+            Task { id: 8, link: LinkedListLink::new() },
+        ];
+        let mut statics = LinkedList::new(StaticTasks);
+        statics.push_back(&NODES[0]).unwrap();
+        statics.push_back(&NODES[1]).unwrap();
+        assert_eq!(statics.back().get().map(|t| t.id), Some(8));
+        statics.clear();
+    }
+
+    #[test]
+    fn test_intrusive_singly_linked_list() {
+        use crate::zen_intrusive::singly_linked_list::{SinglyLinkedList, SinglyLinkedListLink};
+
+        struct Waiter {
+            id: u32,
+            link: SinglyLinkedListLink,
+        }
+        crate::intrusive_adapter!(Waiters = ZenArc<Waiter>: Waiter { link: SinglyLinkedListLink });
+
+        let waiters: std::vec::Vec<ZenArc<Waiter>> = (0..4)
+            .map(|id| ZenArc::new(Waiter { id, link: SinglyLinkedListLink::new() }).unwrap())
+            .collect();
+        let mut list = SinglyLinkedList::new(Waiters);
+        for waiter in waiters.iter().rev() {
+            list.push_front(waiter.clone()).unwrap();
+        }
+        assert!(list.iter().map(|w| w.id).eq([0, 1, 2, 3]));
+
+        let mut cursor = unsafe { list.cursor_mut_from_ptr(&*waiters[1]) };
+        assert_eq!(cursor.remove_next().map(|w| w.id), Some(2));
+        cursor.insert_after(waiters[2].clone()).unwrap();
+        cursor.move_next();
+        assert_eq!(cursor.get().map(|w| w.id), Some(2));
+        assert!(list.cursor_mut().remove_next().is_some());
+        assert!(list.iter().map(|w| w.id).eq([1, 2, 3]));
+        drop(list);
+        assert!(waiters.iter().all(|w| ZenArc::strong_count(w) == 1));
+    }
+
+    #[test]
+    fn test_intrusive_rb_tree() {
+        use crate::zen_intrusive::adapter::{KeyAdapter, Target};
+        use crate::zen_intrusive::rb_tree::{RBTree, RBTreeLink};
+        use core::ops::Bound;
+        use core::pin::Pin;
+
+        struct Timer {
+            deadline: u64,
+            link: RBTreeLink,
+        }
+        crate::intrusive_adapter!(Timers = Pin<ZenBox<Timer>>: Timer { link: RBTreeLink });
+        impl KeyAdapter for Timers {
+            type Key = u64;
+
+            fn get_key(&self, timer: &Target<Self>) -> u64 {
+                timer.deadline
+            }
+        }
+
+        let mut tree = RBTree::new(Timers);
+        let mut model: std::vec::Vec<u64> = std::vec::Vec::new();
+        let mut addresses: std::vec::Vec<*const Timer> = std::vec::Vec::new();
+        let mut state = 0x1234_5678_u64;
+        for round in 0..3000 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            if round < 2000 && state & 3 != 0 || addresses.is_empty() {
+                let deadline = (state >> 40) % 500;
+                let timer = ZenBox::pin(Timer { deadline, link: RBTreeLink::new() }).unwrap();
+                addresses.push(&*timer);
+                tree.insert(timer).unwrap();
+                model.push(deadline);
+            } else {
+                /* Cancel a timer through its address, without a lookup. */
+                let victim = addresses.swap_remove((state >> 33) as usize % addresses.len());
+                let timer = unsafe { tree.cursor_mut_from_ptr(victim).remove() }.unwrap();
+                let pos = model.iter().position(|&d| d == timer.deadline).unwrap();
+                model.swap_remove(pos);
+            }
+            if round % 50 == 0 {
+                tree.assert_valid();
+            }
+        }
+        tree.assert_valid();
+        model.sort();
+        assert!(tree.iter().map(|t| t.deadline).eq(model.iter().copied()));
+        assert!(tree.iter().rev().map(|t| t.deadline).eq(model.iter().rev().copied()));
+
+        let first_after = model.iter().copied().find(|&d| d > 250);
+        assert_eq!(tree.lower_bound(Bound::Excluded(&250)).get().map(|t| t.deadline), first_after);
+        let last_before = model.iter().copied().rev().find(|&d| d <= 100);
+        assert_eq!(tree.upper_bound(Bound::Included(&100)).get().map(|t| t.deadline), last_before);
+        if let Some(&d) = model.get(model.len() / 2) {
+            assert_eq!(tree.find(&d).get().map(|t| t.deadline), Some(d));
+        }
+
+        let mut expired = 0;
+        while tree.front().get().is_some_and(|t| t.deadline < 200) {
+            tree.pop_front();
+            expired += 1;
+        }
+        assert_eq!(expired, model.iter().filter(|&&d| d < 200).count());
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn test_zen_slab() {
+        use crate::try_clone::TryClone;
+
+        let mut slab = ZenSlab::new();
+        let a = slab.try_insert(10).unwrap();
+        let b = slab.try_insert(20).unwrap();
+        let c = slab.try_insert(30).unwrap();
+        assert_eq!(slab.len(), 3);
+        assert_eq!(slab.get(b), Some(&20));
+
+        // A removed slot is reused, and the old key no longer matches.
+        assert_eq!(slab.remove(b), Some(20));
+        assert_eq!(slab.remove(b), None);
+        let d = slab.try_insert(40).unwrap();
+        assert_eq!(d.index(), b.index());
+        assert_ne!(d.generation(), b.generation());
+        assert_eq!(slab.get(b), None);
+        assert_eq!(slab.get(d), Some(&40));
+        assert_eq!(SlabKey::from_bits(d.to_bits()), d);
+
+        let [x, y] = slab.get_disjoint_mut([a, c]).unwrap();
+        core::mem::swap(x, y);
+        assert_eq!(slab.get(a), Some(&30));
+        assert!(slab.get_disjoint_mut([a, a]).is_none());
+        assert!(slab.get_disjoint_mut([a, b]).is_none());
+
+        let e = slab.try_insert_with_key(|key| key.index() as i32).unwrap();
+        assert_eq!(slab.get(e), Some(&3));
+        *slab.get_mut(e).unwrap() += 1;
+
+        let mut pairs: std::vec::Vec<(SlabKey, i32)> = slab.iter().map(|(k, v)| (k, *v)).collect();
+        pairs.sort();
+        assert_eq!(pairs, [(a, 30), (d, 40), (c, 10), (e, 4)]);
+        assert_eq!(slab.iter().len(), 4);
+
+        let copy = slab.try_clone().unwrap();
+        assert_eq!(copy.get(d), Some(&40));
+
+        slab.retain(|_, v| *v >= 30);
+        assert_eq!(slab.len(), 2);
+        assert!(!slab.contains_key(c));
+
+        let drained: std::vec::Vec<i32> = slab.drain().map(|(_, v)| v).collect();
+        assert_eq!(drained, [30, 40]);
+        assert!(slab.is_empty());
+        assert_eq!(slab.get(a), None);
+
+        // Drained slots keep their generations.
+        let f = slab.try_insert(50).unwrap();
+        assert!(f != a && f != d);
+        assert!(slab.capacity() >= 3);
+
+        let owned: std::vec::Vec<i32> = copy.into_iter().map(|(_, v)| v).collect();
+        assert_eq!(owned, [30, 40, 10, 4]);
+
+        // A panicking constructor leaves the free slot linked for the next insert.
+        let mut slab = ZenSlab::new();
+        let g = slab.try_insert(1).unwrap();
+        slab.remove(g);
+        let capacity = slab.capacity();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            slab.try_insert_with_key(|_| -> i32 { panic!("constructor failed") })
+        }));
+        assert!(result.is_err());
+        assert!(slab.is_empty());
+        assert_eq!(slab.capacity(), capacity);
+        let h = slab.try_insert(2).unwrap();
+        assert_eq!(h.index(), g.index());
+        assert_eq!(slab.capacity(), capacity - 1);
+        slab.try_reserve(capacity + 4).unwrap();
+        assert!(slab.capacity() >= capacity + 4);
+
+        // A failed extend removes what it inserted, at every failure point,
+        // and leaves the free list in its old order.
+        use crate::try_collect::{TryExtend, TryFromIterator};
+        let mut slab: ZenSlab<u32> = ZenSlab::try_from_iter(0..8).unwrap();
+        let keys: std::vec::Vec<SlabKey> = slab.keys().collect();
+        for &key in &keys[2..5] {
+            slab.remove(key);
+        }
+        let before: std::vec::Vec<(SlabKey, u32)> = slab.iter().map(|(k, v)| (k, *v)).collect();
+        assert!(slab.try_extend(Unreservable(100..103)).is_err());
+        let mut failures = 0;
+        loop {
+            fail_allocs_after(Some(failures));
+            /* `filter` hides the length, so nothing is reserved up front. */
+            let result = slab.try_extend((100..120).filter(|_| true));
+            fail_allocs_after(None);
+            if result.is_ok() {
+                break;
+            }
+            assert!(
+                slab.iter().map(|(k, v)| (k, *v)).eq(before.iter().copied()),
+                "failed try_extend() changed the slab"
+            );
+            failures += 1;
+        }
+        assert!(failures > 1);
+        assert_eq!(slab.len(), 25);
+        let index_of = |value| slab.iter().find(|(_, v)| **v == value).unwrap().0.index();
+        assert_eq!([index_of(100), index_of(101), index_of(102)], [4, 3, 2]);
+        assert_eq!(index_of(103), 8);
+    }
+
+    #[test]
+    fn test_zen_arena() {
+        struct Node<'a, 'd> {
+            id: usize,
+            edges: Cell<Option<&'a Node<'a, 'd>>>,
+            // Counts drops from outside the arena; the nodes themselves have
+            // no destructor, so they may borrow each other.
+            _tracker: Counted<'d>,
+        }
+
+        let drops = Cell::new(0);
+        {
+            let arena = ZenArena::new();
+            let mut nodes = std::vec::Vec::new();
+            for id in 0..1000 {
+                let node = arena
+                    .try_alloc(Node {
+                        id,
+                        edges: Cell::new(None),
+                        _tracker: Counted(&drops),
+                    })
+                    .ok()
+                    .unwrap();
+                nodes.push(&*node);
+            }
+            // Tie the nodes into a cycle with plain borrows.
+            for (i, node) in nodes.iter().enumerate() {
+                node.edges.set(Some(nodes[(i + 1) % nodes.len()]));
+            }
+            assert_eq!(arena.len(), 1000);
+            assert_eq!(nodes[999].edges.get().unwrap().id, 0);
+            assert_eq!(nodes[500].edges.get().unwrap().id, 501);
+            arena.try_reserve(5000).unwrap();
+            assert_eq!(drops.get(), 0);
+        }
+        assert_eq!(drops.get(), 1000);
+
+        let units = ZenArena::new();
+        for _ in 0..10 {
+            units.try_alloc(()).unwrap();
+        }
+        assert_eq!(units.len(), 10);
+
+        struct Exhausted;
+
+        impl Allocator for Exhausted {
+            fn allocate(&self, _: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                Err(AllocError)
+            }
+            fn allocate_zeroed(&self, _: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                Err(AllocError)
+            }
+            unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+            unsafe fn grow(&self, _: NonNull<u8>, _: Layout, _: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                Err(AllocError)
+            }
+            unsafe fn grow_zeroed(&self, _: NonNull<u8>, _: Layout, _: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                Err(AllocError)
+            }
+            unsafe fn shrink(&self, _: NonNull<u8>, _: Layout, _: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                Err(AllocError)
+            }
+        }
+
+        let failing = ZenArena::new_in(Exhausted);
+        let value = std::string::String::from("kept");
+        assert_eq!(failing.try_alloc(value).unwrap_err(), "kept");
+        assert!(failing.is_empty());
+        assert!(failing.try_reserve(4).unwrap_err().layout().is_some());
+        assert_eq!(
+            ZenArena::<u64>::new().try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+    }
+
+    #[test]
+    fn test_zen_bit_vec() {
+        use crate::try_clone::TryClone;
+        use crate::try_collect::TryCollect;
+
+        let mut bits = ZenBitVec::new();
+        let mut model = std::vec::Vec::new();
+        let mut state = 1u64;
+        for _ in 0..300 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let value = state >> 63 == 1;
+            bits.try_push(value).unwrap();
+            model.push(value);
+        }
+        assert_eq!(bits.len(), 300);
+        assert!(bits.iter().eq(model.iter().copied()));
+        assert_eq!(bits.count_ones(), model.iter().filter(|&&b| b).count());
+        assert!(bits.iter_ones().eq(model.iter().enumerate().filter(|(_, &b)| b).map(|(i, _)| i)));
+        assert_eq!(bits.first_set(), model.iter().position(|&b| b));
+        assert_eq!(bits.first_clear(), model.iter().position(|&b| !b));
+        assert_eq!(bits.last_set(), model.iter().rposition(|&b| b));
+
+        bits.toggle(5).unwrap();
+        bits.set(299).unwrap();
+        bits.clear(0).unwrap();
+        assert_eq!(bits.get(5), Some(!model[5]));
+        assert_eq!(bits.get(299), Some(true));
+        assert_eq!(bits.get(0), Some(false));
+        assert_eq!(bits.get(300), None);
+        assert_eq!(bits.replace(299, false), Ok(true));
+        assert_eq!(bits.replace(299, true), Ok(false));
+
+        // Out-of-range writes are reported and change nothing.
+        let out_of_range = Err(ZenError::IndexOutOfBounds { index: 300, len: 300 });
+        let snapshot = bits.try_clone().unwrap();
+        assert_eq!(bits.set(300), out_of_range);
+        assert_eq!(bits.clear(300), out_of_range);
+        assert_eq!(bits.toggle(300), out_of_range);
+        assert_eq!(bits.replace(300, true), Err(ZenError::IndexOutOfBounds { index: 300, len: 300 }));
+        assert!(bits == snapshot);
+        assert_eq!(bits.next_set(299), Some(299));
+
+        // Growing with ones and shrinking keeps the tail of the last word clear.
+        let mut ones = ZenBitVec::try_from_elem(70, true).unwrap();
+        assert_eq!(ones.count_ones(), 70);
+        assert!(ones.all());
+        ones.try_resize(130, false).unwrap();
+        assert_eq!(ones.count_ones(), 70);
+        assert_eq!(ones.first_clear(), Some(70));
+        ones.try_resize(140, true).unwrap();
+        assert_eq!(ones.count_ones(), 80);
+        ones.truncate(65);
+        assert_eq!(ones.as_words()[1], 1);
+        ones.toggle_all();
+        assert!(!ones.any());
+        assert_eq!(ones.pop(), Some(false));
+
+        let a: ZenBitVec = [true, true, false, false].into_iter().try_collect().unwrap();
+        let b: ZenBitVec = [true, false, true, false].into_iter().try_collect().unwrap();
+        let mut union = a.try_clone().unwrap();
+        union.union_with(&b).unwrap();
+        let mut intersection = a.try_clone().unwrap();
+        intersection.intersect_with(&b).unwrap();
+        let mut difference = a.try_clone().unwrap();
+        difference.difference_with(&b).unwrap();
+        let mut symmetric = a.try_clone().unwrap();
+        symmetric.symmetric_difference_with(&b).unwrap();
+        assert!(union.iter().eq([true, true, true, false]));
+        assert!(intersection.iter().eq([true, false, false, false]));
+        assert!(difference.iter().eq([false, true, false, false]));
+        assert!(symmetric.iter().eq([false, true, true, false]));
+        assert!(a != b);
+
+        let short: ZenBitVec = [true].into_iter().try_collect().unwrap();
+        let mut unchanged = a.try_clone().unwrap();
+        assert_eq!(
+            unchanged.union_with(&short),
+            Err(ZenError::InvalidInput("bit vectors differ in length"))
+        );
+        assert!(unchanged.intersect_with(&short).is_err());
+        assert!(unchanged.difference_with(&short).is_err());
+        assert!(unchanged.symmetric_difference_with(&short).is_err());
+        assert!(unchanged == a);
+    }
+
+    #[test]
+    fn test_zen_bit_set() {
+        use crate::try_collect::{TryCollect, TryExtend};
+
+        let mut ids = ZenBitSet::new();
+        assert_eq!(ids.first_absent(), 0);
+        for id in 0..70 {
+            assert!(ids.try_insert(id).unwrap());
+        }
+        assert!(!ids.try_insert(3).unwrap());
+        assert_eq!(ids.first_absent(), 70);
+        assert!(ids.remove(10));
+        assert!(!ids.remove(10));
+        assert!(!ids.remove(1000));
+        assert_eq!(ids.first_absent(), 10);
+        assert_eq!(ids.len(), 69);
+        assert_eq!(ids.first(), Some(0));
+        assert_eq!(ids.last(), Some(69));
+
+        let evens: ZenBitSet = (0..200).step_by(2).try_collect().unwrap();
+        let small: ZenBitSet = [2, 4, 6].into_iter().try_collect().unwrap();
+        assert!(small.is_subset(&evens));
+        assert!(evens.is_superset(&small));
+        assert!(!evens.is_subset(&small));
+
+        let mut odds: ZenBitSet = (1..10).step_by(2).try_collect().unwrap();
+        assert!(odds.is_disjoint(&evens));
+        odds.try_union_with(&small).unwrap();
+        assert!(odds.iter().eq([1, 2, 3, 4, 5, 6, 7, 9]));
+        odds.difference_with(&small);
+        odds.intersect_with(&evens);
+        assert!(odds.is_empty());
+
+        // Equality ignores how many words each set has grown to.
+        let mut wide = ZenBitSet::new();
+        wide.try_insert(500).unwrap();
+        wide.remove(500);
+        wide.try_union_with(&small).unwrap();
+        assert!(wide == small);
+
+        // A failed extend clears the bits it set and drops the words it added.
+        let mut set = ZenBitSet::new();
+        set.try_insert(1).unwrap();
+        set.try_insert(100).unwrap();
+        let words = set.as_words().len();
+        let result = set.try_extend([3, 64, 100, 1000, usize::MAX]);
+        assert!(result.is_err());
+        assert!(set.iter().eq([1, 100]));
+        assert_eq!(set.as_words().len(), words);
+        assert_eq!(set.try_insert(usize::MAX), Err(TryReserveError::CapacityOverflow));
+
+        let mut bits = ZenBitVec::new();
+        bits.try_push(true).unwrap();
+        assert_eq!(bits.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+    }
+
+    #[test]
+    fn test_zero_sized_types() {
+        use core::marker::PhantomData;
+        use core::sync::atomic::AtomicUsize;
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Marker;
+
+        impl Drop for Marker {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut units = ZenVec::new();
+        assert_eq!(units.cap(), usize::MAX);
+        for _ in 0..1000 {
+            units.push(()).unwrap();
+        }
+        assert_eq!(units.len(), 1000);
+        assert_eq!(units.cap(), usize::MAX);
+        assert_eq!(units.ptr(), NonNull::<()>::dangling().as_ptr());
+        units.insert(500, ()).unwrap();
+        assert_eq!(units.remove(0).unwrap(), ());
+        assert_eq!(units.drain(10..20).unwrap().count(), 10);
+        assert_eq!(units.len(), 990);
+        assert_eq!(units.into_iter().count(), 990);
+        assert_eq!(ZenVec::<()>::with_capacity(10).unwrap().cap(), usize::MAX);
+
+        let mut markers = ZenVec::new();
+        for _ in 0..100 {
+            markers.push(Marker).unwrap();
+        }
+        drop(markers.pop());
+        assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+        drop(markers.drain(0..10).unwrap());
+        assert_eq!(DROPS.load(Ordering::SeqCst), 11);
+        let mut rest = markers.into_iter();
+        drop(rest.next());
+        drop(rest);
+        assert_eq!(DROPS.load(Ordering::SeqCst), 100);
+
+        let unit = ZenBox::new(()).unwrap();
+        assert_eq!(*unit.as_ref(), ());
+        let marker = ZenBox::new(Marker).unwrap();
+        drop(unit);
+        drop(marker);
+        assert_eq!(DROPS.load(Ordering::SeqCst), 101);
+
+        let tag: ZenRc<PhantomData<u64>> = ZenRc::new(PhantomData).unwrap();
+        let other = tag.clone();
+        assert_eq!(ZenRc::strong_count(&tag), 2);
+        drop(other);
+        assert_eq!(ZenRc::strong_count(&tag), 1);
+
+        let mut deque = ZenVecDeque::new();
+        for _ in 0..50 {
+            deque.try_push_front(Marker).unwrap();
+            deque.try_push_back(Marker).unwrap();
+        }
+        assert_eq!(deque.len(), 100);
+        drop(deque.pop_front());
+        drop(deque);
+        assert_eq!(DROPS.load(Ordering::SeqCst), 201);
+
+        let mut small: ZenSmallVec<(), 2> = ZenSmallVec::new();
+        for _ in 0..10 {
+            small.push(()).unwrap();
+        }
+        assert!(small.spilled());
+        assert_eq!(small.cap(), usize::MAX);
+    }
+
+    #[test]
+    fn test_zen_vec_try_sort() {
+        // Sorting by the first field only must keep the second in order.
+        let mut vec = ZenVec::new();
+        let mut model = std::vec::Vec::new();
+        let mut state = 7u64;
+        for i in 0..1000u32 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let key = (state >> 56) as u8;
+            vec.push((key, i)).unwrap();
+            model.push((key, i));
+        }
+        vec.try_sort_by_key(|&(key, _)| key).unwrap();
+        model.sort_by_key(|&(key, _)| key);
+        assert_eq!(vec.as_slice(), model.as_slice());
+
+        vec.try_sort_by(|a, b| b.cmp(a)).unwrap();
+        model.sort_by(|a, b| b.cmp(a));
+        assert_eq!(vec.as_slice(), model.as_slice());
+
+        let mut small = ZenVec::new();
+        for value in [5, 3, 9, 1, 3] {
+            small.push(value).unwrap();
+        }
+        small.try_sort().unwrap();
+        assert_eq!(small.as_slice(), [1, 3, 3, 5, 9]);
+
+        // A panicking comparison leaves every element in place exactly once.
+        let mut strings = ZenVec::new();
+        for i in (0..200).rev() {
+            strings.push(std::format!("{:03}", i)).unwrap();
+        }
+        let mut calls = 0;
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            strings
+                .try_sort_by(|a, b| {
+                    calls += 1;
+                    assert!(calls < 500, "comparison failed");
+                    a.cmp(b)
+                })
+                .unwrap();
+        }));
+        assert!(result.is_err());
+        let mut seen: std::vec::Vec<_> = strings.iter().cloned().collect();
+        seen.sort();
+        let expected: std::vec::Vec<_> = (0..200).map(|i| std::format!("{:03}", i)).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_standard_traits() {
+        use core::borrow::Borrow;
+        use std::collections::{BTreeSet, HashSet};
+        use std::format;
+
+        let vec = ZenVec::try_from([3, 1, 2]).unwrap();
+        assert_eq!(format!("{:?}", vec), "[3, 1, 2]");
+        assert_eq!(vec[1], 1);
+        assert_eq!(vec[1..], [1, 2]);
+        assert_eq!(AsRef::<[i32]>::as_ref(&vec), &[3, 1, 2]);
+        let copy = ZenVec::try_from(&[3, 1, 2][..]).unwrap();
+        assert!(vec == copy);
+        assert!(vec > ZenVec::try_from([3, 1]).unwrap());
+
+        // Vectors are usable as keys and can be looked up by slice.
+        let mut keys = HashSet::new();
+        keys.insert(vec);
+        assert!(keys.contains(&[3, 1, 2][..]));
+        let mut ordered = BTreeSet::new();
+        ordered.insert(ZenVec::try_from([2]).unwrap());
+        ordered.insert(ZenVec::try_from([1, 5]).unwrap());
+        assert_eq!(ordered.first().unwrap()[..], [1, 5]);
+
+        let boxed = ZenBox::new(7).unwrap();
+        assert_eq!(format!("{} {:?}", boxed, boxed), "7 7");
+        assert_eq!(format!("{:p}", boxed), format!("{:p}", &*boxed));
+        assert!(boxed < ZenBox::new(8).unwrap());
+        assert_eq!(*Borrow::<i32>::borrow(&boxed), 7);
+        assert_eq!(*ZenBox::<u8>::try_default().unwrap(), 0);
+
+        let rc = ZenRc::new(std::string::String::from("rc")).unwrap();
+        assert_eq!(format!("{}", rc), "rc");
+        assert!(rc == ZenRc::new(std::string::String::from("rc")).unwrap());
+        let arc = ZenArc::new(5).unwrap();
+        let mut arcs = HashSet::new();
+        arcs.insert(arc.clone());
+        assert!(arcs.contains(&5));
+        assert_eq!(format!("{:p}", arc), format!("{:p}", &*arc));
+        let cow = ZenCow::new(9).unwrap();
+        assert_eq!(format!("{:?}", cow), "9");
+        assert!(cow.clone() == cow);
+
+        let text = ZenAsciiString::try_from("say \"hi\"").unwrap();
+        assert_eq!(format!("{}", text), "say \"hi\"");
+        assert_eq!(format!("{:?}", text), "\"say \\\"hi\\\"\"");
+        assert_eq!(text[0], ZenAsciiChar::new('s').unwrap());
+        assert!(ZenAsciiString::try_from("abc").unwrap() < ZenAsciiString::try_from("abd").unwrap());
+        assert!(ZenAsciiString::try_from("caf\u{e9}").is_err());
+        let chars = [ZenAsciiChar::new('o').unwrap(), ZenAsciiChar::new('k').unwrap()];
+        assert_eq!(format!("{}", ZenAsciiString::try_from(chars).unwrap()), "ok");
+
+        // The other containers: `Debug`, plus `Eq`, `Ord` and `Hash` where
+        // the standard equivalent has them.
+        use crate::try_collect::TryFromIterator;
+        fn hash_of<T: core::hash::Hash>(value: &T) -> u64 {
+            use core::hash::Hasher;
+            let mut hasher = std::hash::DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let small = ZenSmallVec::<u8, 2>::try_from_iter([1, 2, 3]).unwrap();
+        let inline = ZenSmallVec::<u8, 4>::try_from_iter([1, 2, 3]).unwrap();
+        assert_eq!(format!("{:?}", small), "[1, 2, 3]");
+        assert_eq!(hash_of(&small), hash_of(&inline));
+        assert!(small < ZenSmallVec::try_from_iter([1, 3]).unwrap());
+
+        /* Same elements, wrapped differently. */
+        let mut wrapped: ZenVecDeque<u8> = ZenVecDeque::with_capacity(4).unwrap();
+        for x in [3, 2, 1] {
+            wrapped.try_push_front(x).unwrap();
+        }
+        let straight = ZenVecDeque::try_from_iter([1, 2, 3]).unwrap();
+        assert_eq!(format!("{:?}", wrapped), "[1, 2, 3]");
+        assert!(wrapped == straight && wrapped.cmp(&straight).is_eq());
+        assert_eq!(hash_of(&wrapped), hash_of(&straight));
+
+        let heap = ZenBinaryHeap::try_from_iter([1, 3, 2]).unwrap();
+        assert_eq!(format!("{:?}", heap), "[3, 1, 2]");
+
+        let mut map: ZenHashMap<u8, char> = ZenHashMap::new();
+        map.try_insert(1, 'a').unwrap();
+        assert_eq!(format!("{:?}", map), "{1: 'a'}");
+        let mut set: ZenHashSet<u8> = ZenHashSet::new();
+        set.try_insert(1).unwrap();
+        assert_eq!(format!("{:?}", set), "{1}");
+
+        let tree = ZenBTreeMap::try_from_iter([(2, 'b'), (1, 'a')]).unwrap();
+        assert_eq!(format!("{:?}", tree), "{1: 'a', 2: 'b'}");
+        assert!(tree > ZenBTreeMap::try_from_iter([(1, 'a')]).unwrap());
+        assert_eq!(hash_of(&tree), hash_of(&ZenBTreeMap::try_from_iter([(1, 'a'), (2, 'b')]).unwrap()));
+        let tree_set = ZenBTreeSet::try_from_iter([2, 1]).unwrap();
+        assert_eq!(format!("{:?}", tree_set), "{1, 2}");
+        assert!(tree_set < ZenBTreeSet::try_from_iter([1, 3]).unwrap());
+        let mut sets = HashSet::new();
+        assert!(sets.insert(tree_set));
+        assert!(!sets.insert(ZenBTreeSet::try_from_iter([1, 2]).unwrap()));
+
+        let mut slab = ZenSlab::new();
+        let key = slab.try_insert('x').unwrap();
+        assert_eq!(format!("{:?}", slab), format!("{{{:?}: 'x'}}", key));
+        let arena: ZenArena<u8> = ZenArena::new();
+        arena.try_alloc(1).unwrap();
+        assert_eq!(format!("{:?}", arena), "ZenArena { len: 1, .. }");
+
+        let bits = ZenBitVec::try_from_iter([true, false]).unwrap();
+        assert_eq!(format!("{:?}", bits), "[true, false]");
+        assert!(bits > ZenBitVec::try_from_iter([false, true, true]).unwrap());
+        assert_eq!(hash_of(&bits), hash_of(&ZenBitVec::try_from_iter([true, false]).unwrap()));
+        /* Equal sets hash alike however many words they hold. */
+        let mut wide = ZenBitSet::with_capacity(1000).unwrap();
+        wide.try_insert(3).unwrap();
+        let narrow = ZenBitSet::try_from_iter([3]).unwrap();
+        assert!(wide == narrow);
+        assert_eq!(hash_of(&wide), hash_of(&narrow));
+        assert_eq!(format!("{:?}", narrow), "{3}");
+        assert!(narrow > ZenBitSet::try_from_iter([1, 9]).unwrap());
+    }
+
+    #[test]
+    fn test_zen_error() {
+        use core::alloc::AllocError;
+        use core::fmt::Write;
+
+        // Formats into a fixed buffer so the tests need no allocator.
+        struct Buf {
+            bytes: [u8; 96],
+            len: usize,
+        }
+        impl Write for Buf {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                let end = self.len + s.len();
+                self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+                self.len = end;
+                Ok(())
+            }
+        }
+        fn display(err: &dyn core::fmt::Display) -> Buf {
+            let mut buf = Buf { bytes: [0; 96], len: 0 };
+            write!(buf, "{}", err).unwrap();
+            buf
+        }
+        fn text(buf: &Buf) -> &str {
+            core::str::from_utf8(&buf.bytes[..buf.len]).unwrap()
+        }
+
+        // A layout that can't be expressed is an overflow, not an allocator failure.
+        let overflow = ZenVec::<u64>::with_capacity(usize::MAX).err();
+        assert_eq!(overflow, Some(TryReserveError::CapacityOverflow));
+        assert_eq!(overflow.unwrap().layout(), None);
+        let mut vec: ZenVec<u64> = ZenVec::new();
+        assert_eq!(vec.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+        vec.push(1).unwrap();
+        assert_eq!(vec.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+        // The other containers report overflow the same way.
+        let overflow = Err(TryReserveError::CapacityOverflow);
+        assert_eq!(ZenVecDeque::<u64>::with_capacity(usize::MAX).map(|_| ()), overflow);
+        assert_eq!(ZenSmallVec::<u64, 4>::with_capacity(usize::MAX).map(|_| ()), overflow);
+        assert_eq!(ZenBinaryHeap::<u64>::with_capacity(usize::MAX).map(|_| ()), overflow);
+        assert_eq!(ZenHashMap::<u64, u64>::with_capacity(usize::MAX).map(|_| ()), overflow);
+        assert_eq!(ZenHashSet::<u64>::new().try_reserve(usize::MAX), overflow);
+
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let refused = TryReserveError::AllocError { layout };
+        assert_eq!(refused.layout(), Some(layout));
+        assert_eq!(
+            text(&display(&refused)),
+            "allocator failed to provide 64 bytes aligned to 8"
+        );
+        assert_eq!(
+            text(&display(&TryReserveError::CapacityOverflow)),
+            "requested capacity exceeds the maximum size"
+        );
+
+        let err: ZenError = refused.into();
+        assert!(err.is_alloc_failure());
+        assert_eq!(err, ZenError::TryReserve(refused));
+        assert_eq!(AllocError::from(err), AllocError);
+        assert_eq!(AllocError::from(refused), AllocError);
+
+        assert_eq!(vec.remove(4), Err(ZenError::IndexOutOfBounds { index: 4, len: 1 }));
+        let err = vec.insert(2, 0).unwrap_err();
+        assert!(!err.is_alloc_failure());
+        assert_eq!(text(&display(&err)), "index 2 out of bounds for length 1");
+
+        vec.push(2).unwrap();
+        assert_eq!(
+            vec.try_split_off(3).err(),
+            Some(ZenError::IndexOutOfBounds { index: 3, len: 2 })
+        );
+        assert_eq!(
+            vec.drain(0..3).err(),
+            Some(ZenError::IndexOutOfBounds { index: 3, len: 2 })
+        );
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = vec.drain(2..1).err();
+        assert_eq!(
+            reversed,
+            Some(ZenError::IndexOutOfBounds { index: 2, len: 1 })
+        );
+        assert!(vec == [1, 2], "failed ZenVec operations changed the vector");
+
+        let err = ZenAsciiString::from_str("caf\u{e9}").unwrap_err();
+        assert!(matches!(err, ZenError::NotAscii(ascii) if ascii.valid_up_to() == 3));
+        assert_eq!(text(&display(&err)), "byte at index 3 is not ASCII");
+    }
+
+    #[test]
+    fn test_try_macros() {
+        type Fixtures = (ZenVec<u32>, ZenVec<ZenVec<u8>>, ZenBox<u64>);
+        fn build() -> Result<Fixtures, ZenError> {
+            let list = try_vec![1, 2, 3,]?;
+            let nested = try_vec![try_vec![7u8; 2]?, try_vec![7, 7]?, try_vec![7; 2]?]?;
+            let boxed = try_box!(40 + 2)?;
+            Ok((list, nested, boxed))
+        }
+
+        let (list, nested, boxed) = build().unwrap();
+        assert!(list == [1, 2, 3], "try_vec! list failed");
+        assert_eq!(list.cap(), 3, "try_vec! did not reserve exactly");
+        assert_eq!(nested.len(), 3);
+        assert!(nested.iter().all(|inner| *inner == [7, 7]), "try_vec! repeat failed");
+        assert_eq!(*boxed, 42);
+
+        let empty: ZenVec<u8> = try_vec![].unwrap();
+        assert!(empty.is_empty());
+        let none: ZenVec<u8> = try_vec![1; 0].unwrap();
+        assert!(none.is_empty());
+        let units: ZenVec<()> = try_vec![(); 1000].unwrap();
+        assert_eq!(units.len(), 1000);
+
+        /* Each listed element is evaluated exactly once, in order. */
+        let mut calls = 0;
+        let mut next = || {
+            calls += 1;
+            calls
+        };
+        let counted = try_vec![next(), next(), next()].unwrap();
+        assert!(counted == [1, 2, 3], "try_vec! evaluated out of order");
+
+        let too_many = try_vec![0u64; usize::MAX].err();
+        assert_eq!(too_many, Some(ZenError::TryReserve(TryReserveError::CapacityOverflow)));
+
+        // Non-ASCII literals are rejected at compile time.
+        let ascii = try_ascii!("hello").unwrap();
+        assert_eq!(ascii.len(), 5);
+        assert!(ascii == ZenAsciiString::from_str("hello").unwrap());
+
+        let large = try_box!([0u8; 4096]).unwrap();
+        assert!(large.iter().all(|&b| b == 0));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use crate::zen_serde::zen_serde::{MaxPrealloc, DEFAULT_MAX_PREALLOC_BYTES};
+        use serde::de::value::{Error as ValueError, SeqDeserializer};
+        use serde::de::DeserializeSeed;
+        use serde::Deserialize;
+        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+        let vec: ZenVec<u16> = try_vec![1, 2, 3].unwrap();
+        assert_tokens(
+            &vec,
+            &[
+                Token::Seq { len: Some(3) },
+                Token::U16(1),
+                Token::U16(2),
+                Token::U16(3),
+                Token::SeqEnd,
+            ],
+        );
+        assert_tokens(&ZenBox::new(7u8).unwrap(), &[Token::U8(7)]);
+        assert_tokens(&ZenRc::new(true).unwrap(), &[Token::Bool(true)]);
+        assert_tokens(&ZenArc::new(-4i32).unwrap(), &[Token::I32(-4)]);
+        assert_tokens(&try_ascii!("zen").unwrap(), &[Token::Str("zen")]);
+        assert_tokens(&ZenString::from_str("z\u{e9}n").unwrap(), &[Token::Str("z\u{e9}n")]);
+        assert_de_tokens_error::<ZenAsciiString>(
+            &[Token::Str("z\u{e9}n")],
+            "invalid value: string \"z\u{e9}n\", expected an ASCII string",
+        );
+
+        let mut map: ZenHashMap<u32, ZenVec<u8>> = ZenHashMap::new();
+        map.try_insert(1, try_vec![1].unwrap()).unwrap();
+        map.try_insert(2, try_vec![2, 2].unwrap()).unwrap();
+        let json = serde_json::to_string(&map).unwrap();
+        let back: ZenHashMap<u32, ZenVec<u8>> = serde_json::from_str(&json).unwrap();
+        assert!(back == map, "ZenHashMap serde round trip failed");
+
+        let mut tree: ZenBTreeMap<i8, bool> = ZenBTreeMap::new();
+        tree.try_insert(-1, true).unwrap();
+        tree.try_insert(5, false).unwrap();
+        assert_eq!(serde_json::to_string(&tree).unwrap(), r#"{"-1":true,"5":false}"#);
+        let back: ZenBTreeMap<i8, bool> = serde_json::from_str(r#"{"5":false,"-1":true}"#).unwrap();
+        assert_eq!(back.iter().collect::<std::vec::Vec<_>>(), [(&-1, &true), (&5, &false)]);
+
+        let set: ZenBTreeSet<u8> = serde_json::from_str("[3,1,2,1]").unwrap();
+        assert_eq!(serde_json::to_string(&set).unwrap(), "[1,2,3]");
+        let set: ZenHashSet<u8> = serde_json::from_str("[3,1,2,1]").unwrap();
+        assert_eq!(set.len(), 3);
+
+        // A length prefix that lies about the element count.
+        struct Lying(core::ops::Range<u64>);
+        impl Iterator for Lying {
+            type Item = u64;
+            fn next(&mut self) -> Option<u64> {
+                self.0.next()
+            }
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (usize::MAX, Some(usize::MAX))
+            }
+        }
+        fn hostile() -> SeqDeserializer<Lying, ValueError> {
+            SeqDeserializer::new(Lying(0..3))
+        }
+
+        let vec = ZenVec::<u64>::deserialize(hostile()).unwrap();
+        assert!(vec == [0, 1, 2]);
+        assert_eq!(vec.cap(), DEFAULT_MAX_PREALLOC_BYTES / 8);
+
+        let vec = MaxPrealloc::<ZenVec<u64>>::new(16).deserialize(hostile()).unwrap();
+        assert!(vec == [0, 1, 2]);
+        assert!(vec.cap() < 8, "prealloc limit ignored");
+        let set = MaxPrealloc::<ZenHashSet<u64>>::new(16).deserialize(hostile()).unwrap();
+        assert!(set.len() == 3 && set.capacity() < 8);
+
+        /* With no limit the reservation can't be satisfied, which must be an error. */
+        let err = MaxPrealloc::<ZenVec<u64>>::new(usize::MAX).deserialize(hostile()).unwrap_err();
+        assert_eq!(
+            std::string::ToString::to_string(&err),
+            "requested capacity exceeds the maximum size"
+        );
+        /* The limit only applied to that call. */
+        assert_eq!(ZenVec::<u64>::deserialize(hostile()).unwrap().cap(), DEFAULT_MAX_PREALLOC_BYTES / 8);
+    }
+
+    #[test]
+    fn test_zen_string() {
+        use crate::try_clone::TryClone;
+        use crate::try_collect::{TryCollect, TryExtend};
+
+        let mut s = ZenString::new();
+        assert!(s.is_empty());
+        s.try_push_str("h\u{e9}llo").unwrap();
+        s.try_push(' ').unwrap();
+        s.try_push('\u{1f980}').unwrap();
+        assert_eq!(s, "h\u{e9}llo \u{1f980}");
+        assert_eq!(s.len(), 11);
+        assert_eq!(s.chars().count(), 7);
+        assert_eq!(s.bytes().nth(1), Some(0xc3));
+        assert!(s.starts_with("h\u{e9}"), "ZenString does not deref to str");
+
+        assert_eq!(s.pop(), Some('\u{1f980}'));
+        assert_eq!(s.insert(2, 'x'), Err(ZenError::InvalidInput("index is not on a char boundary")));
+        assert_eq!(s.insert(20, 'x'), Err(ZenError::IndexOutOfBounds { index: 20, len: 7 }));
+        s.insert(0, '\u{bf}').unwrap();
+        s.insert_str(s.len(), "!?").unwrap();
+        assert_eq!(s, "\u{bf}h\u{e9}llo !?");
+        assert_eq!(s.remove(3), Ok('\u{e9}'));
+        assert_eq!(s.remove(0), Ok('\u{bf}'));
+        assert!(s.remove(s.len()).is_err());
+        assert_eq!(s, "hllo !?");
+
+        s.retain(|c| c != 'l');
+        assert_eq!(s, "ho !?");
+        let mut mixed = ZenString::from_str("a\u{e9}b\u{1f980}c\u{e9}").unwrap();
+        mixed.retain(|c| c != '\u{e9}');
+        assert_eq!(mixed, "ab\u{1f980}c");
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mixed.retain(|c| {
+                assert!(c != 'c', "predicate failed");
+                c != 'a'
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(mixed, "b\u{1f980}", "a panicking retain left the string inconsistent");
+        s.truncate(2);
+        assert_eq!(s, "ho");
+        s.truncate(10);
+        assert_eq!(s, "ho");
+
+        let mut s = ZenString::from_str("a\u{3b1}b\u{3b2}c").unwrap();
+        assert!(s.drain(2..4).is_err(), "drain split a char");
+        let drained: ZenString = s.drain(1..6).unwrap().try_collect().unwrap();
+        assert_eq!(drained, "\u{3b1}b\u{3b2}");
+        assert_eq!(s, "ac");
+        let mut s = ZenString::from_str("xyz").unwrap();
+        let mut drain = s.drain(..2).unwrap();
+        assert_eq!(drain.next_back(), Some('y'));
+        drop(drain);
+        assert_eq!(s, "z");
+
+        let ascii = ZenAsciiString::from_str("plain").unwrap();
+        let ptr = ascii.as_slice().as_ptr() as *const u8;
+        let utf8 = ZenString::from(ascii);
+        assert_eq!(utf8.as_ptr(), ptr, "ASCII conversion copied the buffer");
+        assert_eq!(utf8, "plain");
+        let back = ZenAsciiString::try_from(utf8).unwrap();
+        assert!(back == ZenAsciiString::from_str("plain").unwrap());
+        let not_ascii = ZenString::from_str("caf\u{e9}").unwrap();
+        let err = ZenAsciiString::try_from(not_ascii).unwrap_err();
+        assert_eq!(err.ascii_error().valid_up_to(), 3);
+        assert_eq!(err.into_string(), "caf\u{e9}", "rejected string was not handed back");
+
+        let bytes = try_vec![b'o', 0xffu8, 0xfe].unwrap();
+        let err = ZenString::from_utf8(bytes).unwrap_err();
+        assert_eq!(err.valid_up_to(), 1);
+        assert_eq!(err.as_bytes(), [b'o', 0xff, 0xfe]);
+        assert!(err.into_bytes() == [b'o', 0xff, 0xfe]);
+        let bytes = try_vec![b'o', b'k'].unwrap();
+        assert_eq!(ZenString::from_utf8(bytes).unwrap(), "ok");
+
+        let mut joined = ZenString::new();
+        joined.try_extend(["ab", "", "cd"]).unwrap();
+        joined.try_extend("ef".chars()).unwrap();
+        assert_eq!(joined, "abcdef");
+        assert!(joined.try_clone().unwrap() == joined);
+    }
+
+    #[test]
+    fn test_try_format() {
+        use crate::zen_string::try_to_string::TryToString;
+        use core::fmt::Write;
+
+        let name = "z\u{e9}n";
+        let line = try_format!("{}: {:>4} [{:?}]", name, 42, Some(1.5)).unwrap();
+        assert_eq!(line, "z\u{e9}n:   42 [Some(1.5)]");
+        assert_eq!(try_format!("static").unwrap(), "static");
+        assert!(try_format!("").unwrap().is_empty());
+
+        assert_eq!(17u8.try_to_string().unwrap(), "17");
+        assert_eq!("text".try_to_string().unwrap(), "text");
+        let err = ZenError::IndexOutOfBounds { index: 1, len: 0 };
+        assert_eq!(err.try_to_string().unwrap(), "index 1 out of bounds for length 0");
+
+        let mut s = ZenString::from_str("n=").unwrap();
+        write!(s, "{}", -3).unwrap();
+        s.write_char('!').unwrap();
+        s.try_write_fmt(format_args!(" {:#x}", 255)).unwrap();
+        assert_eq!(s, "n=-3! 0xff");
+
+        let mut ascii = ZenAsciiString::new();
+        let two = "two";
+        write!(ascii, "{}-{}", 1, two).unwrap();
+        assert!(ascii == ZenAsciiString::from_str("1-two").unwrap());
+        assert!(write!(ascii, "{}", name).is_err(), "non-ASCII text written");
+        assert!(ascii == ZenAsciiString::from_str("1-two").unwrap());
+
+        // Writes some ASCII, then text that fails, and hides the failure.
+        struct Swallowing;
+        impl core::fmt::Display for Swallowing {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                let _ = f.write_str("ab");
+                let _ = f.write_str("\u{e9}");
+                let _ = f.write_str("cd");
+                Ok(())
+            }
+        }
+        // Writes some text, then fails without the writer failing.
+        struct Failing;
+        impl core::fmt::Display for Failing {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("partial")?;
+                Err(core::fmt::Error)
+            }
+        }
+
+        let err = ascii.try_write_fmt(format_args!("{}{}", 3, name)).unwrap_err();
+        assert!(matches!(err, ZenError::NotAscii(e) if e.valid_up_to() == 1));
+        assert!(ascii == ZenAsciiString::from_str("1-two").unwrap(), "earlier pieces kept");
+        assert!(ascii.try_write_fmt(format_args!("{}", Swallowing)).is_err(), "swallowed error lost");
+        assert!(ascii == ZenAsciiString::from_str("1-two").unwrap());
+        ascii.try_write_fmt(format_args!("={}", 3)).unwrap();
+        assert!(ascii == ZenAsciiString::from_str("1-two=3").unwrap());
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            s.try_write_fmt(format_args!("{}", Failing))
+        }));
+        assert!(result.is_err(), "a failing formatting impl did not panic");
+        assert_eq!(s, "n=-3! 0xff");
+    }
+
+    #[test]
+    fn test_zen_ascii_string_api() {
+        let mut s = ZenAsciiString::from_bytes(b"  Hello, World  ").unwrap();
+        let err = ZenAsciiString::from_bytes(b"ok\xffno").unwrap_err();
+        assert!(matches!(err, ZenError::NotAscii(ascii) if ascii.valid_up_to() == 2));
+
+        assert_eq!(s.as_str().as_ptr(), s.as_slice().as_ptr() as *const u8);
+        assert_eq!(s.as_bytes(), b"  Hello, World  ");
+        assert_eq!(s.trim(), "Hello, World");
+        assert_eq!(s.trim_start(), "Hello, World  ");
+        assert_eq!(s.trim_end(), "  Hello, World");
+
+        s.try_push(ZenAsciiChar::new('!').unwrap()).unwrap();
+        s.push(ZenAsciiChar::new('?').unwrap()).unwrap();
+        let tail = try_ascii!("<>").unwrap();
+        s.try_push_str(&tail).unwrap();
+        assert_eq!(s, "  Hello, World  !?<>");
+        assert_eq!(s.pop(), Some(ZenAsciiChar::new('>').unwrap()));
+        s.truncate(14);
+        assert_eq!(s, "  Hello, World");
+        assert!("  Hello, World" == s, "str == ZenAsciiString failed");
+
+        assert_eq!(s.find("o"), Some(6));
+        assert_eq!(s.rfind("o"), Some(10));
+        assert_eq!(s.find("xyz"), None);
+        assert!(s.contains("lo, W") && s.ends_with("ld") && s.starts_with("  H"));
+
+        let upper = s.to_ascii_uppercase().unwrap();
+        assert_eq!(upper, "  HELLO, WORLD");
+        assert!(upper.eq_ignore_ascii_case("  hello, world"));
+        s.make_ascii_lowercase();
+        assert_eq!(s, "  hello, world");
+
+        let csv = try_ascii!("a,,b,c").unwrap();
+        let mut parts = csv.split(ZenAsciiChar::new(',').unwrap());
+        assert_eq!(parts.next().unwrap(), "a");
+        assert_eq!(parts.next().unwrap(), "");
+        assert_eq!(parts.next_back().unwrap(), "c");
+        assert_eq!(parts.next().unwrap(), "b");
+        assert!(parts.next().is_none());
+        let words = try_ascii!(" one\ttwo  three\n").unwrap();
+        assert_eq!(words.split_whitespace().count(), 3);
+        assert_eq!(words.split_whitespace().next_back().unwrap(), "three");
+
+        let dash = try_ascii!("-").unwrap();
+        assert_eq!(csv.replace(",", &dash).unwrap(), "a--b-c");
+        assert_eq!(csv.replace(",,", &dash).unwrap(), "a-b,c");
+        assert_eq!(csv.replace("x", &dash).unwrap(), "a,,b,c");
+
+        let quoted = try_ascii!("it's \"q\"").unwrap();
+        assert_eq!(try_format!("{}", quoted).unwrap(), "it's \"q\"");
+        assert_eq!(try_format!("{:?}", quoted).unwrap(), "\"it's \\\"q\\\"\"");
+    }
+
+    #[test]
+    fn test_ascii_str() {
+        const GREETING: &AsciiStr = AsciiStr::new("hello, world");
+        const EMPTY: &AsciiStr = AsciiStr::new("");
+        assert_eq!(GREETING.len(), 12);
+        assert!(EMPTY.is_empty());
+        assert_eq!(<&AsciiStr>::default(), "");
+
+        let word = AsciiStr::from_str("ascii").unwrap();
+        assert_eq!(word.as_str().as_ptr(), "ascii".as_ptr());
+        assert_eq!(AsciiStr::from_str("na\u{ef}ve").unwrap_err().valid_up_to(), 2);
+        assert_eq!(AsciiStr::from_bytes(b"\x80").unwrap_err().valid_up_to(), 0);
+        let from_bytes: &AsciiStr = b"bytes"[..].try_into().unwrap();
+        assert_eq!(from_bytes, "bytes");
+        let as_str: &str = from_bytes.into();
+        assert_eq!(as_str, "bytes");
+
+        assert_eq!(&GREETING[..5], "hello");
+        assert_eq!(&GREETING[7..], "world");
+        assert_eq!(GREETING.get(7..12).unwrap(), "world");
+        assert!(GREETING.get(7..13).is_none());
+        let (left, right) = GREETING.split_at(5);
+        assert_eq!((left.as_str(), right.as_str()), ("hello", ", world"));
+        assert_eq!(GREETING.first(), ZenAsciiChar::new('h'));
+        assert_eq!(GREETING.last(), ZenAsciiChar::new('d'));
+
+        let mut chars = GREETING.chars();
+        assert_eq!(chars.len(), 12);
+        assert_eq!(chars.next(), ZenAsciiChar::new('h'));
+        assert_eq!(chars.next_back(), ZenAsciiChar::new('d'));
+        assert_eq!(chars.as_ascii_str(), "ello, worl");
+        assert_eq!(GREETING.bytes().filter(|&b| b == b'o').count(), 2);
+        let mut vowels = 0;
+        for c in word {
+            if "aeiou".contains(c.as_char()) {
+                vowels += 1;
+            }
+        }
+        assert_eq!(vowels, 3);
+
+        let mut bytes = *b"mixed Case";
+        let text = AsciiStr::from_bytes_mut(&mut bytes).unwrap();
+        text[..5].make_ascii_uppercase();
+        assert_eq!(text, "MIXED Case");
+        for c in text.chars_mut() {
+            if c.as_char() == ' ' {
+                *c = ZenAsciiChar::new('_').unwrap();
+            }
+        }
+        assert_eq!(&bytes, b"MIXED_Case");
+
+        let mut owned = ZenAsciiString::try_from(GREETING).unwrap();
+        assert_eq!(owned, GREETING);
+        let borrowed: &AsciiStr = &owned;
+        assert_eq!(borrowed.as_str(), "hello, world");
+        owned.get_mut(..1).unwrap().make_ascii_uppercase();
+        assert_eq!(owned, "Hello, world");
+        assert_eq!(try_ascii!("lit").unwrap(), "lit");
+    }
+
+    #[test]
+    fn test_zen_ascii_char() {
+        const A: ZenAsciiChar = match ZenAsciiChar::from_u8(b'A') {
+            Some(c) => c,
+            None => panic!(),
+        };
+        const Z: ZenAsciiChar = unsafe { ZenAsciiChar::from_u8_unchecked(b'z') };
+        assert_eq!(A, 'A');
+        assert_eq!(Z, b'z');
+        assert_eq!(ZenAsciiChar::try_from(b'~'), Ok(ZenAsciiChar::new('~').unwrap()));
+        assert_eq!(ZenAsciiChar::try_from(0x80u8).unwrap_err().valid_up_to(), 0);
+        assert!(ZenAsciiChar::try_from('\u{e9}').is_err());
+        assert_eq!(ZenAsciiChar::try_from('\n'), Ok(ZenAsciiChar::LF));
+        assert_eq!(u8::from(ZenAsciiChar::DEL), 0x7f);
+        assert_eq!(char::from(ZenAsciiChar::SPACE), ' ');
+        assert_eq!(ZenAsciiChar::default(), ZenAsciiChar::NUL);
+
+        assert!(A.is_alphabetic() && A.is_alphanumeric() && A.is_uppercase() && A.is_hexdigit());
+        assert!(!Z.is_hexdigit() && Z.is_lowercase());
+        let seven = ZenAsciiChar::new('7').unwrap();
+        assert!(seven.is_digit() && !seven.is_alphabetic());
+        assert_eq!(seven.to_hex_digit(), Some(7));
+        assert_eq!(ZenAsciiChar::new('f').unwrap().to_hex_digit(), Some(15));
+        assert_eq!(Z.to_hex_digit(), None);
+        assert!(ZenAsciiChar::TAB.is_whitespace() && ZenAsciiChar::TAB.is_control());
+        assert!(!ZenAsciiChar::VT.is_whitespace());
+        assert!(ZenAsciiChar::SPACE.is_printable() && !ZenAsciiChar::SPACE.is_graphic());
+        assert!(ZenAsciiChar::new('!').unwrap().is_punctuation());
+        assert!(!ZenAsciiChar::ESC.is_printable());
+
+        assert_eq!(A.to_lowercase(), 'a');
+        assert_eq!(Z.to_uppercase(), 'Z');
+        assert_eq!(seven.to_uppercase(), seven);
+        let mut c = Z;
+        c.make_uppercase();
+        assert!(c.eq_ignore_case(Z) && c != Z);
+        c.make_lowercase();
+        assert_eq!(c, Z);
+
+        let mut s = ZenAsciiString::new();
+        s.try_push(ZenAsciiChar::try_from('x').unwrap()).unwrap();
+        s.try_push(ZenAsciiChar::CR).unwrap();
+        assert_eq!(s.pop(), Some(ZenAsciiChar::CR));
+        assert_eq!(s.pop(), Some(ZenAsciiChar::new('x').unwrap()));
+        assert_eq!(s.pop(), None);
+        assert_eq!(try_format!("{:?}{}", ZenAsciiChar::TAB, A).unwrap(), "'\\t'A");
+    }
+
     #[test]
     fn test_box_allocation() {
         let value = ZenBox::new(42).unwrap();
@@ -442,9 +2650,45 @@ mod tests {
         let mut cow2 = cow1.clone();
         assert_eq!(*cow2.as_ref(), 42);
 
-        *cow2.get_mut() = 100;
+        *cow2.get_mut().unwrap() = 100;
         assert_eq!(*cow2.as_ref(), 100);
         assert_eq!(*cow1.as_ref(), 42); // Ensure cow1 is unchanged
+
+        // Clone-only values work too; `try_get_mut` needs `TryClone`.
+        let words = ZenCow::new(std::string::String::from("shared")).unwrap();
+        let mut mine = words.clone();
+        mine.get_mut().unwrap().push('!');
+        assert_eq!(words.as_ref(), "shared");
+        assert_eq!(mine.as_ref(), "shared!");
+
+        let mut cow3 = cow1.clone();
+        *cow3.try_get_mut().unwrap() += 1;
+        assert_eq!(*cow3.as_ref(), 43);
+        assert_eq!(*cow1.as_ref(), 42);
+    }
+
+    #[test]
+    fn test_try_clone() {
+        use crate::try_clone::TryClone;
+
+        let mut inner: ZenVec<u8> = ZenVec::new();
+        inner.push(1).unwrap();
+        inner.push(2).unwrap();
+        let mut nested: ZenVec<ZenBox<ZenVec<u8>>> = ZenVec::new();
+        nested.push(ZenBox::new(inner).unwrap()).unwrap();
+
+        let mut copy = nested.try_clone().unwrap();
+        copy[0].push(3).unwrap();
+        assert!(*nested[0] == [1, 2], "ZenVec try_clone() was shallow");
+        assert!(*copy[0] == [1, 2, 3], "ZenVec try_clone() failed");
+
+        let rc = ZenRc::new(7u32).unwrap();
+        let rc2 = rc.try_clone().unwrap();
+        assert_eq!(ZenRc::strong_count(&rc2), 2);
+
+        let string = ZenAsciiString::from_str("abc").ok().unwrap();
+        assert_eq!(string.try_clone().unwrap().len(), 3);
+        assert_eq!(42u64.try_clone(), Ok(42));
     }
 
     #[test]
@@ -0,0 +1,77 @@
+use crate::zen_box::zen_box::ZenBox;
+use crate::zen_error::zen_error::{TryReserveError, ZenError};
+use core::alloc::Layout;
+
+// Fallible counterparts of `vec!`, `Box::new` and `format!`. Each macro
+// evaluates to a `Result` instead of panicking or aborting when memory runs
+// out, so fixtures can be built with a single `?`.
+
+// `try_vec![a, b, c]` reserves room for exactly the listed elements, counted
+// at compile time, before evaluating any of them. `try_vec![elem; n]` clones
+// `elem` into `n` slots.
+#[macro_export]
+macro_rules! try_vec {
+    () => {
+        ::core::result::Result::<_, $crate::ZenError>::Ok($crate::ZenVec::new())
+    };
+    ($elem:expr; $n:expr) => {
+        $crate::ZenVec::try_from_elem($elem, $n).map_err($crate::ZenError::from)
+    };
+    ($($x:expr),+ $(,)?) => {{
+        const LEN: usize = <[()]>::len(&[$($crate::__replace_expr!($x, ())),+]);
+        match $crate::ZenVec::with_capacity(LEN) {
+            ::core::result::Result::Ok(mut vec) => {
+                /* Room was reserved above, so these can't fail. */
+                $(let _ = vec.push($x);)+
+                ::core::result::Result::Ok(vec)
+            }
+            ::core::result::Result::Err(err) => {
+                ::core::result::Result::Err($crate::ZenError::from(err))
+            }
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! try_box {
+    ($value:expr) => {
+        $crate::__private::try_box($value)
+    };
+}
+
+// Builds a `ZenAsciiString` from a string literal. A non-ASCII literal is a
+// compile error, so the only runtime failure is allocation.
+#[macro_export]
+macro_rules! try_ascii {
+    ($s:literal) => {{
+        const S: &$crate::AsciiStr = $crate::AsciiStr::new($s);
+        S.try_to_ascii_string().map_err($crate::ZenError::from)
+    }};
+}
+
+// `format!` into a `ZenString`, as `Result<ZenString, AllocError>`.
+#[macro_export]
+macro_rules! try_format {
+    ($($arg:tt)*) => {
+        $crate::__private::try_format(::core::format_args!($($arg)*))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __replace_expr {
+    ($_t:expr, $sub:expr) => {
+        $sub
+    };
+}
+
+// `ZenBox::new` only reports that it failed; the refused layout is always
+// the one for `T`.
+#[doc(hidden)]
+pub fn try_box<T>(value: T) -> Result<ZenBox<T>, ZenError> {
+    ZenBox::new(value).map_err(|_| {
+        ZenError::TryReserve(TryReserveError::AllocError {
+            layout: Layout::new::<T>(),
+        })
+    })
+}
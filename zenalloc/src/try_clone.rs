@@ -0,0 +1,15 @@
+use core::alloc::AllocError;
+
+// Fallible counterpart of `Clone` for types whose copies need to allocate.
+// `Copy` types get it for free; containers implement it by deep-copying
+// their contents and report allocation failure instead of aborting.
+pub trait TryClone: Sized {
+    fn try_clone(&self) -> Result<Self, AllocError>;
+}
+
+impl<T: Copy> TryClone for T {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(*self)
+    }
+}
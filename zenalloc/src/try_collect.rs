@@ -0,0 +1,23 @@
+use core::alloc::AllocError;
+
+// Fallible counterpart of `FromIterator`. Implementations reserve capacity
+// from the iterator's `size_hint` up front and report allocation failure
+// instead of aborting.
+pub trait TryFromIterator<A>: Sized {
+    fn try_from_iter<I: IntoIterator<Item = A>>(iter: I) -> Result<Self, AllocError>;
+}
+
+// Fallible counterpart of `Extend`. If allocation fails partway through,
+// the collection is rolled back to the state it had before the call.
+pub trait TryExtend<A> {
+    fn try_extend<I: IntoIterator<Item = A>>(&mut self, iter: I) -> Result<(), AllocError>;
+}
+
+// Adds `try_collect` to every iterator, e.g. `iter.try_collect::<ZenVec<_>>()`.
+pub trait TryCollect: Iterator + Sized {
+    fn try_collect<C: TryFromIterator<Self::Item>>(self) -> Result<C, AllocError> {
+        C::try_from_iter(self)
+    }
+}
+
+impl<I: Iterator> TryCollect for I {}
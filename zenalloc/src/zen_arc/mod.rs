@@ -1 +1,2 @@
+#[allow(clippy::module_inception)]
 pub mod zen_arc;
\ No newline at end of file
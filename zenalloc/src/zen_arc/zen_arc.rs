@@ -1,10 +1,15 @@
 use core::sync::atomic::{AtomicUsize, Ordering};
 use core::ptr::NonNull;
+use core::borrow::Borrow;
+use core::cmp;
+use core::fmt;
+use core::hash::{Hash, Hasher};
 use core::ops::Deref;
 use core::alloc::{Layout, AllocError};
 use core::ptr;
 use crate::System;
 use crate::alloc_trait::Allocator;
+use crate::try_clone::TryClone;
 
 pub struct ZenArc<T> {
     ptr: NonNull<ZenArcBox<T>>,
@@ -31,18 +36,51 @@ impl<T> ZenArc<T> {
         }
     }
 
+    // Turns the reference into a pointer to the value. The count is kept,
+    // so the value stays alive until `from_raw` gives it back.
+    pub fn into_raw(this: Self) -> *const T {
+        let ptr = unsafe { ptr::addr_of!((*this.ptr.as_ptr()).value) };
+        core::mem::forget(this);
+        ptr
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must come from `ZenArc::into_raw`, and each pointer may be
+    /// turned back only once.
+    pub unsafe fn from_raw(ptr: *const T) -> Self {
+        let offset = core::mem::offset_of!(ZenArcBox<T>, value);
+        let inner = (ptr as *const u8).sub(offset) as *mut ZenArcBox<T>;
+        ZenArc {
+            ptr: NonNull::new_unchecked(inner),
+        }
+    }
+
+    // `Default` can't report allocation failure, so this stands in for it.
+    pub fn try_default() -> Result<Self, AllocError>
+    where
+        T: Default,
+    {
+        Self::new(T::default())
+    }
+
     pub fn strong_count(this: &Self) -> usize {
         unsafe { this.ptr.as_ref().ref_count.load(Ordering::SeqCst) }
     }
+}
 
-    pub fn clone(this: &Self) -> Self {
-        let count = ZenArc::strong_count(this);
+impl<T> Clone for ZenArc<T> {
+    fn clone(&self) -> Self {
         unsafe {
-            this.ptr.as_ref().ref_count.fetch_add(1, Ordering::SeqCst);
-        }
-        ZenArc {
-            ptr: this.ptr,
+            self.ptr.as_ref().ref_count.fetch_add(1, Ordering::SeqCst);
         }
+        ZenArc { ptr: self.ptr }
+    }
+}
+
+impl<T> TryClone for ZenArc<T> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(self.clone())
     }
 }
 
@@ -71,5 +109,61 @@ impl<T> Drop for ZenArc<T> {
     }
 }
 
+impl<T: fmt::Debug> fmt::Debug for ZenArc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for ZenArc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T> fmt::Pointer for ZenArc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&(&**self as *const T), f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for ZenArc<T> {
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(&**self, &**other)
+    }
+}
+
+impl<T: Eq> Eq for ZenArc<T> {}
+
+impl<T: PartialOrd> PartialOrd for ZenArc<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord> Ord for ZenArc<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T: Hash> Hash for ZenArc<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T> Borrow<T> for ZenArc<T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T> AsRef<T> for ZenArc<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
 unsafe impl<T: Send + Sync> Send for ZenArc<T> {}
 unsafe impl<T: Sync> Sync for ZenArc<T> {}
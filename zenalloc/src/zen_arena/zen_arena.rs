@@ -0,0 +1,210 @@
+use crate::alloc_trait::Allocator;
+use crate::zen_error::zen_error::TryReserveError;
+use crate::System;
+use core::alloc::Layout;
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr::{self, NonNull};
+
+// Chunks start at this many values and double up to `MAX_CHUNK_BYTES`.
+const MIN_CHUNK_LEN: usize = 8;
+const MAX_CHUNK_BYTES: usize = 2 * 1024 * 1024;
+
+// Sits at the start of every chunk, followed by the values. Chunks form a
+// list from the newest back to the oldest.
+struct ChunkHeader {
+    prev: Option<NonNull<ChunkHeader>>,
+    cap: usize,
+    // Values in use; only kept up to date for chunks other than the current one.
+    len: usize,
+}
+
+// A typed arena. Values are never moved or freed individually, so
+// `try_alloc` can hand out `&mut T` that lives as long as the arena and
+// values can refer to each other with plain borrows. Everything is dropped
+// together when the arena goes away.
+pub struct ZenArena<T, A: Allocator = System> {
+    alloc: A,
+    chunk: Cell<Option<NonNull<ChunkHeader>>>,
+    ptr: Cell<*mut T>,
+    end: Cell<*mut T>,
+    len: Cell<usize>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> ZenArena<T> {
+    pub const fn new() -> Self {
+        Self::new_in(System)
+    }
+}
+
+impl<T, A: Allocator> ZenArena<T, A> {
+    pub const fn new_in(alloc: A) -> Self {
+        Self {
+            alloc,
+            chunk: Cell::new(None),
+            ptr: Cell::new(ptr::null_mut()),
+            end: Cell::new(ptr::null_mut()),
+            len: Cell::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len.get() == 0
+    }
+
+    // Moves `value` into the arena, or hands it back if no chunk could be
+    // allocated.
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_alloc(&self, value: T) -> Result<&mut T, T> {
+        if mem::size_of::<T>() == 0 {
+            self.len.set(self.len.get() + 1);
+            let slot = NonNull::<T>::dangling().as_ptr();
+            unsafe {
+                ptr::write(slot, value);
+                return Ok(&mut *slot);
+            }
+        }
+
+        if self.ptr.get() == self.end.get() && self.grow(1).is_err() {
+            return Err(value);
+        }
+        let slot = self.ptr.get();
+        unsafe {
+            self.ptr.set(slot.add(1));
+            ptr::write(slot, value);
+        }
+        self.len.set(self.len.get() + 1);
+        Ok(unsafe { &mut *slot })
+    }
+
+    // Makes sure the next `additional` values fit without a new chunk.
+    pub fn try_reserve(&self, additional: usize) -> Result<(), TryReserveError> {
+        if mem::size_of::<T>() == 0 {
+            return Ok(());
+        }
+        let available = (self.end.get() as usize - self.ptr.get() as usize) / mem::size_of::<T>();
+        if available >= additional {
+            return Ok(());
+        }
+        self.grow(additional)
+    }
+
+    fn chunk_layout(cap: usize) -> Option<(Layout, usize)> {
+        let header = Layout::new::<ChunkHeader>();
+        let values = Layout::array::<T>(cap).ok()?;
+        let (layout, offset) = header.extend(values).ok()?;
+        Some((layout.pad_to_align(), offset))
+    }
+
+    // Starts a new chunk with room for at least `additional` values. The
+    // unused tail of the current chunk is abandoned.
+    fn grow(&self, additional: usize) -> Result<(), TryReserveError> {
+        let max_len = (MAX_CHUNK_BYTES / mem::size_of::<T>()).max(1);
+        let cap = match self.chunk.get() {
+            Some(chunk) => unsafe { chunk.as_ref().cap.saturating_mul(2) }.min(max_len),
+            None => MIN_CHUNK_LEN,
+        }
+        .max(additional);
+        let (layout, offset) =
+            Self::chunk_layout(cap).ok_or(TryReserveError::CapacityOverflow)?;
+        let base = self
+            .alloc
+            .allocate(layout)
+            .map_err(|_| TryReserveError::AllocError { layout })?
+            .cast::<u8>();
+
+        if let Some(mut current) = self.chunk.get() {
+            unsafe {
+                let start = Self::values(current);
+                current.as_mut().len = self.ptr.get().offset_from(start) as usize;
+            }
+        }
+
+        let header = base.cast::<ChunkHeader>();
+        unsafe {
+            header.as_ptr().write(ChunkHeader {
+                prev: self.chunk.get(),
+                cap,
+                len: 0,
+            });
+            let start = base.as_ptr().add(offset).cast::<T>();
+            self.ptr.set(start);
+            self.end.set(start.add(cap));
+        }
+        self.chunk.set(Some(header));
+        Ok(())
+    }
+
+    unsafe fn values(chunk: NonNull<ChunkHeader>) -> *mut T {
+        let (_, offset) = Self::chunk_layout(chunk.as_ref().cap).unwrap_unchecked();
+        chunk.as_ptr().cast::<u8>().add(offset).cast::<T>()
+    }
+}
+
+// There is no `TryFromIterator` or `TryExtend`: a value is only reachable
+// through the reference `try_alloc` returns, so values added in bulk could
+// never be used, only dropped with the arena.
+impl<T, A: Allocator + Default> Default for ZenArena<T, A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+// The values can't be listed: `try_alloc` handed out `&mut` borrows of
+// every one of them.
+impl<T, A: Allocator> fmt::Debug for ZenArena<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZenArena")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/* Values may borrow from each other, so they are allowed to dangle while
+ * being dropped, as with the standard collections. */
+unsafe impl<#[may_dangle] T, A: Allocator> Drop for ZenArena<T, A> {
+    fn drop(&mut self) {
+        if mem::size_of::<T>() == 0 {
+            for _ in 0..self.len.get() {
+                unsafe { ptr::drop_in_place(NonNull::<T>::dangling().as_ptr()) };
+            }
+            return;
+        }
+
+        let mut next = self.chunk.get();
+        let mut used = self.ptr.get();
+        while let Some(chunk) = next {
+            unsafe {
+                let start = Self::values(chunk);
+                let len = if used.is_null() {
+                    chunk.as_ref().len
+                } else {
+                    used.offset_from(start) as usize
+                };
+                used = ptr::null_mut();
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(start, len));
+
+                let header = chunk.as_ref();
+                next = header.prev;
+                let (layout, _) = Self::chunk_layout(header.cap).unwrap_unchecked();
+                self.alloc.deallocate(chunk.cast(), layout);
+            }
+        }
+    }
+}
+
+unsafe impl<T: Send, A: Allocator + Send> Send for ZenArena<T, A> {}
@@ -0,0 +1,602 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_error::zen_error::ZenError;
+use crate::zen_vec::zen_vec::check_range;
+use core::{
+    alloc::AllocError,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut, RangeBounds},
+    ptr, slice,
+};
+
+// Returned when an element does not fit into a full `ZenArrayVec`. The
+// rejected element is handed back to the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T>(pub T);
+
+impl<T> CapacityError<T> {
+    pub fn element(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for CapacityError<T> {}
+
+// Returned by `ZenArrayVec::insert`. Either way the rejected element is
+// handed back to the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertError<T> {
+    Full(T),
+    OutOfBounds { index: usize, len: usize, elem: T },
+}
+
+impl<T> InsertError<T> {
+    pub fn element(self) -> T {
+        match self {
+            InsertError::Full(elem) | InsertError::OutOfBounds { elem, .. } => elem,
+        }
+    }
+}
+
+impl<T> fmt::Display for InsertError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertError::Full(_) => f.write_str("insufficient capacity"),
+            InsertError::OutOfBounds { index, len, .. } => {
+                write!(f, "insertion index {} out of bounds for length {}", index, len)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for InsertError<T> {}
+
+// A vector with a fixed capacity of `N` elements stored inline. It never
+// touches the allocator, so it is usable where allocating is not allowed.
+pub struct ZenArrayVec<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ZenArrayVec<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            data: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub const fn cap(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    #[inline]
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    fn ptr(&self) -> *const T {
+        self.data.as_ptr() as *const T
+    }
+
+    fn mut_ptr(&mut self) -> *mut T {
+        self.data.as_mut_ptr() as *mut T
+    }
+
+    pub fn push(&mut self, elem: T) -> Result<(), CapacityError<T>> {
+        if self.len == N {
+            return Err(CapacityError(elem));
+        }
+
+        self.data[self.len].write(elem);
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            unsafe { Some(self.data[self.len].assume_init_read()) }
+        }
+    }
+
+    // Inserts `elem` at `index`, handing it back if `index > len` or the
+    // vector is full.
+    pub fn insert(&mut self, index: usize, elem: T) -> Result<(), InsertError<T>> {
+        if index > self.len {
+            return Err(InsertError::OutOfBounds {
+                index,
+                len: self.len,
+                elem,
+            });
+        }
+        if self.len == N {
+            return Err(InsertError::Full(elem));
+        }
+
+        unsafe {
+            let base = self.mut_ptr();
+            ptr::copy(base.add(index), base.add(index + 1), self.len - index);
+            ptr::write(base.add(index), elem);
+        }
+
+        self.len += 1;
+        Ok(())
+    }
+
+    // Pushes every item of `iter`, or none of them: if one does not fit,
+    // the items pushed so far are dropped again and the rejected one is
+    // returned. The rest of the iterator is dropped.
+    pub fn extend<I>(&mut self, iter: I) -> Result<(), CapacityError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let original_len = self.len;
+        for elem in iter {
+            if let Err(err) = self.push(elem) {
+                self.truncate(original_len);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<T, ZenError> {
+        if index >= self.len {
+            return Err(ZenError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+
+        self.len -= 1;
+
+        unsafe {
+            let base = self.mut_ptr();
+            let result = ptr::read(base.add(index));
+            ptr::copy(base.add(index + 1), base.add(index), self.len - index);
+            Ok(result)
+        }
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, ZenError> {
+        if index >= self.len {
+            return Err(ZenError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+
+        unsafe {
+            let base = self.mut_ptr();
+            let value = ptr::read(base.add(index));
+            ptr::copy(base.add(self.len - 1), base.add(index), 1);
+            self.len -= 1;
+            Ok(value)
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let remaining = self.len - len;
+        unsafe {
+            let tail = ptr::slice_from_raw_parts_mut(self.mut_ptr().add(len), remaining);
+            self.len = len;
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let original_len = self.len;
+        if original_len == 0 {
+            return;
+        }
+
+        /* Hide the elements from the vector until the guard has closed the gaps. */
+        self.len = 0;
+
+        struct BackshiftOnDrop<'a, T, const N: usize> {
+            vec: &'a mut ZenArrayVec<T, N>,
+            processed: usize,
+            deleted: usize,
+            original_len: usize,
+        }
+
+        impl<T, const N: usize> Drop for BackshiftOnDrop<'_, T, N> {
+            fn drop(&mut self) {
+                if self.deleted > 0 {
+                    unsafe {
+                        let base = self.vec.mut_ptr();
+                        ptr::copy(
+                            base.add(self.processed),
+                            base.add(self.processed - self.deleted),
+                            self.original_len - self.processed,
+                        );
+                    }
+                }
+                self.vec.len = self.original_len - self.deleted;
+            }
+        }
+
+        let mut guard = BackshiftOnDrop {
+            vec: self,
+            processed: 0,
+            deleted: 0,
+            original_len,
+        };
+
+        while guard.processed != original_len {
+            let cur = unsafe { &mut *guard.vec.mut_ptr().add(guard.processed) };
+            if !f(cur) {
+                guard.processed += 1;
+                guard.deleted += 1;
+                unsafe { ptr::drop_in_place(cur) };
+                continue;
+            }
+            if guard.deleted > 0 {
+                unsafe {
+                    let hole = guard.vec.mut_ptr().add(guard.processed - guard.deleted);
+                    ptr::copy_nonoverlapping(cur, hole, 1);
+                }
+            }
+            guard.processed += 1;
+        }
+    }
+
+    pub fn drain<R>(&mut self, range: R) -> Result<Drain<'_, T, N>, ZenError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let (start, end) = check_range(range, len)?;
+
+        self.len = start;
+        Ok(Drain {
+            vec: self,
+            start,
+            end,
+            tail_start: end,
+            tail_len: len - end,
+        })
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.mut_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Drop for ZenArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for ZenArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for ZenArrayVec<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ZenArrayVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: TryClone, const N: usize> TryClone for ZenArrayVec<T, N> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut vec = ZenArrayVec::new();
+        for elem in self.iter() {
+            /* Same capacity as `self`, so this can only fail in `try_clone`. */
+            let _ = vec.push(elem.try_clone()?);
+        }
+        Ok(vec)
+    }
+}
+
+// `TryFromIterator` and `TryExtend` report running out of inline capacity as
+// an `AllocError`, the same way the heap-backed collections report running
+// out of memory, and drop the element that did not fit. The inherent
+// `extend` hands it back in a `CapacityError` instead.
+impl<T, const N: usize> TryFromIterator<T> for ZenArrayVec<T, N> {
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, AllocError> {
+        let mut vec = ZenArrayVec::new();
+        vec.try_extend(iter)?;
+        Ok(vec)
+    }
+}
+
+impl<T, const N: usize> TryExtend<T> for ZenArrayVec<T, N> {
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let iter = iter.into_iter();
+        let original_len = self.len;
+
+        if iter.size_hint().0 > self.remaining_capacity() {
+            return Err(AllocError);
+        }
+        for elem in iter {
+            if self.push(elem).is_err() {
+                self.truncate(original_len);
+                return Err(AllocError);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq<[T]> for ZenArrayVec<T, N> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq, const N: usize, const M: usize> PartialEq<[T; M]> for ZenArrayVec<T, N> {
+    fn eq(&self, other: &[T; M]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq, const N: usize, const M: usize> PartialEq<ZenArrayVec<T, M>>
+    for ZenArrayVec<T, N>
+{
+    fn eq(&self, other: &ZenArrayVec<T, M>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ZenArrayVec<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for ZenArrayVec<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for ZenArrayVec<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+// Hashes like the equivalent slice, as `ZenVec` does.
+impl<T: Hash, const N: usize> Hash for ZenArrayVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ZenArrayVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ZenArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        let this = ManuallyDrop::new(self);
+        /* `this` is never dropped, so the elements are moved exactly once. */
+        let data = unsafe { ptr::read(&this.data) };
+        IntoIter {
+            data,
+            start: 0,
+            end: this.len,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ZenArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ZenArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+// Owning iterator returned by `ZenArrayVec::into_iter`.
+pub struct IntoIter<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    start: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe {
+            slice::from_raw_parts(
+                (self.data.as_ptr() as *const T).add(self.start),
+                self.end - self.start,
+            )
+        }
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start < self.end {
+            let item = unsafe { self.data[self.start].assume_init_read() };
+            self.start += 1;
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start < self.end {
+            self.end -= 1;
+            unsafe { Some(self.data[self.end].assume_init_read()) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let start = mem::replace(&mut self.start, self.end);
+        unsafe {
+            let remaining = ptr::slice_from_raw_parts_mut(
+                (self.data.as_mut_ptr() as *mut T).add(start),
+                self.end - start,
+            );
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
+
+// Iterator returned by `ZenArrayVec::drain`. The tail is moved back into
+// place when it is dropped.
+pub struct Drain<'a, T, const N: usize> {
+    vec: &'a mut ZenArrayVec<T, N>,
+    start: usize,
+    end: usize,
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start < self.end {
+            let item = unsafe { ptr::read(self.vec.ptr().add(self.start)) };
+            self.start += 1;
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start < self.end {
+            self.end -= 1;
+            unsafe { Some(ptr::read(self.vec.ptr().add(self.end))) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Drain<'_, T, N> {}
+
+impl<T, const N: usize> FusedIterator for Drain<'_, T, N> {}
+
+impl<T, const N: usize> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        /* Moves the tail back even if dropping a remaining element panics. */
+        struct MoveTail<'r, 'a, T, const N: usize>(&'r mut Drain<'a, T, N>);
+
+        impl<T, const N: usize> Drop for MoveTail<'_, '_, T, N> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                unsafe {
+                    let base = drain.vec.mut_ptr();
+                    let len = drain.vec.len;
+                    ptr::copy(base.add(drain.tail_start), base.add(len), drain.tail_len);
+                    drain.vec.len = len + drain.tail_len;
+                }
+            }
+        }
+
+        let start = mem::replace(&mut self.start, self.end);
+        let end = self.end;
+        let guard = MoveTail(self);
+        unsafe {
+            let remaining = ptr::slice_from_raw_parts_mut(guard.0.vec.mut_ptr().add(start), end - start);
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
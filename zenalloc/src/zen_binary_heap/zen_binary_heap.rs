@@ -0,0 +1,422 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_error::zen_error::TryReserveError;
+use crate::zen_vec::zen_vec::{ZenVec, ZenVecIter};
+use core::{
+    alloc::AllocError,
+    fmt,
+    iter::FusedIterator,
+    mem::{self, ManuallyDrop},
+    ops::{Deref, DerefMut},
+    ptr,
+};
+
+// A max-heap priority queue backed by a `ZenVec`. Wrap elements in
+// `core::cmp::Reverse` to get a min-heap.
+//
+// Sifting only ever indexes within `0..len` and never trusts `Ord` for
+// bounds, so an inconsistent `Ord` implementation yields an unspecified
+// order but never a panic, a leak or a double drop.
+pub struct ZenBinaryHeap<T> {
+    data: ZenVec<T>,
+}
+
+impl<T: Ord> ZenBinaryHeap<T> {
+    pub fn new() -> Self {
+        Self { data: ZenVec::new() }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            data: ZenVec::with_capacity(capacity)?,
+        })
+    }
+
+    pub fn try_push(&mut self, item: T) -> Result<(), TryReserveError> {
+        let old_len = self.data.len();
+        self.data.try_reserve(1)?;
+        /* Room was reserved above, so this can't fail. */
+        let _ = self.data.push(item);
+        unsafe { self.sift_up(0, old_len) };
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.data.pop().map(|mut item| {
+            if let Some(top) = self.data.first_mut() {
+                mem::swap(&mut item, top);
+                unsafe { self.sift_down_to_bottom(0) };
+            }
+            item
+        })
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    // Returns a mutable handle to the greatest element. The heap is
+    // restored when the handle is dropped, if the element was modified.
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut {
+                heap: self,
+                sift: false,
+            })
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.data.try_reserve(additional)
+    }
+
+    // Consumes the heap and returns its elements in ascending order, using
+    // the heap's own buffer.
+    pub fn into_sorted_vec(mut self) -> ZenVec<T> {
+        let mut end = self.len();
+        while end > 1 {
+            end -= 1;
+            self.data.as_mut_slice().swap(0, end);
+            unsafe { self.sift_down_range(0, end) };
+        }
+        self.into_vec()
+    }
+
+    // Removes the elements in heap order, greatest first. Elements that
+    // are not consumed are removed when the iterator is dropped.
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, T> {
+        DrainSorted { heap: self }
+    }
+
+    // Moves the element at `pos` up towards `start` until its parent is not
+    // smaller.
+    //
+    // # Safety
+    //
+    // `start <= pos < len`.
+    unsafe fn sift_up(&mut self, start: usize, pos: usize) -> usize {
+        let mut hole = Hole::new(self.data.as_mut_slice(), pos);
+
+        while hole.pos() > start {
+            let parent = (hole.pos() - 1) / 2;
+            if hole.element() <= hole.get(parent) {
+                break;
+            }
+            hole.move_to(parent);
+        }
+
+        hole.pos()
+    }
+
+    // Moves the element at `pos` down until both children are not greater,
+    // considering only the elements before `end`.
+    //
+    // # Safety
+    //
+    // `pos < end <= len`.
+    unsafe fn sift_down_range(&mut self, pos: usize, end: usize) {
+        let mut hole = Hole::new(self.data.as_mut_slice(), pos);
+        let mut child = 2 * hole.pos() + 1;
+
+        /* `child < end - 1` keeps `child + 1` in bounds without overflow. */
+        while child <= end.saturating_sub(2) {
+            child += (hole.get(child) <= hole.get(child + 1)) as usize;
+            if hole.element() >= hole.get(child) {
+                return;
+            }
+            hole.move_to(child);
+            child = 2 * hole.pos() + 1;
+        }
+
+        if child == end - 1 && hole.element() < hole.get(child) {
+            hole.move_to(child);
+        }
+    }
+
+    unsafe fn sift_down(&mut self, pos: usize) {
+        let len = self.len();
+        self.sift_down_range(pos, len);
+    }
+
+    // Moves the element at `pos` all the way down to a leaf, then back up.
+    // This takes fewer comparisons than `sift_down` after a `pop`, since the
+    // element that replaced the root came from the bottom of the heap.
+    //
+    // # Safety
+    //
+    // `pos < len`.
+    unsafe fn sift_down_to_bottom(&mut self, mut pos: usize) {
+        let end = self.len();
+        let start = pos;
+
+        let mut hole = Hole::new(self.data.as_mut_slice(), pos);
+        let mut child = 2 * hole.pos() + 1;
+
+        while child <= end.saturating_sub(2) {
+            child += (hole.get(child) <= hole.get(child + 1)) as usize;
+            hole.move_to(child);
+            child = 2 * hole.pos() + 1;
+        }
+
+        if child == end - 1 {
+            hole.move_to(child);
+        }
+        pos = hole.pos();
+        drop(hole);
+
+        self.sift_up(start, pos);
+    }
+
+    // Restores the heap property over the whole buffer in O(n).
+    fn rebuild(&mut self) {
+        let mut n = self.len() / 2;
+        while n > 0 {
+            n -= 1;
+            unsafe { self.sift_down(n) };
+        }
+    }
+}
+
+impl<T> ZenBinaryHeap<T> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn cap(&self) -> usize {
+        self.data.cap()
+    }
+
+    // Iterates over the elements in arbitrary (heap) order.
+    pub fn iter(&self) -> ZenVecIter<'_, T> {
+        self.data.iter()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    pub fn into_vec(self) -> ZenVec<T> {
+        self.data
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+}
+
+impl<T: Ord> Default for ZenBinaryHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Heapifies the vector in place, without allocating.
+impl<T: Ord> From<ZenVec<T>> for ZenBinaryHeap<T> {
+    fn from(vec: ZenVec<T>) -> Self {
+        let mut heap = ZenBinaryHeap { data: vec };
+        heap.rebuild();
+        heap
+    }
+}
+
+impl<T: Ord> From<ZenBinaryHeap<T>> for ZenVec<T> {
+    fn from(heap: ZenBinaryHeap<T>) -> Self {
+        heap.data
+    }
+}
+
+// Lists the elements in heap order, as `BinaryHeap` does.
+impl<T: fmt::Debug> fmt::Debug for ZenBinaryHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.data.iter()).finish()
+    }
+}
+
+impl<T: Ord + TryClone> TryClone for ZenBinaryHeap<T> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(Self {
+            data: self.data.try_clone()?,
+        })
+    }
+}
+
+impl<T: Ord> TryFromIterator<T> for ZenBinaryHeap<T> {
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, AllocError> {
+        Ok(ZenBinaryHeap::from(ZenVec::try_from_iter(iter)?))
+    }
+}
+
+impl<T: Ord> TryExtend<T> for ZenBinaryHeap<T> {
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let original_len = self.len();
+
+        /* Append everything first: on failure the vector rolls back and the heap is untouched. */
+        self.data.try_extend(iter)?;
+        for pos in original_len..self.len() {
+            unsafe { self.sift_up(0, pos) };
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> IntoIterator for ZenBinaryHeap<T> {
+    type Item = T;
+    type IntoIter = crate::zen_vec::into_iter::IntoIter<T>;
+
+    // Yields the elements in arbitrary order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ZenBinaryHeap<T> {
+    type Item = &'a T;
+    type IntoIter = ZenVecIter<'a, T>;
+
+    fn into_iter(self) -> ZenVecIter<'a, T> {
+        self.iter()
+    }
+}
+
+// Mutable handle to the greatest element, returned by `peek_mut`.
+pub struct PeekMut<'a, T: Ord> {
+    heap: &'a mut ZenBinaryHeap<T>,
+    sift: bool,
+}
+
+impl<T: Ord> PeekMut<'_, T> {
+    // Removes the peeked element from the heap and returns it.
+    pub fn pop(mut this: Self) -> T {
+        /* The heap is already valid again once the top is removed. */
+        this.sift = false;
+        match this.heap.pop() {
+            Some(item) => item,
+            /* A `PeekMut` is only created for a non-empty heap. */
+            None => unsafe { core::hint::unreachable_unchecked() },
+        }
+    }
+}
+
+impl<T: Ord> Deref for PeekMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        /* A `PeekMut` is only created for a non-empty heap. */
+        unsafe { self.heap.data.as_slice().get_unchecked(0) }
+    }
+}
+
+impl<T: Ord> DerefMut for PeekMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.sift = true;
+        unsafe { self.heap.data.as_mut_slice().get_unchecked_mut(0) }
+    }
+}
+
+impl<T: Ord> Drop for PeekMut<'_, T> {
+    fn drop(&mut self) {
+        if self.sift {
+            unsafe { self.heap.sift_down(0) };
+        }
+    }
+}
+
+// Iterator returned by `ZenBinaryHeap::drain_sorted`.
+pub struct DrainSorted<'a, T: Ord> {
+    heap: &'a mut ZenBinaryHeap<T>,
+}
+
+impl<T: Ord> Iterator for DrainSorted<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.heap.len();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: Ord> ExactSizeIterator for DrainSorted<'_, T> {}
+
+impl<T: Ord> FusedIterator for DrainSorted<'_, T> {}
+
+impl<T: Ord> Drop for DrainSorted<'_, T> {
+    fn drop(&mut self) {
+        self.heap.clear();
+    }
+}
+
+// A slot whose element has been moved out and is held in `elt`. The
+// element is written back to wherever the hole ends up, even if a
+// comparison panics.
+struct Hole<'a, T> {
+    data: &'a mut [T],
+    elt: ManuallyDrop<T>,
+    pos: usize,
+}
+
+impl<'a, T> Hole<'a, T> {
+    // # Safety
+    //
+    // `pos < data.len()`.
+    #[inline]
+    unsafe fn new(data: &'a mut [T], pos: usize) -> Self {
+        let elt = ptr::read(data.get_unchecked(pos));
+        Hole {
+            data,
+            elt: ManuallyDrop::new(elt),
+            pos,
+        }
+    }
+
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[inline]
+    fn element(&self) -> &T {
+        &self.elt
+    }
+
+    // # Safety
+    //
+    // `index < data.len()` and `index != pos`.
+    #[inline]
+    unsafe fn get(&self, index: usize) -> &T {
+        self.data.get_unchecked(index)
+    }
+
+    // Moves the element at `index` into the hole; the hole moves to `index`.
+    //
+    // # Safety
+    //
+    // `index < data.len()` and `index != pos`.
+    #[inline]
+    unsafe fn move_to(&mut self, index: usize) {
+        let ptr = self.data.as_mut_ptr();
+        ptr::copy_nonoverlapping(ptr.add(index), ptr.add(self.pos), 1);
+        self.pos = index;
+    }
+}
+
+impl<T> Drop for Hole<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            let pos = self.pos;
+            ptr::copy_nonoverlapping(&*self.elt, self.data.as_mut_ptr().add(pos), 1);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod zen_bit_set;
+#[allow(clippy::module_inception)]
+pub mod zen_bit_vec;
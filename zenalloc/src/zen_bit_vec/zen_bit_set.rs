@@ -0,0 +1,293 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_bit_vec::zen_bit_vec::{words_for, Ones, BITS};
+use crate::zen_error::zen_error::TryReserveError;
+use crate::zen_vec::zen_vec::ZenVec;
+use core::alloc::AllocError;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+// A set of small integers, one bit per possible member. The word vector
+// only grows on insert; trailing zero words are allowed and ignored.
+pub struct ZenBitSet {
+    words: ZenVec<usize>,
+}
+
+impl ZenBitSet {
+    pub fn new() -> Self {
+        Self {
+            words: ZenVec::new(),
+        }
+    }
+
+    // Room for members below `bits` without reallocating.
+    pub fn with_capacity(bits: usize) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            words: ZenVec::with_capacity(words_for(bits))?,
+        })
+    }
+
+    // Counts the members; this is a popcount over every word.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.words.cap() * BITS
+    }
+
+    pub fn as_words(&self) -> &[usize] {
+        &self.words
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words
+            .get(value / BITS)
+            .is_some_and(|word| word & (1 << (value % BITS)) != 0)
+    }
+
+    // Makes room for members below `bits`.
+    pub fn try_grow_to(&mut self, bits: usize) -> Result<(), TryReserveError> {
+        let needed = words_for(bits);
+        if needed <= self.words.len() {
+            return Ok(());
+        }
+        self.words.try_reserve(needed - self.words.len())?;
+        while self.words.len() < needed {
+            /* Room was reserved above, so this can't fail. */
+            let _ = self.words.push(0);
+        }
+        Ok(())
+    }
+
+    // Returns whether the value was newly added.
+    pub fn try_insert(&mut self, value: usize) -> Result<bool, TryReserveError> {
+        self.try_grow_to(value.checked_add(1).ok_or(TryReserveError::CapacityOverflow)?)?;
+        let word = &mut self.words[value / BITS];
+        let mask = 1 << (value % BITS);
+        let added = *word & mask == 0;
+        *word |= mask;
+        Ok(added)
+    }
+
+    // Returns whether the value was a member.
+    pub fn remove(&mut self, value: usize) -> bool {
+        match self.words.get_mut(value / BITS) {
+            Some(word) => {
+                let mask = 1 << (value % BITS);
+                let present = *word & mask != 0;
+                *word &= !mask;
+                present
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = 0;
+        }
+    }
+
+    // The smallest member.
+    pub fn first(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    // The largest member.
+    pub fn last(&self) -> Option<usize> {
+        let (index, &word) = self
+            .words
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, &word)| word != 0)?;
+        Some(index * BITS + (BITS - 1 - word.leading_zeros() as usize))
+    }
+
+    // The smallest value that is not a member, e.g. the next free ID.
+    pub fn first_absent(&self) -> usize {
+        for (index, &word) in self.words.iter().enumerate() {
+            if word != !0 {
+                return index * BITS + (!word).trailing_zeros() as usize;
+            }
+        }
+        self.words.len() * BITS
+    }
+
+    // Members in ascending order.
+    pub fn iter(&self) -> Ones<'_> {
+        Ones::new(&self.words)
+    }
+
+    pub fn words(&self) -> core::iter::Copied<core::slice::Iter<'_, usize>> {
+        self.words.as_slice().iter().copied()
+    }
+
+    // Adds every member of `other`, growing if `other` is wider.
+    pub fn try_union_with(&mut self, other: &ZenBitSet) -> Result<(), TryReserveError> {
+        let used = other.words.len() - other.trailing_zero_words();
+        self.try_grow_to(used * BITS)?;
+        for (word, &other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other;
+        }
+        Ok(())
+    }
+
+    pub fn intersect_with(&mut self, other: &ZenBitSet) {
+        for (index, word) in self.words.iter_mut().enumerate() {
+            *word &= other.words.get(index).copied().unwrap_or(0);
+        }
+    }
+
+    pub fn difference_with(&mut self, other: &ZenBitSet) {
+        for (word, &other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word &= !other;
+        }
+    }
+
+    pub fn is_subset(&self, other: &ZenBitSet) -> bool {
+        self.words
+            .iter()
+            .enumerate()
+            .all(|(index, &word)| word & !other.words.get(index).copied().unwrap_or(0) == 0)
+    }
+
+    pub fn is_superset(&self, other: &ZenBitSet) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &ZenBitSet) -> bool {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .all(|(&a, &b)| a & b == 0)
+    }
+
+    fn trailing_zero_words(&self) -> usize {
+        self.words.iter().rev().take_while(|&&word| word == 0).count()
+    }
+
+    // Undoes a partial `try_extend`: drops the words it added and clears
+    // the bits it set in the words that were already there.
+    fn roll_back(&mut self, original_words: usize, added: Option<&[usize]>) {
+        self.words.truncate(original_words);
+        if let Some(added) = added {
+            for (word, &bits) in self.words.iter_mut().zip(added.iter()) {
+                *word &= !bits;
+            }
+        }
+    }
+}
+
+impl Default for ZenBitSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryClone for ZenBitSet {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(Self {
+            words: self.words.try_clone()?,
+        })
+    }
+}
+
+// Sets with the same members are equal whatever their word counts.
+impl PartialEq for ZenBitSet {
+    fn eq(&self, other: &Self) -> bool {
+        let used = self.words.len() - self.trailing_zero_words();
+        let other_used = other.words.len() - other.trailing_zero_words();
+        self.words[..used] == other.words[..other_used]
+    }
+}
+
+impl Eq for ZenBitSet {}
+
+// Orders by members, like a `BTreeSet<usize>`.
+impl PartialOrd for ZenBitSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ZenBitSet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+// Skips trailing empty words, as `eq` does.
+impl Hash for ZenBitSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let used = self.words.len() - self.trailing_zero_words();
+        self.words[..used].hash(state);
+    }
+}
+
+impl fmt::Debug for ZenBitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl TryFromIterator<usize> for ZenBitSet {
+    fn try_from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Result<Self, AllocError> {
+        let mut set = ZenBitSet::new();
+        set.try_extend(iter)?;
+        Ok(set)
+    }
+}
+
+// Only growing can fail. Bits newly set in the existing words are recorded
+// so a failure can clear them again; the record is allocated the first
+// time one is needed, before that bit is set.
+impl TryExtend<usize> for ZenBitSet {
+    fn try_extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let original_words = self.words.len();
+        let mut added: Option<ZenVec<usize>> = None;
+
+        for value in iter {
+            let index = value / BITS;
+            let mask = 1 << (value % BITS);
+            if index >= original_words {
+                if let Err(err) = self.try_insert(value) {
+                    self.roll_back(original_words, added.as_deref());
+                    return Err(err.into());
+                }
+                continue;
+            }
+            if self.words[index] & mask != 0 {
+                continue;
+            }
+            let record = match &mut added {
+                Some(record) => record,
+                None => match ZenVec::try_from_elem(0, original_words) {
+                    Ok(record) => added.insert(record),
+                    Err(err) => {
+                        self.roll_back(original_words, None);
+                        return Err(err.into());
+                    }
+                },
+            };
+            record[index] |= mask;
+            self.words[index] |= mask;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a ZenBitSet {
+    type Item = usize;
+    type IntoIter = Ones<'a>;
+
+    fn into_iter(self) -> Ones<'a> {
+        self.iter()
+    }
+}
@@ -0,0 +1,501 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_error::zen_error::{TryReserveError, ZenError};
+use crate::zen_vec::zen_vec::ZenVec;
+use core::{
+    alloc::AllocError,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+};
+
+pub(crate) const BITS: usize = usize::BITS as usize;
+
+// Number of words needed to hold `len` bits.
+#[inline]
+pub(crate) fn words_for(len: usize) -> usize {
+    len.div_ceil(BITS)
+}
+
+// A growable vector of bits packed into words. Bits past `len` in the last
+// word are always zero, so whole-word operations never have to mask them.
+// Writing past `len` returns `IndexOutOfBounds` instead of panicking.
+pub struct ZenBitVec {
+    words: ZenVec<usize>,
+    len: usize,
+}
+
+impl ZenBitVec {
+    pub fn new() -> Self {
+        Self {
+            words: ZenVec::new(),
+            len: 0,
+        }
+    }
+
+    // Room for at least `bits` bits.
+    pub fn with_capacity(bits: usize) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            words: ZenVec::with_capacity(words_for(bits))?,
+            len: 0,
+        })
+    }
+
+    // `len` bits, all set to `value`.
+    pub fn try_from_elem(len: usize, value: bool) -> Result<Self, TryReserveError> {
+        let mut bits = Self::with_capacity(len)?;
+        bits.try_resize(len, value)?;
+        Ok(bits)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.words.cap() * BITS
+    }
+
+    // The backing words, least significant bit first.
+    pub fn as_words(&self) -> &[usize] {
+        &self.words
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let new_len = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        self.words
+            .try_reserve(words_for(new_len).saturating_sub(self.words.len()))
+    }
+
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index >= self.len {
+            return None;
+        }
+        Some(self.words[index / BITS] & (1 << (index % BITS)) != 0)
+    }
+
+    #[inline]
+    fn check(&self, index: usize) -> Result<(), ZenError> {
+        if index >= self.len {
+            return Err(ZenError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn set(&mut self, index: usize) -> Result<(), ZenError> {
+        self.check(index)?;
+        self.words[index / BITS] |= 1 << (index % BITS);
+        Ok(())
+    }
+
+    pub fn clear(&mut self, index: usize) -> Result<(), ZenError> {
+        self.check(index)?;
+        self.words[index / BITS] &= !(1 << (index % BITS));
+        Ok(())
+    }
+
+    pub fn toggle(&mut self, index: usize) -> Result<(), ZenError> {
+        self.check(index)?;
+        self.words[index / BITS] ^= 1 << (index % BITS);
+        Ok(())
+    }
+
+    // Sets a bit to `value` and returns its old value.
+    pub fn replace(&mut self, index: usize, value: bool) -> Result<bool, ZenError> {
+        self.check(index)?;
+        let word = &mut self.words[index / BITS];
+        let mask = 1 << (index % BITS);
+        let old = *word & mask != 0;
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+        Ok(old)
+    }
+
+    pub fn set_all(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = !0;
+        }
+        self.mask_tail();
+    }
+
+    pub fn clear_all(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = 0;
+        }
+    }
+
+    pub fn toggle_all(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = !*word;
+        }
+        self.mask_tail();
+    }
+
+    // Zeroes the unused bits of the last word.
+    fn mask_tail(&mut self) {
+        let used = self.len % BITS;
+        if used != 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << used) - 1;
+            }
+        }
+    }
+
+    pub fn try_push(&mut self, value: bool) -> Result<(), TryReserveError> {
+        if self.len.is_multiple_of(BITS) {
+            self.words.try_reserve(1)?;
+            /* Room was reserved above, so this can't fail. */
+            let _ = self.words.push(0);
+        }
+        self.len += 1;
+        if value {
+            let index = self.len - 1;
+            self.words[index / BITS] |= 1 << (index % BITS);
+        }
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<bool> {
+        let value = self.get(self.len.checked_sub(1)?)?;
+        self.truncate(self.len - 1);
+        Some(value)
+    }
+
+    // Grows with bits set to `value`, or shrinks, to `new_len` bits.
+    pub fn try_resize(&mut self, new_len: usize, value: bool) -> Result<(), TryReserveError> {
+        if new_len <= self.len {
+            self.truncate(new_len);
+            return Ok(());
+        }
+
+        let old_len = self.len;
+        let new_words = words_for(new_len);
+        self.words.try_reserve(new_words - self.words.len())?;
+        let fill = if value { !0 } else { 0 };
+        if value && !old_len.is_multiple_of(BITS) {
+            let last = self.words.len() - 1;
+            self.words[last] |= !0 << (old_len % BITS);
+        }
+        while self.words.len() < new_words {
+            /* Room was reserved above, so this can't fail. */
+            let _ = self.words.push(fill);
+        }
+        self.len = new_len;
+        self.mask_tail();
+        Ok(())
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.words.truncate(words_for(len));
+            self.len = len;
+            self.mask_tail();
+        }
+    }
+
+    // Removes every bit.
+    pub fn reset(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&word| word != 0)
+    }
+
+    pub fn all(&self) -> bool {
+        self.first_clear().is_none()
+    }
+
+    // Index of the first set bit at or after `from`.
+    pub fn next_set(&self, from: usize) -> Option<usize> {
+        self.scan(from, |word| word)
+    }
+
+    // Index of the first clear bit at or after `from`.
+    pub fn next_clear(&self, from: usize) -> Option<usize> {
+        self.scan(from, |word| !word)
+    }
+
+    pub fn first_set(&self) -> Option<usize> {
+        self.next_set(0)
+    }
+
+    pub fn first_clear(&self) -> Option<usize> {
+        self.next_clear(0)
+    }
+
+    pub fn last_set(&self) -> Option<usize> {
+        let (index, &word) = self
+            .words
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, &word)| word != 0)?;
+        Some(index * BITS + (BITS - 1 - word.leading_zeros() as usize))
+    }
+
+    // Finds the first bit at or after `from` that is set in `map(word)`.
+    fn scan(&self, from: usize, map: impl Fn(usize) -> usize) -> Option<usize> {
+        if from >= self.len {
+            return None;
+        }
+        let mut index = from / BITS;
+        let mut word = map(self.words[index]) & (!0 << (from % BITS));
+        loop {
+            if word != 0 {
+                let bit = index * BITS + word.trailing_zeros() as usize;
+                return (bit < self.len).then_some(bit);
+            }
+            index += 1;
+            word = map(*self.words.get(index)?);
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            bits: self,
+            front: 0,
+            back: self.len,
+        }
+    }
+
+    // Indices of the set bits, in ascending order.
+    pub fn iter_ones(&self) -> Ones<'_> {
+        Ones::new(&self.words)
+    }
+
+    pub fn words(&self) -> core::iter::Copied<core::slice::Iter<'_, usize>> {
+        self.words.as_slice().iter().copied()
+    }
+
+    // The whole-vector operations below need both sides to have the same
+    // length, and leave `self` untouched otherwise.
+    #[inline]
+    fn zip_words(
+        &mut self,
+        other: &ZenBitVec,
+        f: impl Fn(usize, usize) -> usize,
+    ) -> Result<(), ZenError> {
+        if self.len != other.len {
+            return Err(ZenError::InvalidInput("bit vectors differ in length"));
+        }
+        for (word, &other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word = f(*word, other);
+        }
+        Ok(())
+    }
+
+    pub fn union_with(&mut self, other: &ZenBitVec) -> Result<(), ZenError> {
+        self.zip_words(other, |a, b| a | b)
+    }
+
+    pub fn intersect_with(&mut self, other: &ZenBitVec) -> Result<(), ZenError> {
+        self.zip_words(other, |a, b| a & b)
+    }
+
+    pub fn difference_with(&mut self, other: &ZenBitVec) -> Result<(), ZenError> {
+        self.zip_words(other, |a, b| a & !b)
+    }
+
+    pub fn symmetric_difference_with(&mut self, other: &ZenBitVec) -> Result<(), ZenError> {
+        self.zip_words(other, |a, b| a ^ b)
+    }
+}
+
+impl Default for ZenBitVec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryClone for ZenBitVec {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(Self {
+            words: self.words.try_clone()?,
+            len: self.len,
+        })
+    }
+}
+
+impl PartialEq for ZenBitVec {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.words.as_slice() == other.words.as_slice()
+    }
+}
+
+impl Eq for ZenBitVec {}
+
+// Compares bit by bit, like a slice of `bool`s would.
+impl PartialOrd for ZenBitVec {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ZenBitVec {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+// Bits past `len` are always clear, so hashing whole words agrees with `eq`.
+impl Hash for ZenBitVec {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        self.words.as_slice().hash(state);
+    }
+}
+
+impl fmt::Debug for ZenBitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl TryFromIterator<bool> for ZenBitVec {
+    fn try_from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Result<Self, AllocError> {
+        let mut bits = ZenBitVec::new();
+        bits.try_extend(iter)?;
+        Ok(bits)
+    }
+}
+
+impl TryExtend<bool> for ZenBitVec {
+    fn try_extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let iter = iter.into_iter();
+        let original_len = self.len;
+
+        self.try_reserve(iter.size_hint().0)?;
+        for value in iter {
+            if let Err(err) = self.try_push(value) {
+                self.truncate(original_len);
+                return Err(err.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a ZenBitVec {
+    type Item = bool;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a> {
+    bits: &'a ZenBitVec,
+    front: usize,
+    back: usize,
+}
+
+impl Clone for Iter<'_> {
+    fn clone(&self) -> Self {
+        Self {
+            bits: self.bits,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl Iterator for Iter<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.bits.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<bool> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.bits.get(self.back)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+// Walks the set bits of a word slice, one word at a time.
+pub struct Ones<'a> {
+    words: &'a [usize],
+    index: usize,
+    current: usize,
+}
+
+impl<'a> Ones<'a> {
+    pub(crate) fn new(words: &'a [usize]) -> Self {
+        Self {
+            words,
+            index: 0,
+            current: words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl Clone for Ones<'_> {
+    fn clone(&self) -> Self {
+        Self {
+            words: self.words,
+            index: self.index,
+            current: self.current,
+        }
+    }
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        /* Clear the lowest set bit. */
+        self.current &= self.current - 1;
+        Some(self.index * BITS + bit)
+    }
+}
+
+impl FusedIterator for Ones<'_> {}
@@ -1 +1,2 @@
+#[allow(clippy::module_inception)]
 pub mod zen_box;
\ No newline at end of file
@@ -1,6 +1,13 @@
 use core::alloc::{AllocError, Layout};
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::{self, ManuallyDrop};
+use core::pin::Pin;
 use core::ptr::{self, NonNull};
 use crate::alloc_trait::Allocator;
+use crate::try_clone::TryClone;
 use crate::System;
 
 pub struct ZenBox<T> {
@@ -9,18 +16,55 @@ pub struct ZenBox<T> {
 
 impl<T> ZenBox<T> {
     pub fn new(value: T) -> Result<Self, AllocError> {
-        let layout = Layout::new::<T>();
-        let ptr = System.allocate(layout)?;
+        // Zero-sized values need no memory; any aligned pointer will do.
+        let ptr = if mem::size_of::<T>() == 0 {
+            NonNull::dangling()
+        } else {
+            System.allocate(Layout::new::<T>())?.cast()
+        };
         unsafe {
-            ptr::write(ptr.as_ptr() as *mut T, value);
+            ptr::write(ptr.as_ptr(), value);
         }
-        Ok(ZenBox { ptr: ptr.cast() })
+        Ok(ZenBox { ptr })
+    }
+
+    // `Default` can't report allocation failure, so boxes get this instead.
+    pub fn try_default() -> Result<Self, AllocError>
+    where
+        T: Default,
+    {
+        Self::new(T::default())
+    }
+
+    pub fn pin(value: T) -> Result<Pin<Self>, AllocError> {
+        Ok(Self::into_pin(Self::new(value)?))
+    }
+
+    pub fn into_pin(this: Self) -> Pin<Self> {
+        /* The value lives on the heap and never moves while the box owns it. */
+        unsafe { Pin::new_unchecked(this) }
     }
 
+    // Gives up ownership of the value without dropping or freeing it.
+    pub fn into_raw(this: Self) -> *mut T {
+        ManuallyDrop::new(this).ptr.as_ptr()
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must come from `ZenBox::into_raw` and not be used afterwards.
+    pub unsafe fn from_raw(ptr: *mut T) -> Self {
+        ZenBox {
+            ptr: NonNull::new_unchecked(ptr),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn as_ref(&self) -> &T {
         unsafe { self.ptr.as_ref() }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn as_mut(&mut self) -> &mut T {
         unsafe { self.ptr.as_mut() }
     }
@@ -31,11 +75,19 @@ impl<T> Drop for ZenBox<T> {
         let layout = Layout::new::<T>();
         unsafe {
             ptr::drop_in_place(self.ptr.as_ptr());
-            System.deallocate(self.ptr.cast(), layout);
+            if layout.size() != 0 {
+                System.deallocate(self.ptr.cast(), layout);
+            }
         }
     }
 }
 
+impl<T: TryClone> TryClone for ZenBox<T> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        ZenBox::new(self.as_ref().try_clone()?)
+    }
+}
+
 impl<T> core::ops::Deref for ZenBox<T> {
     type Target = T;
 
@@ -50,5 +102,73 @@ impl<T> core::ops::DerefMut for ZenBox<T> {
     }
 }
 
+impl<T: fmt::Debug> fmt::Debug for ZenBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for ZenBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T> fmt::Pointer for ZenBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&(&**self as *const T), f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for ZenBox<T> {
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(&**self, &**other)
+    }
+}
+
+impl<T: Eq> Eq for ZenBox<T> {}
+
+impl<T: PartialOrd> PartialOrd for ZenBox<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord> Ord for ZenBox<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T: Hash> Hash for ZenBox<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T> Borrow<T> for ZenBox<T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T> AsRef<T> for ZenBox<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T> BorrowMut<T> for ZenBox<T> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T> AsMut<T> for ZenBox<T> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
 unsafe impl<T: Send> Send for ZenBox<T> {}
 unsafe impl<T: Sync> Sync for ZenBox<T> {}
@@ -0,0 +1,4 @@
+mod node;
+#[allow(clippy::module_inception)]
+pub mod zen_btree_map;
+pub mod zen_btree_set;
@@ -0,0 +1,428 @@
+use crate::alloc_trait::Allocator;
+use crate::System;
+use core::alloc::{AllocError, Layout};
+use core::mem::MaybeUninit;
+use core::ptr::{self, NonNull};
+
+// Nodes hold between `MIN_LEN` and `CAPACITY` keys; only the root may hold
+// fewer. Internal nodes have one more edge than they have keys.
+pub(super) const B: usize = 6;
+pub(super) const CAPACITY: usize = 2 * B - 1;
+pub(super) const MIN_LEN: usize = B - 1;
+
+pub(super) type NodePtr<K, V> = NonNull<LeafNode<K, V>>;
+
+#[repr(C)]
+pub(super) struct LeafNode<K, V> {
+    parent: Option<NodePtr<K, V>>,
+    parent_idx: u16,
+    len: u16,
+    keys: [MaybeUninit<K>; CAPACITY],
+    vals: [MaybeUninit<V>; CAPACITY],
+}
+
+// An internal node starts with a leaf node, so a pointer to either can be
+// used as a `NodePtr`. The height of a node tells which one it is.
+#[repr(C)]
+struct InternalNode<K, V> {
+    data: LeafNode<K, V>,
+    edges: [MaybeUninit<NodePtr<K, V>>; CAPACITY + 1],
+}
+
+// A position in a node: either the key-value pair at `idx` or the edge at
+// `idx`, depending on where it is used.
+pub(super) struct Handle<K, V> {
+    pub(super) node: NodePtr<K, V>,
+    pub(super) height: usize,
+    pub(super) idx: usize,
+}
+
+impl<K, V> Clone for Handle<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for Handle<K, V> {}
+
+impl<K, V> PartialEq for Handle<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node && self.idx == other.idx
+    }
+}
+
+fn leaf_layout<K, V>() -> Layout {
+    Layout::new::<LeafNode<K, V>>()
+}
+
+fn internal_layout<K, V>() -> Layout {
+    Layout::new::<InternalNode<K, V>>()
+}
+
+fn allocate_node<K, V>(layout: Layout) -> Result<NodePtr<K, V>, AllocError> {
+    let node = System.allocate(layout)?.cast::<LeafNode<K, V>>();
+    unsafe {
+        let raw = node.as_ptr();
+        ptr::addr_of_mut!((*raw).parent).write(None);
+        ptr::addr_of_mut!((*raw).parent_idx).write(0);
+        ptr::addr_of_mut!((*raw).len).write(0);
+    }
+    Ok(node)
+}
+
+pub(super) fn new_leaf<K, V>() -> Result<NodePtr<K, V>, AllocError> {
+    allocate_node(leaf_layout::<K, V>())
+}
+
+pub(super) fn new_internal<K, V>() -> Result<NodePtr<K, V>, AllocError> {
+    allocate_node(internal_layout::<K, V>())
+}
+
+// `node` must have been allocated for the given height, and nothing may
+// point to it any more.
+pub(super) unsafe fn free_node<K, V>(node: NodePtr<K, V>, height: usize) {
+    let layout = if height == 0 {
+        leaf_layout::<K, V>()
+    } else {
+        internal_layout::<K, V>()
+    };
+    System.deallocate(node.cast(), layout);
+}
+
+#[inline]
+pub(super) unsafe fn len<K, V>(node: NodePtr<K, V>) -> usize {
+    (*node.as_ptr()).len as usize
+}
+
+#[inline]
+pub(super) unsafe fn set_len<K, V>(node: NodePtr<K, V>, len: usize) {
+    (*node.as_ptr()).len = len as u16;
+}
+
+#[inline]
+pub(super) unsafe fn parent<K, V>(node: NodePtr<K, V>) -> Option<NodePtr<K, V>> {
+    (*node.as_ptr()).parent
+}
+
+#[inline]
+pub(super) unsafe fn parent_idx<K, V>(node: NodePtr<K, V>) -> usize {
+    (*node.as_ptr()).parent_idx as usize
+}
+
+#[inline]
+pub(super) unsafe fn set_parent<K, V>(node: NodePtr<K, V>, parent: Option<NodePtr<K, V>>) {
+    (*node.as_ptr()).parent = parent;
+}
+
+#[inline]
+pub(super) unsafe fn key<K, V>(node: NodePtr<K, V>, idx: usize) -> *mut K {
+    (ptr::addr_of_mut!((*node.as_ptr()).keys) as *mut K).add(idx)
+}
+
+#[inline]
+pub(super) unsafe fn val<K, V>(node: NodePtr<K, V>, idx: usize) -> *mut V {
+    (ptr::addr_of_mut!((*node.as_ptr()).vals) as *mut V).add(idx)
+}
+
+#[inline]
+unsafe fn edges<K, V>(node: NodePtr<K, V>) -> *mut NodePtr<K, V> {
+    ptr::addr_of_mut!((*node.cast::<InternalNode<K, V>>().as_ptr()).edges) as *mut NodePtr<K, V>
+}
+
+#[inline]
+pub(super) unsafe fn edge<K, V>(node: NodePtr<K, V>, idx: usize) -> NodePtr<K, V> {
+    *edges(node).add(idx)
+}
+
+// Stores `child` at edge `idx` of `node` and points it back at its parent.
+#[inline]
+pub(super) unsafe fn set_edge<K, V>(node: NodePtr<K, V>, idx: usize, child: NodePtr<K, V>) {
+    *edges(node).add(idx) = child;
+    (*child.as_ptr()).parent = Some(node);
+    (*child.as_ptr()).parent_idx = idx as u16;
+}
+
+unsafe fn correct_parent_links<K, V>(node: NodePtr<K, V>, from: usize, to: usize) {
+    for idx in from..=to {
+        set_edge(node, idx, edge(node, idx));
+    }
+}
+
+unsafe fn slice_insert<T>(base: *mut T, len: usize, idx: usize, value: T) {
+    ptr::copy(base.add(idx), base.add(idx + 1), len - idx);
+    base.add(idx).write(value);
+}
+
+unsafe fn slice_remove<T>(base: *mut T, len: usize, idx: usize) -> T {
+    let value = base.add(idx).read();
+    ptr::copy(base.add(idx + 1), base.add(idx), len - idx - 1);
+    value
+}
+
+// Inserts a pair at `idx` of a node with room for it.
+pub(super) unsafe fn insert_fit<K, V>(node: NodePtr<K, V>, idx: usize, k: K, v: V) {
+    let len = len(node);
+    slice_insert(key(node, 0), len, idx, k);
+    slice_insert(val(node, 0), len, idx, v);
+    set_len(node, len + 1);
+}
+
+// Inserts a pair at `idx` of an internal node with room for it, with
+// `right` as the edge following the pair.
+pub(super) unsafe fn insert_fit_edge<K, V>(
+    node: NodePtr<K, V>,
+    idx: usize,
+    k: K,
+    v: V,
+    right: NodePtr<K, V>,
+) {
+    let len = len(node);
+    insert_fit(node, idx, k, v);
+    slice_insert(edges(node), len + 1, idx + 1, right);
+    correct_parent_links(node, idx + 1, len + 1);
+}
+
+// Removes the pair at `idx`. For internal nodes the edge after it is
+// removed as well and returned.
+pub(super) unsafe fn remove_kv<K, V>(
+    node: NodePtr<K, V>,
+    height: usize,
+    idx: usize,
+) -> (K, V, Option<NodePtr<K, V>>) {
+    let len = len(node);
+    let k = slice_remove(key(node, 0), len, idx);
+    let v = slice_remove(val(node, 0), len, idx);
+    let edge = if height > 0 {
+        let edge = slice_remove(edges(node), len + 1, idx + 1);
+        correct_parent_links(node, idx + 1, len - 1);
+        Some(edge)
+    } else {
+        None
+    };
+    set_len(node, len - 1);
+    (k, v, edge)
+}
+
+// Chooses where to split a full node when inserting at edge `idx`: the
+// index of the pair moving up, and whether the insertion then goes into
+// the left or right half, at which index.
+pub(super) fn splitpoint(idx: usize) -> (usize, bool, usize) {
+    if idx < B - 1 {
+        (B - 2, true, idx)
+    } else if idx == B - 1 {
+        (B - 1, true, idx)
+    } else if idx == B {
+        (B - 1, false, 0)
+    } else {
+        (B, false, idx - (B + 1))
+    }
+}
+
+// Moves everything after the pair at `mid` into the empty node `right`
+// and takes the pair at `mid` out.
+pub(super) unsafe fn split<K, V>(
+    node: NodePtr<K, V>,
+    height: usize,
+    mid: usize,
+    right: NodePtr<K, V>,
+) -> (K, V) {
+    let len = len(node);
+    let new_len = len - mid - 1;
+    let k = key(node, mid).read();
+    let v = val(node, mid).read();
+    ptr::copy_nonoverlapping(key(node, mid + 1), key(right, 0), new_len);
+    ptr::copy_nonoverlapping(val(node, mid + 1), val(right, 0), new_len);
+    if height > 0 {
+        ptr::copy_nonoverlapping(edges(node).add(mid + 1), edges(right), new_len + 1);
+        correct_parent_links(right, 0, new_len);
+    }
+    set_len(node, mid);
+    set_len(right, new_len);
+    (k, v)
+}
+
+// Moves the pairs from `idx` on, and the edges after them, into the empty
+// node `right`. Edge 0 of an internal `right` is left for the caller.
+pub(super) unsafe fn cut_right<K, V>(
+    node: NodePtr<K, V>,
+    height: usize,
+    idx: usize,
+    right: NodePtr<K, V>,
+) {
+    let len = len(node);
+    let new_len = len - idx;
+    ptr::copy_nonoverlapping(key(node, idx), key(right, 0), new_len);
+    ptr::copy_nonoverlapping(val(node, idx), val(right, 0), new_len);
+    if height > 0 {
+        ptr::copy_nonoverlapping(edges(node).add(idx + 1), edges(right).add(1), new_len);
+        if new_len > 0 {
+            correct_parent_links(right, 1, new_len);
+        }
+    }
+    set_len(node, idx);
+    set_len(right, new_len);
+}
+
+// Merges the child right of pair `idx` of `parent` into the child left of
+// it, pulling the pair down between them. The right child is freed.
+pub(super) unsafe fn merge<K, V>(
+    parent: NodePtr<K, V>,
+    parent_height: usize,
+    idx: usize,
+) -> NodePtr<K, V> {
+    let left = edge(parent, idx);
+    let right = edge(parent, idx + 1);
+    let left_len = len(left);
+    let right_len = len(right);
+    let (k, v, _) = remove_kv(parent, parent_height, idx);
+
+    key(left, left_len).write(k);
+    val(left, left_len).write(v);
+    ptr::copy_nonoverlapping(key(right, 0), key(left, left_len + 1), right_len);
+    ptr::copy_nonoverlapping(val(right, 0), val(left, left_len + 1), right_len);
+    if parent_height > 1 {
+        ptr::copy_nonoverlapping(edges(right), edges(left).add(left_len + 1), right_len + 1);
+        correct_parent_links(left, left_len + 1, left_len + 1 + right_len);
+    }
+    set_len(left, left_len + 1 + right_len);
+    free_node(right, parent_height - 1);
+    left
+}
+
+// Moves the last pair of the child left of pair `idx` through the parent
+// into the front of the child right of it.
+pub(super) unsafe fn steal_left<K, V>(parent: NodePtr<K, V>, parent_height: usize, idx: usize) {
+    let left = edge(parent, idx);
+    let right = edge(parent, idx + 1);
+    let left_len = len(left);
+    let right_len = len(right);
+
+    let k = key(left, left_len - 1).read();
+    let v = val(left, left_len - 1).read();
+    let k = ptr::replace(key(parent, idx), k);
+    let v = ptr::replace(val(parent, idx), v);
+    slice_insert(key(right, 0), right_len, 0, k);
+    slice_insert(val(right, 0), right_len, 0, v);
+    if parent_height > 1 {
+        let child = edge(left, left_len);
+        slice_insert(edges(right), right_len + 1, 0, child);
+        correct_parent_links(right, 0, right_len + 1);
+    }
+    set_len(left, left_len - 1);
+    set_len(right, right_len + 1);
+}
+
+// Moves the first pair of the child right of pair `idx` through the parent
+// onto the end of the child left of it.
+pub(super) unsafe fn steal_right<K, V>(parent: NodePtr<K, V>, parent_height: usize, idx: usize) {
+    let left = edge(parent, idx);
+    let right = edge(parent, idx + 1);
+    let left_len = len(left);
+    let right_len = len(right);
+
+    let k = slice_remove(key(right, 0), right_len, 0);
+    let v = slice_remove(val(right, 0), right_len, 0);
+    let k = ptr::replace(key(parent, idx), k);
+    let v = ptr::replace(val(parent, idx), v);
+    key(left, left_len).write(k);
+    val(left, left_len).write(v);
+    if parent_height > 1 {
+        let child = slice_remove(edges(right), right_len + 1, 0);
+        set_edge(left, left_len + 1, child);
+        correct_parent_links(right, 0, right_len - 1);
+    }
+    set_len(left, left_len + 1);
+    set_len(right, right_len - 1);
+}
+
+pub(super) unsafe fn first_leaf_edge<K, V>(mut node: NodePtr<K, V>, mut height: usize) -> Handle<K, V> {
+    while height > 0 {
+        node = edge(node, 0);
+        height -= 1;
+    }
+    Handle { node, height: 0, idx: 0 }
+}
+
+pub(super) unsafe fn last_leaf_edge<K, V>(mut node: NodePtr<K, V>, mut height: usize) -> Handle<K, V> {
+    while height > 0 {
+        node = edge(node, len(node));
+        height -= 1;
+    }
+    Handle {
+        node,
+        height: 0,
+        idx: len(node),
+    }
+}
+
+// The pair right of an edge, climbing up while the edge is the last one
+// of its node.
+pub(super) unsafe fn next_kv<K, V>(mut edge: Handle<K, V>) -> Option<Handle<K, V>> {
+    while edge.idx >= len(edge.node) {
+        edge = Handle {
+            idx: parent_idx(edge.node),
+            node: parent(edge.node)?,
+            height: edge.height + 1,
+        };
+    }
+    Some(edge)
+}
+
+// The pair left of an edge, climbing up while the edge is the first one
+// of its node.
+pub(super) unsafe fn next_back_kv<K, V>(mut edge: Handle<K, V>) -> Option<Handle<K, V>> {
+    while edge.idx == 0 {
+        edge = Handle {
+            idx: parent_idx(edge.node),
+            node: parent(edge.node)?,
+            height: edge.height + 1,
+        };
+    }
+    edge.idx -= 1;
+    Some(edge)
+}
+
+// The leaf edge directly right of a pair.
+pub(super) unsafe fn right_leaf_edge<K, V>(kv: Handle<K, V>) -> Handle<K, V> {
+    if kv.height == 0 {
+        Handle {
+            idx: kv.idx + 1,
+            ..kv
+        }
+    } else {
+        first_leaf_edge(edge(kv.node, kv.idx + 1), kv.height - 1)
+    }
+}
+
+// The leaf edge directly left of a pair.
+pub(super) unsafe fn left_leaf_edge<K, V>(kv: Handle<K, V>) -> Handle<K, V> {
+    if kv.height == 0 {
+        kv
+    } else {
+        last_leaf_edge(edge(kv.node, kv.idx), kv.height - 1)
+    }
+}
+
+// Drops every pair below `node` and frees the nodes.
+pub(super) unsafe fn drop_subtree<K, V>(node: NodePtr<K, V>, height: usize) {
+    let len = len(node);
+    for idx in 0..len {
+        ptr::drop_in_place(key(node, idx));
+        ptr::drop_in_place(val(node, idx));
+    }
+    if height > 0 {
+        for idx in 0..=len {
+            drop_subtree(edge(node, idx), height - 1);
+        }
+    }
+    free_node(node, height);
+}
+
+// Number of pairs below `node`.
+pub(super) unsafe fn subtree_len<K, V>(node: NodePtr<K, V>, height: usize) -> usize {
+    let len = len(node);
+    if height == 0 {
+        return len;
+    }
+    (0..=len).fold(len, |sum, idx| sum + subtree_len(edge(node, idx), height - 1))
+}
@@ -0,0 +1,1404 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_btree_map::node::{self, Handle, NodePtr, CAPACITY, MIN_LEN};
+use crate::zen_vec::zen_vec::ZenVec;
+use core::{
+    alloc::AllocError,
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem,
+    ops::{Bound, RangeBounds},
+    ptr,
+};
+
+// An ordered map stored in a B-tree. Nodes are allocated through
+// `alloc_trait::Allocator`, and every node an insertion may need is
+// allocated before the tree is touched, so a failed allocation leaves the
+// map exactly as it was.
+pub struct ZenBTreeMap<K, V> {
+    root: Option<NodePtr<K, V>>,
+    height: usize,
+    length: usize,
+    marker: PhantomData<(K, V)>,
+}
+
+unsafe impl<K: Send, V: Send> Send for ZenBTreeMap<K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for ZenBTreeMap<K, V> {}
+
+enum Search<K, V> {
+    Found(Handle<K, V>),
+    GoDown(Handle<K, V>),
+}
+
+unsafe fn search_tree<K, V, Q>(mut node: NodePtr<K, V>, mut height: usize, key: &Q) -> Search<K, V>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    loop {
+        let len = node::len(node);
+        let mut idx = 0;
+        while idx < len {
+            match key.cmp((*node::key(node, idx)).borrow()) {
+                Ordering::Greater => idx += 1,
+                Ordering::Equal => return Search::Found(Handle { node, height, idx }),
+                Ordering::Less => break,
+            }
+        }
+        if height == 0 {
+            return Search::GoDown(Handle { node, height, idx });
+        }
+        node = node::edge(node, idx);
+        height -= 1;
+    }
+}
+
+// The leaf edge where a range with this bound starts (`upper == false`) or
+// ends (`upper == true`).
+unsafe fn bound_leaf_edge<K, V, Q>(
+    mut node: NodePtr<K, V>,
+    mut height: usize,
+    bound: Bound<&Q>,
+    upper: bool,
+) -> Handle<K, V>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    loop {
+        let len = node::len(node);
+        let mut idx = 0;
+        while idx < len {
+            let key = (*node::key(node, idx)).borrow();
+            let before_bound = match bound {
+                Bound::Unbounded => upper,
+                Bound::Included(bound) if upper => key <= bound,
+                Bound::Included(bound) => key < bound,
+                Bound::Excluded(bound) if upper => key < bound,
+                Bound::Excluded(bound) => key <= bound,
+            };
+            if !before_bound {
+                break;
+            }
+            idx += 1;
+        }
+        if height == 0 {
+            return Handle { node, height, idx };
+        }
+        node = node::edge(node, idx);
+        height -= 1;
+    }
+}
+
+// Nodes allocated ahead of an insertion. Spare nodes of each kind are
+// chained through their parent links.
+struct NodePool<K, V> {
+    leaves: Option<NodePtr<K, V>>,
+    internals: Option<NodePtr<K, V>>,
+}
+
+impl<K, V> NodePool<K, V> {
+    fn new() -> Self {
+        Self {
+            leaves: None,
+            internals: None,
+        }
+    }
+
+    fn push_leaf(&mut self) -> Result<(), AllocError> {
+        let node = node::new_leaf()?;
+        unsafe { node::set_parent(node, self.leaves) };
+        self.leaves = Some(node);
+        Ok(())
+    }
+
+    fn push_internal(&mut self) -> Result<(), AllocError> {
+        let node = node::new_internal()?;
+        unsafe { node::set_parent(node, self.internals) };
+        self.internals = Some(node);
+        Ok(())
+    }
+
+    // The caller must have counted the nodes it needs.
+    unsafe fn take_leaf(&mut self) -> NodePtr<K, V> {
+        Self::take(&mut self.leaves)
+    }
+
+    unsafe fn take_internal(&mut self) -> NodePtr<K, V> {
+        Self::take(&mut self.internals)
+    }
+
+    unsafe fn take(chain: &mut Option<NodePtr<K, V>>) -> NodePtr<K, V> {
+        debug_assert!(chain.is_some(), "node pool ran out");
+        let node = chain.unwrap_unchecked();
+        *chain = node::parent(node);
+        node::set_parent(node, None);
+        node
+    }
+}
+
+impl<K, V> Drop for NodePool<K, V> {
+    fn drop(&mut self) {
+        unsafe {
+            while self.leaves.is_some() {
+                let node = self.take_leaf();
+                node::free_node(node, 0);
+            }
+            while self.internals.is_some() {
+                let node = self.take_internal();
+                node::free_node(node, 1);
+            }
+        }
+    }
+}
+
+enum Prepared<K, V> {
+    Found(Handle<K, V>),
+    Vacant(Option<Handle<K, V>>, NodePool<K, V>),
+}
+
+// Leaf edges delimiting a run of pairs, shared by all the iterators.
+struct LeafRange<K, V> {
+    front: Option<Handle<K, V>>,
+    back: Option<Handle<K, V>>,
+}
+
+impl<K, V> Clone for LeafRange<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<K, V> LeafRange<K, V> {
+    fn none() -> Self {
+        Self {
+            front: None,
+            back: None,
+        }
+    }
+
+    fn full(root: Option<NodePtr<K, V>>, height: usize) -> Self {
+        match root {
+            Some(root) => unsafe {
+                Self {
+                    front: Some(node::first_leaf_edge(root, height)),
+                    back: Some(node::last_leaf_edge(root, height)),
+                }
+            },
+            None => Self::none(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.front == self.back
+    }
+
+    unsafe fn next_kv(&mut self) -> Option<Handle<K, V>> {
+        if self.is_empty() {
+            return None;
+        }
+        let kv = node::next_kv(self.front?)?;
+        self.front = Some(node::right_leaf_edge(kv));
+        Some(kv)
+    }
+
+    unsafe fn next_back_kv(&mut self) -> Option<Handle<K, V>> {
+        if self.is_empty() {
+            return None;
+        }
+        let kv = node::next_back_kv(self.back?)?;
+        self.back = Some(node::left_leaf_edge(kv));
+        Some(kv)
+    }
+}
+
+unsafe fn key_value<'a, K, V>(kv: Handle<K, V>) -> (&'a K, &'a V) {
+    (&*node::key(kv.node, kv.idx), &*node::val(kv.node, kv.idx))
+}
+
+unsafe fn key_value_mut<'a, K, V>(kv: Handle<K, V>) -> (&'a K, &'a mut V) {
+    (&*node::key(kv.node, kv.idx), &mut *node::val(kv.node, kv.idx))
+}
+
+impl<K, V> ZenBTreeMap<K, V> {
+    pub const fn new() -> Self {
+        Self {
+            root: None,
+            height: 0,
+            length: 0,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn clear(&mut self) {
+        drop(mem::take(self));
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            range: LeafRange::full(self.root, self.height),
+            length: self.length,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            range: LeafRange::full(self.root, self.height),
+            length: self.length,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            inner: self.iter_mut(),
+        }
+    }
+
+    fn first_kv(&self) -> Option<Handle<K, V>> {
+        unsafe { node::next_kv(node::first_leaf_edge(self.root?, self.height)) }
+    }
+
+    fn last_kv(&self) -> Option<Handle<K, V>> {
+        unsafe { node::next_back_kv(node::last_leaf_edge(self.root?, self.height)) }
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.first_kv().map(|kv| unsafe { key_value(kv) })
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.last_kv().map(|kv| unsafe { key_value(kv) })
+    }
+
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let kv = self.first_kv()?;
+        Some(unsafe { self.remove_kv(kv) })
+    }
+
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let kv = self.last_kv()?;
+        Some(unsafe { self.remove_kv(kv) })
+    }
+
+    // Removes a pair, taking its in-order predecessor from a leaf when the
+    // pair sits in an internal node, then rebalances upwards.
+    unsafe fn remove_kv(&mut self, kv: Handle<K, V>) -> (K, V) {
+        self.length -= 1;
+        if kv.height == 0 {
+            let (k, v, _) = node::remove_kv(kv.node, 0, kv.idx);
+            self.fix_underflow(kv.node, 0);
+            return (k, v);
+        }
+
+        let leaf = node::left_leaf_edge(kv);
+        let (pred_k, pred_v, _) = node::remove_kv(leaf.node, 0, leaf.idx - 1);
+        let k = ptr::replace(node::key(kv.node, kv.idx), pred_k);
+        let v = ptr::replace(node::val(kv.node, kv.idx), pred_v);
+        self.fix_underflow(leaf.node, 0);
+        (k, v)
+    }
+
+    // Restores the minimum node length on the path from `node` to the root
+    // by stealing from or merging with a sibling. Never allocates.
+    unsafe fn fix_underflow(&mut self, mut node: NodePtr<K, V>, mut height: usize) {
+        loop {
+            let len = node::len(node);
+            let Some(parent) = node::parent(node) else {
+                if len == 0 {
+                    self.pop_root_level();
+                }
+                return;
+            };
+            if len >= MIN_LEN {
+                return;
+            }
+
+            let idx = node::parent_idx(node);
+            let parent_height = height + 1;
+            if idx > 0 {
+                let left = node::edge(parent, idx - 1);
+                if node::len(left) + len < CAPACITY {
+                    node::merge(parent, parent_height, idx - 1);
+                } else {
+                    node::steal_left(parent, parent_height, idx - 1);
+                    return;
+                }
+            } else {
+                let right = node::edge(parent, 1);
+                if len + node::len(right) < CAPACITY {
+                    node::merge(parent, parent_height, 0);
+                } else {
+                    node::steal_right(parent, parent_height, 0);
+                    return;
+                }
+            }
+            node = parent;
+            height = parent_height;
+        }
+    }
+
+    // Replaces an empty root by its only child, or frees an empty leaf root.
+    unsafe fn pop_root_level(&mut self) {
+        let root = self.root.unwrap_unchecked();
+        if self.height == 0 {
+            node::free_node(root, 0);
+            self.root = None;
+        } else {
+            let child = node::edge(root, 0);
+            node::set_parent(child, None);
+            node::free_node(root, self.height);
+            self.root = Some(child);
+            self.height -= 1;
+        }
+    }
+
+    // Gives every node on the right border of the tree more than the
+    // minimum length, after `split_off` cut through it.
+    unsafe fn fix_right_border(&mut self) {
+        let Some(mut node) = self.root else {
+            return;
+        };
+        let mut height = self.height;
+        loop {
+            if node::len(node) == 0 && node::parent(node).is_none() {
+                self.pop_root_level();
+                match self.root {
+                    Some(root) => node = root,
+                    None => return,
+                }
+                height = self.height;
+                continue;
+            }
+            if height == 0 {
+                return;
+            }
+
+            let last = node::len(node);
+            let sibling = node::edge(node, last - 1);
+            let mut child = node::edge(node, last);
+            if node::len(sibling) + node::len(child) < CAPACITY {
+                child = node::merge(node, height, last - 1);
+            } else {
+                while node::len(child) <= MIN_LEN {
+                    node::steal_left(node, height, last - 1);
+                }
+            }
+            if node::len(node) > 0 {
+                node = child;
+                height -= 1;
+            }
+        }
+    }
+
+    // The mirror image of `fix_right_border`, for the new map's left border.
+    unsafe fn fix_left_border(&mut self) {
+        let Some(mut node) = self.root else {
+            return;
+        };
+        let mut height = self.height;
+        loop {
+            if node::len(node) == 0 && node::parent(node).is_none() {
+                self.pop_root_level();
+                match self.root {
+                    Some(root) => node = root,
+                    None => return,
+                }
+                height = self.height;
+                continue;
+            }
+            if height == 0 {
+                return;
+            }
+
+            let child = node::edge(node, 0);
+            let sibling = node::edge(node, 1);
+            if node::len(child) + node::len(sibling) < CAPACITY {
+                node::merge(node, height, 0);
+            } else {
+                while node::len(child) <= MIN_LEN {
+                    node::steal_right(node, height, 0);
+                }
+            }
+            if node::len(node) > 0 {
+                node = child;
+                height -= 1;
+            }
+        }
+    }
+}
+
+impl<K: Ord, V> ZenBTreeMap<K, V> {
+    fn find<Q>(&self, key: &Q) -> Option<Handle<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match unsafe { search_tree(self.root?, self.height, key) } {
+            Search::Found(kv) => Some(kv),
+            Search::GoDown(_) => None,
+        }
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).map(|kv| unsafe { key_value(kv) })
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).map(|kv| unsafe { &mut *node::val(kv.node, kv.idx) })
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    // Finds where `key` goes and allocates every node that inserting it
+    // could need: one per full node on the path up, plus a new root if the
+    // root is full too.
+    fn prepare_insert(&self, key: &K) -> Result<Prepared<K, V>, AllocError> {
+        let mut pool = NodePool::new();
+        let Some(root) = self.root else {
+            pool.push_leaf()?;
+            return Ok(Prepared::Vacant(None, pool));
+        };
+
+        let edge = match unsafe { search_tree(root, self.height, key) } {
+            Search::Found(kv) => return Ok(Prepared::Found(kv)),
+            Search::GoDown(edge) => edge,
+        };
+
+        let mut node = edge.node;
+        unsafe {
+            while node::len(node) == CAPACITY {
+                if node == edge.node {
+                    pool.push_leaf()?;
+                } else {
+                    pool.push_internal()?;
+                }
+                match node::parent(node) {
+                    Some(parent) => node = parent,
+                    None => {
+                        pool.push_internal()?;
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(Prepared::Vacant(Some(edge), pool))
+    }
+
+    // Inserts at a leaf edge found by `prepare_insert`, splitting full
+    // nodes with the nodes it allocated. Returns the new value's address.
+    unsafe fn insert_prepared(
+        &mut self,
+        edge: Option<Handle<K, V>>,
+        pool: &mut NodePool<K, V>,
+        key: K,
+        value: V,
+    ) -> *mut V {
+        self.length += 1;
+        let Some(edge) = edge else {
+            let leaf = pool.take_leaf();
+            node::insert_fit(leaf, 0, key, value);
+            self.root = Some(leaf);
+            self.height = 0;
+            return node::val(leaf, 0);
+        };
+
+        if node::len(edge.node) < CAPACITY {
+            node::insert_fit(edge.node, edge.idx, key, value);
+            return node::val(edge.node, edge.idx);
+        }
+
+        let (mid, go_left, idx) = node::splitpoint(edge.idx);
+        let mut left = edge.node;
+        let mut right = pool.take_leaf();
+        let (mut up_k, mut up_v) = node::split(left, 0, mid, right);
+        let target = if go_left { left } else { right };
+        node::insert_fit(target, idx, key, value);
+        let inserted = node::val(target, idx);
+
+        let mut height = 0;
+        loop {
+            let Some(parent) = node::parent(left) else {
+                let root = pool.take_internal();
+                node::key(root, 0).write(up_k);
+                node::val(root, 0).write(up_v);
+                node::set_len(root, 1);
+                node::set_edge(root, 0, left);
+                node::set_edge(root, 1, right);
+                self.root = Some(root);
+                self.height += 1;
+                return inserted;
+            };
+            let parent_idx = node::parent_idx(left);
+            height += 1;
+
+            if node::len(parent) < CAPACITY {
+                node::insert_fit_edge(parent, parent_idx, up_k, up_v, right);
+                return inserted;
+            }
+
+            let (mid, go_left, idx) = node::splitpoint(parent_idx);
+            let new_right = pool.take_internal();
+            let (k, v) = node::split(parent, height, mid, new_right);
+            let target = if go_left { parent } else { new_right };
+            node::insert_fit_edge(target, idx, up_k, up_v, right);
+            (up_k, up_v) = (k, v);
+            left = parent;
+            right = new_right;
+        }
+    }
+
+    // Inserts a key-value pair, returning the previous value for `key`.
+    // On allocation failure the map is unchanged and the pair is dropped.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, AllocError> {
+        match self.prepare_insert(&key)? {
+            Prepared::Found(kv) => unsafe {
+                Ok(Some(mem::replace(&mut *node::val(kv.node, kv.idx), value)))
+            },
+            Prepared::Vacant(edge, mut pool) => {
+                unsafe { self.insert_prepared(edge, &mut pool, key, value) };
+                Ok(None)
+            }
+        }
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let kv = self.find(key)?;
+        Some(unsafe { self.remove_kv(kv) })
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut cursor = self.lower_bound_mut::<K>(Bound::Unbounded);
+        while let Some((key, value)) = cursor.key_value_mut() {
+            if f(key, value) {
+                cursor.move_next();
+            } else {
+                cursor.remove_current();
+            }
+        }
+    }
+
+    fn leaf_range<Q, R>(&self, range: R) -> LeafRange<K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let Some(root) = self.root else {
+            return LeafRange::none();
+        };
+
+        let (start, end) = (range.start_bound(), range.end_bound());
+        match (start, end) {
+            (Bound::Excluded(s), Bound::Excluded(e)) if s == e => return LeafRange::none(),
+            (
+                Bound::Included(s) | Bound::Excluded(s),
+                Bound::Included(e) | Bound::Excluded(e),
+            ) if s > e => return LeafRange::none(),
+            _ => {}
+        }
+
+        unsafe {
+            LeafRange {
+                front: Some(bound_leaf_edge(root, self.height, start, false)),
+                back: Some(bound_leaf_edge(root, self.height, end, true)),
+            }
+        }
+    }
+
+    // Iterates over the pairs whose keys fall within `range`. An inverted
+    // range yields nothing instead of panicking.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Range {
+            range: self.leaf_range(range),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn range_mut<Q, R>(&mut self, range: R) -> RangeMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        RangeMut {
+            range: self.leaf_range(range),
+            marker: PhantomData,
+        }
+    }
+
+    fn lower_bound_kv<Q>(&self, bound: Bound<&Q>) -> Option<Handle<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        unsafe { node::next_kv(bound_leaf_edge(self.root?, self.height, bound, false)) }
+    }
+
+    fn upper_bound_kv<Q>(&self, bound: Bound<&Q>) -> Option<Handle<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        unsafe { node::next_back_kv(bound_leaf_edge(self.root?, self.height, bound, true)) }
+    }
+
+    // A cursor at the first pair above `bound`, or at the ghost position
+    // if there is none.
+    pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Cursor<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Cursor {
+            current: self.lower_bound_kv(bound),
+            map: self,
+        }
+    }
+
+    // A cursor at the last pair below `bound`, or at the ghost position if
+    // there is none.
+    pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Cursor<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Cursor {
+            current: self.upper_bound_kv(bound),
+            map: self,
+        }
+    }
+
+    pub fn lower_bound_mut<Q>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        CursorMut {
+            current: self.lower_bound_kv(bound),
+            map: self,
+        }
+    }
+
+    pub fn upper_bound_mut<Q>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        CursorMut {
+            current: self.upper_bound_kv(bound),
+            map: self,
+        }
+    }
+
+    // Splits the map at `key`, returning everything from `key` on. The
+    // nodes for the new map's spine are allocated first, so on failure the
+    // map is unchanged.
+    pub fn split_off<Q>(&mut self, key: &Q) -> Result<Self, AllocError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let Some(root) = self.root else {
+            return Ok(Self::new());
+        };
+
+        let mut spine = NodePool::new();
+        spine.push_leaf()?;
+        for _ in 0..self.height {
+            spine.push_internal()?;
+        }
+
+        let mut right = Self {
+            root: None,
+            height: self.height,
+            length: 0,
+            marker: PhantomData,
+        };
+        unsafe {
+            let mut node = root;
+            let mut height = self.height;
+            let mut right_parent = None;
+            loop {
+                let len = node::len(node);
+                let mut idx = 0;
+                while idx < len && (*node::key(node, idx)).borrow() < key {
+                    idx += 1;
+                }
+
+                let right_node = if height == 0 {
+                    spine.take_leaf()
+                } else {
+                    spine.take_internal()
+                };
+                node::cut_right(node, height, idx, right_node);
+                match right_parent {
+                    Some(parent) => node::set_edge(parent, 0, right_node),
+                    None => right.root = Some(right_node),
+                }
+                if height == 0 {
+                    break;
+                }
+                right_parent = Some(right_node);
+                node = node::edge(node, idx);
+                height -= 1;
+            }
+
+            self.fix_right_border();
+            right.fix_left_border();
+            right.length = right.root.map_or(0, |root| node::subtree_len(root, right.height));
+        }
+        self.length -= right.length;
+        Ok(right)
+    }
+
+    // Moves every pair of `other` into `self`, replacing the values of keys
+    // present in both. The nodes are allocated first, so on failure both
+    // maps are unchanged.
+    pub fn append(&mut self, other: &mut Self) -> Result<(), AllocError> {
+        if self.is_empty() {
+            mem::swap(self, other);
+            return Ok(());
+        }
+
+        let mut pool = self.reserve_for(other)?;
+        for (key, value) in mem::take(other) {
+            let root = unsafe { self.root.unwrap_unchecked() };
+            match unsafe { search_tree(root, self.height, &key) } {
+                Search::Found(kv) => unsafe { *node::val(kv.node, kv.idx) = value },
+                Search::GoDown(edge) => unsafe {
+                    self.insert_prepared(Some(edge), &mut pool, key, value);
+                },
+            }
+        }
+        Ok(())
+    }
+
+    // Allocates every node that moving the pairs of `other` into `self`
+    // could need. A split turns a full node and the key coming in into two
+    // nodes with a single key above `MIN_LEN` between them, so each split
+    // uses up `CAPACITY - MIN_LEN` of the keys above `MIN_LEN` that a level
+    // held on the insertion paths or was sent. Every level the root splits
+    // at also needs a new root.
+    fn reserve_for(&self, other: &Self) -> Result<NodePool<K, V>, AllocError> {
+        let mut pool = NodePool::new();
+        let Some(root) = self.root else {
+            return Ok(pool);
+        };
+
+        let mut spare = ZenVec::try_from_elem(0, self.height + 1)?;
+        let mut incoming = 0;
+        let mut prev_leaf = None;
+        for key in other.keys() {
+            let Search::GoDown(edge) = (unsafe { search_tree(root, self.height, key) }) else {
+                continue;
+            };
+            incoming += 1;
+
+            /* Ascending keys share the top of their paths; count each node once. */
+            let mut node = Some(edge.node);
+            let mut prev = prev_leaf;
+            let mut height = 0;
+            while let Some(current) = node {
+                if Some(current) == prev {
+                    break;
+                }
+                unsafe {
+                    spare[height] += node::len(current).saturating_sub(MIN_LEN);
+                    node = node::parent(current);
+                    prev = prev.and_then(|p| node::parent(p));
+                }
+                height += 1;
+            }
+            prev_leaf = Some(edge.node);
+        }
+
+        let mut height = 0;
+        while incoming > 0 {
+            let held = spare.get(height).copied().unwrap_or(0);
+            let splits = (held + incoming) / (CAPACITY - MIN_LEN);
+            for _ in 0..splits {
+                if height == 0 {
+                    pool.push_leaf()?;
+                } else {
+                    pool.push_internal()?;
+                }
+            }
+            if splits > 0 && height >= self.height {
+                pool.push_internal()?;
+            }
+            incoming = splits;
+            height += 1;
+        }
+        Ok(pool)
+    }
+}
+
+#[cfg(test)]
+impl<K: Ord, V> ZenBTreeMap<K, V> {
+    // Checks node lengths, parent links, key order and the element count.
+    pub(crate) fn assert_valid(&self) {
+        unsafe fn check<K: Ord, V>(node: NodePtr<K, V>, height: usize, is_root: bool) -> usize {
+            let len = node::len(node);
+            assert!(len <= CAPACITY);
+            assert!(is_root || len >= MIN_LEN, "underfull node");
+            for idx in 1..len {
+                assert!(*node::key(node, idx - 1) < *node::key(node, idx));
+            }
+            if height == 0 {
+                return len;
+            }
+            let mut count = len;
+            for idx in 0..=len {
+                let child = node::edge(node, idx);
+                assert!(node::parent(child) == Some(node));
+                assert_eq!(node::parent_idx(child), idx);
+                count += check(child, height - 1, false);
+            }
+            count
+        }
+
+        match self.root {
+            Some(root) => unsafe {
+                assert!(node::parent(root).is_none());
+                assert!(node::len(root) > 0);
+                assert_eq!(check(root, self.height, true), self.length);
+                assert!(self.iter().zip(self.iter().skip(1)).all(|(a, b)| a.0 < b.0));
+            },
+            None => assert_eq!(self.length, 0),
+        }
+    }
+}
+
+impl<K, V> Drop for ZenBTreeMap<K, V> {
+    fn drop(&mut self) {
+        if let Some(root) = self.root.take() {
+            unsafe { node::drop_subtree(root, self.height) };
+        }
+    }
+}
+
+impl<K, V> Default for ZenBTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + TryClone, V: TryClone> TryClone for ZenBTreeMap<K, V> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut map = ZenBTreeMap::new();
+        for (key, value) in self.iter() {
+            map.try_insert(key.try_clone()?, value.try_clone()?)?;
+        }
+        Ok(map)
+    }
+}
+
+impl<K: Ord, V> TryFromIterator<(K, V)> for ZenBTreeMap<K, V> {
+    fn try_from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Result<Self, AllocError> {
+        let mut map = ZenBTreeMap::new();
+        map.try_extend(iter)?;
+        Ok(map)
+    }
+}
+
+// The pairs are gathered in a scratch tree first and moved over only once
+// everything they need is allocated, so a failed extend leaves the map
+// unchanged.
+impl<K: Ord, V> TryExtend<(K, V)> for ZenBTreeMap<K, V> {
+    fn try_extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let mut scratch = ZenBTreeMap::new();
+        for (key, value) in iter {
+            scratch.try_insert(key, value)?;
+        }
+        self.append(&mut scratch)
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for ZenBTreeMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for ZenBTreeMap<K, V> {}
+
+impl<K: PartialOrd, V: PartialOrd> PartialOrd for ZenBTreeMap<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K: Ord, V: Ord> Ord for ZenBTreeMap<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K: Hash, V: Hash> Hash for ZenBTreeMap<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        self.iter().for_each(|pair| pair.hash(state));
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ZenBTreeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> IntoIterator for ZenBTreeMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter { map: self }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a ZenBTreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut ZenBTreeMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+// A read-only position in the map: a pair, or the ghost position between
+// the last and the first pair.
+pub struct Cursor<'a, K, V> {
+    current: Option<Handle<K, V>>,
+    map: &'a ZenBTreeMap<K, V>,
+}
+
+impl<K, V> Clone for Cursor<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current,
+            map: self.map,
+        }
+    }
+}
+
+impl<'a, K, V> Cursor<'a, K, V> {
+    pub fn key(&self) -> Option<&'a K> {
+        self.key_value().map(|(key, _)| key)
+    }
+
+    pub fn value(&self) -> Option<&'a V> {
+        self.key_value().map(|(_, value)| value)
+    }
+
+    pub fn key_value(&self) -> Option<(&'a K, &'a V)> {
+        self.current.map(|kv| unsafe { key_value(kv) })
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = next_of(self.map, self.current);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = prev_of(self.map, self.current);
+    }
+
+    pub fn peek_next(&self) -> Option<(&'a K, &'a V)> {
+        next_of(self.map, self.current).map(|kv| unsafe { key_value(kv) })
+    }
+
+    pub fn peek_prev(&self) -> Option<(&'a K, &'a V)> {
+        prev_of(self.map, self.current).map(|kv| unsafe { key_value(kv) })
+    }
+}
+
+fn next_of<K, V>(map: &ZenBTreeMap<K, V>, current: Option<Handle<K, V>>) -> Option<Handle<K, V>> {
+    match current {
+        Some(kv) => unsafe { node::next_kv(node::right_leaf_edge(kv)) },
+        None => map.first_kv(),
+    }
+}
+
+fn prev_of<K, V>(map: &ZenBTreeMap<K, V>, current: Option<Handle<K, V>>) -> Option<Handle<K, V>> {
+    match current {
+        Some(kv) => unsafe { node::next_back_kv(node::left_leaf_edge(kv)) },
+        None => map.last_kv(),
+    }
+}
+
+// A cursor that can also change values and remove pairs.
+pub struct CursorMut<'a, K, V> {
+    current: Option<Handle<K, V>>,
+    map: &'a mut ZenBTreeMap<K, V>,
+}
+
+impl<K, V> CursorMut<'_, K, V> {
+    pub fn key(&self) -> Option<&K> {
+        self.key_value().map(|(key, _)| key)
+    }
+
+    pub fn value(&self) -> Option<&V> {
+        self.key_value().map(|(_, value)| value)
+    }
+
+    pub fn key_value(&self) -> Option<(&K, &V)> {
+        self.current.map(|kv| unsafe { key_value(kv) })
+    }
+
+    pub fn value_mut(&mut self) -> Option<&mut V> {
+        self.key_value_mut().map(|(_, value)| value)
+    }
+
+    pub fn key_value_mut(&mut self) -> Option<(&K, &mut V)> {
+        self.current.map(|kv| unsafe { key_value_mut(kv) })
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = next_of(self.map, self.current);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = prev_of(self.map, self.current);
+    }
+
+    pub fn peek_next(&self) -> Option<(&K, &V)> {
+        next_of(self.map, self.current).map(|kv| unsafe { key_value(kv) })
+    }
+
+    pub fn peek_prev(&self) -> Option<(&K, &V)> {
+        prev_of(self.map, self.current).map(|kv| unsafe { key_value(kv) })
+    }
+
+    pub fn as_cursor(&self) -> Cursor<'_, K, V> {
+        Cursor {
+            current: self.current,
+            map: self.map,
+        }
+    }
+}
+
+impl<K: Ord, V> CursorMut<'_, K, V> {
+    // Removes the current pair and moves to the next one.
+    pub fn remove_current(&mut self) -> Option<(K, V)> {
+        let kv = self.current?;
+        let (key, value) = unsafe { self.map.remove_kv(kv) };
+        self.current = self.map.lower_bound_kv(Bound::Excluded(&key));
+        Some((key, value))
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    range: LeafRange<K, V>,
+    length: usize,
+    marker: PhantomData<&'a (K, V)>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            range: self.range.clone(),
+            length: self.length,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        if self.length == 0 {
+            return None;
+        }
+        self.length -= 1;
+        unsafe { self.range.next_kv().map(|kv| key_value(kv)) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        if self.length == 0 {
+            return None;
+        }
+        self.length -= 1;
+        unsafe { self.range.next_back_kv().map(|kv| key_value(kv)) }
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+pub struct IterMut<'a, K, V> {
+    range: LeafRange<K, V>,
+    length: usize,
+    marker: PhantomData<&'a mut (K, V)>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        if self.length == 0 {
+            return None;
+        }
+        self.length -= 1;
+        unsafe { self.range.next_kv().map(|kv| key_value_mut(kv)) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
+        if self.length == 0 {
+            return None;
+        }
+        self.length -= 1;
+        unsafe { self.range.next_back_kv().map(|kv| key_value_mut(kv)) }
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+pub struct Range<'a, K, V> {
+    range: LeafRange<K, V>,
+    marker: PhantomData<&'a (K, V)>,
+}
+
+impl<K, V> Clone for Range<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            range: self.range.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        unsafe { self.range.next_kv().map(|kv| key_value(kv)) }
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        unsafe { self.range.next_back_kv().map(|kv| key_value(kv)) }
+    }
+}
+
+impl<K, V> FusedIterator for Range<'_, K, V> {}
+
+pub struct RangeMut<'a, K, V> {
+    range: LeafRange<K, V>,
+    marker: PhantomData<&'a mut (K, V)>,
+}
+
+impl<'a, K, V> Iterator for RangeMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        unsafe { self.range.next_kv().map(|kv| key_value_mut(kv)) }
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for RangeMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
+        unsafe { self.range.next_back_kv().map(|kv| key_value_mut(kv)) }
+    }
+}
+
+impl<K, V> FusedIterator for RangeMut<'_, K, V> {}
+
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    fn next_back(&mut self) -> Option<&'a K> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<&'a V> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    fn next_back(&mut self) -> Option<&'a V> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<&'a mut V> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for ValuesMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<&'a mut V> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
+
+// Owning iterator. Pairs are taken off the ends of the map one at a time,
+// so whatever is left is dropped with the map.
+pub struct IntoIter<K, V> {
+    map: ZenBTreeMap<K, V>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.map.pop_first()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.map.len(), Some(self.map.len()))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<(K, V)> {
+        self.map.pop_last()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
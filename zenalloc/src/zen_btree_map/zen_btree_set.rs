@@ -0,0 +1,314 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_btree_map::zen_btree_map::{self, ZenBTreeMap};
+use core::{
+    alloc::AllocError,
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    ops::{Bound, RangeBounds},
+};
+
+// An ordered set built on ZenBTreeMap with unit values.
+pub struct ZenBTreeSet<T> {
+    map: ZenBTreeMap<T, ()>,
+}
+
+impl<T> ZenBTreeSet<T> {
+    pub const fn new() -> Self {
+        Self {
+            map: ZenBTreeMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.map.keys(),
+        }
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|(value, _)| value)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|(value, _)| value)
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.map.pop_first().map(|(value, _)| value)
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.map.pop_last().map(|(value, _)| value)
+    }
+}
+
+impl<T: Ord> ZenBTreeSet<T> {
+    // Adds a value, returning whether it was newly inserted. An existing
+    // equal value is left in place and `value` is dropped.
+    pub fn try_insert(&mut self, value: T) -> Result<bool, AllocError> {
+        Ok(self.map.try_insert(value, ())?.is_none())
+    }
+
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.get_key_value(value).map(|(value, _)| value)
+    }
+
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove_entry(value).map(|(value, _)| value)
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain(|value, _| f(value));
+    }
+
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Range {
+            inner: self.map.range(range),
+        }
+    }
+
+    // The first value above `bound`.
+    pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.lower_bound(bound).key()
+    }
+
+    // The last value below `bound`.
+    pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.upper_bound(bound).key()
+    }
+
+    pub fn split_off<Q>(&mut self, value: &Q) -> Result<Self, AllocError>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Ok(Self {
+            map: self.map.split_off(value)?,
+        })
+    }
+
+    // On allocation failure, both sets are unchanged.
+    pub fn append(&mut self, other: &mut Self) -> Result<(), AllocError> {
+        self.map.append(&mut other.map)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|value| other.contains(value))
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.iter().all(|value| !other.contains(value))
+    }
+}
+
+impl<T> Default for ZenBTreeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + TryClone> TryClone for ZenBTreeSet<T> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(Self {
+            map: self.map.try_clone()?,
+        })
+    }
+}
+
+impl<T: Ord> TryFromIterator<T> for ZenBTreeSet<T> {
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, AllocError> {
+        let mut set = ZenBTreeSet::new();
+        set.try_extend(iter)?;
+        Ok(set)
+    }
+}
+
+// Leaves the set unchanged on failure, like `ZenBTreeMap::try_extend`.
+impl<T: Ord> TryExtend<T> for ZenBTreeSet<T> {
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        self.map.try_extend(iter.into_iter().map(|value| (value, ())))
+    }
+}
+
+impl<T: PartialEq> PartialEq for ZenBTreeSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T: Eq> Eq for ZenBTreeSet<T> {}
+
+impl<T: PartialOrd> PartialOrd for ZenBTreeSet<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for ZenBTreeSet<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for ZenBTreeSet<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        self.iter().for_each(|value| value.hash(state));
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ZenBTreeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> IntoIterator for ZenBTreeSet<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            inner: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ZenBTreeSet<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: zen_btree_map::Keys<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.inner.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+pub struct Range<'a, T> {
+    inner: zen_btree_map::Range<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next().map(|(value, _)| value)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Range<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.inner.next_back().map(|(value, _)| value)
+    }
+}
+
+impl<T> FusedIterator for Range<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: zen_btree_map::IntoIter<T, ()>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.next_back().map(|(value, _)| value)
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
@@ -1 +1,2 @@
+#[allow(clippy::module_inception)]
 pub mod zen_cow;
\ No newline at end of file
@@ -1,7 +1,12 @@
 use core::alloc::{AllocError, Layout};
+use core::borrow::Borrow;
+use core::cmp;
+use core::fmt;
+use core::hash::{Hash, Hasher};
 use core::ptr::{self, NonNull};
 use core::sync::atomic::{AtomicUsize, Ordering};
 use crate::alloc_trait::Allocator;
+use crate::try_clone::TryClone;
 use crate::System;
 
 pub struct ZenCow<T: Clone> {
@@ -12,14 +17,26 @@ pub struct ZenCow<T: Clone> {
 impl<T: Clone> ZenCow<T> {
     pub fn new(value: T) -> Result<Self, AllocError> {
         let layout = Layout::new::<T>();
-        let ptr = System.allocate(layout)?;
-        unsafe {
-            ptr::write(ptr.as_ptr() as *mut T, value);
-        }
+        // Zero-sized values need no memory; only the count is allocated.
+        let ptr = if layout.size() == 0 {
+            NonNull::slice_from_raw_parts(NonNull::dangling(), 0)
+        } else {
+            System.allocate(layout)?
+        };
 
         let count_layout = Layout::new::<AtomicUsize>();
-        let ref_count = System.allocate(count_layout)?;
+        let ref_count = match System.allocate(count_layout) {
+            Ok(ref_count) => ref_count,
+            Err(alloc_err) => {
+                if layout.size() != 0 {
+                    unsafe { System.deallocate(ptr.cast(), layout) };
+                }
+                return Err(alloc_err);
+            }
+        };
+
         unsafe {
+            ptr::write(ptr.as_ptr() as *mut T, value);
             ptr::write(ref_count.as_ptr() as *mut AtomicUsize, AtomicUsize::new(1));
         }
 
@@ -29,30 +46,41 @@ impl<T: Clone> ZenCow<T> {
         })
     }
 
+    // `Default` can't report allocation failure, so this stands in for it.
+    pub fn try_default() -> Result<Self, AllocError>
+    where
+        T: Default,
+    {
+        Self::new(T::default())
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn as_ref(&self) -> &T {
         unsafe { self.ptr.as_ref() }
     }
 
-    pub fn get_mut(&mut self) -> &mut T {
+    // Returns a mutable reference, first copying the value if it is shared.
+    // Fails without touching `self` if the copy cannot be allocated.
+    pub fn get_mut(&mut self) -> Result<&mut T, AllocError> {
         if self.ref_count().load(Ordering::SeqCst) != 1 {
-            // Clone the data
-            let layout = Layout::new::<T>();
-            let new_ptr = System.allocate(layout).unwrap();
-            unsafe {
-                ptr::write(new_ptr.as_ptr() as *mut T, self.ptr.as_ref().clone());
-            }
-            self.ptr = new_ptr.cast();
+            let unique = ZenCow::new(self.as_ref().clone())?;
+            /* Dropping the shared handle releases our reference to the original. */
+            *self = unique;
+        }
+        Ok(unsafe { self.ptr.as_mut() })
+    }
 
-            // Update the reference count
-            self.ref_count().fetch_sub(1, Ordering::SeqCst);
-            let count_layout = Layout::new::<AtomicUsize>();
-            let new_ref_count = System.allocate(count_layout).unwrap();
-            unsafe {
-                ptr::write(new_ref_count.as_ptr() as *mut AtomicUsize, AtomicUsize::new(1));
-            }
-            self.ref_count = new_ref_count.cast();
+    // As `get_mut`, but copies the value with `try_clone`, so a value whose
+    // own copy allocates can fail instead of aborting.
+    pub fn try_get_mut(&mut self) -> Result<&mut T, AllocError>
+    where
+        T: TryClone,
+    {
+        if self.ref_count().load(Ordering::SeqCst) != 1 {
+            let unique = ZenCow::new(self.as_ref().try_clone()?)?;
+            *self = unique;
         }
-        unsafe { self.ptr.as_mut() }
+        Ok(unsafe { self.ptr.as_mut() })
     }
 
     fn ref_count(&self) -> &AtomicUsize {
@@ -70,13 +98,21 @@ impl<T: Clone> Clone for ZenCow<T> {
     }
 }
 
+impl<T: Clone> TryClone for ZenCow<T> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(self.clone())
+    }
+}
+
 impl<T: Clone> Drop for ZenCow<T> {
     fn drop(&mut self) {
         if self.ref_count().fetch_sub(1, Ordering::SeqCst) == 1 {
             let layout = Layout::new::<T>();
             unsafe {
                 ptr::drop_in_place(self.ptr.as_ptr());
-                System.deallocate(self.ptr.cast(), layout);
+                if layout.size() != 0 {
+                    System.deallocate(self.ptr.cast(), layout);
+                }
             }
 
             let count_layout = Layout::new::<AtomicUsize>();
@@ -87,5 +123,61 @@ impl<T: Clone> Drop for ZenCow<T> {
     }
 }
 
+impl<T: Clone + fmt::Debug> fmt::Debug for ZenCow<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Clone + fmt::Display> fmt::Display for ZenCow<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Clone> fmt::Pointer for ZenCow<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&(self.as_ref() as *const T), f)
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq for ZenCow<T> {
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(self.as_ref(), other.as_ref())
+    }
+}
+
+impl<T: Clone + Eq> Eq for ZenCow<T> {}
+
+impl<T: Clone + PartialOrd> PartialOrd for ZenCow<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(self.as_ref(), other.as_ref())
+    }
+}
+
+impl<T: Clone + Ord> Ord for ZenCow<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        Ord::cmp(self.as_ref(), other.as_ref())
+    }
+}
+
+impl<T: Clone + Hash> Hash for ZenCow<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(self.as_ref(), state)
+    }
+}
+
+impl<T: Clone> Borrow<T> for ZenCow<T> {
+    fn borrow(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+impl<T: Clone> AsRef<T> for ZenCow<T> {
+    fn as_ref(&self) -> &T {
+        self.as_ref()
+    }
+}
+
 unsafe impl<T: Send + Sync + Clone> Send for ZenCow<T> {}
 unsafe impl<T: Sync + Clone> Sync for ZenCow<T> {}
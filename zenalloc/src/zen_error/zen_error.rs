@@ -0,0 +1,122 @@
+use core::alloc::{AllocError, Layout};
+use core::fmt;
+
+// Why a buffer could not be grown. Capacity overflow means the request
+// could not even be expressed as a `Layout`; an allocator failure carries
+// the layout that was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryReserveError {
+    CapacityOverflow,
+    AllocError { layout: Layout },
+}
+
+impl TryReserveError {
+    // The layout the allocator refused, if it got that far.
+    pub fn layout(&self) -> Option<Layout> {
+        match self {
+            TryReserveError::CapacityOverflow => None,
+            TryReserveError::AllocError { layout } => Some(*layout),
+        }
+    }
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => {
+                f.write_str("requested capacity exceeds the maximum size")
+            }
+            TryReserveError::AllocError { layout } => write!(
+                f,
+                "allocator failed to provide {} bytes aligned to {}",
+                layout.size(),
+                layout.align()
+            ),
+        }
+    }
+}
+
+// Input that should have been ASCII wasn't. Everything before
+// `valid_up_to` was ASCII.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsciiError {
+    pub(crate) valid_up_to: usize,
+}
+
+impl AsciiError {
+    // Index of the first byte that is not ASCII.
+    pub fn valid_up_to(&self) -> usize {
+        self.valid_up_to
+    }
+}
+
+impl fmt::Display for AsciiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "byte at index {} is not ASCII", self.valid_up_to)
+    }
+}
+
+// The crate-wide error for operations that can fail for more than one
+// reason. Operations that can only fail to allocate return
+// `TryReserveError` or `AllocError` instead, and convert into this.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZenError {
+    TryReserve(TryReserveError),
+    IndexOutOfBounds { index: usize, len: usize },
+    InvalidInput(&'static str),
+    NotAscii(AsciiError),
+}
+
+impl ZenError {
+    // Shorthand for the common allocation-failure case.
+    pub fn is_alloc_failure(&self) -> bool {
+        matches!(self, ZenError::TryReserve(_))
+    }
+}
+
+impl fmt::Display for ZenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZenError::TryReserve(err) => fmt::Display::fmt(err, f),
+            ZenError::IndexOutOfBounds { index, len } => {
+                write!(f, "index {} out of bounds for length {}", index, len)
+            }
+            ZenError::InvalidInput(reason) => write!(f, "invalid input: {}", reason),
+            ZenError::NotAscii(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl From<TryReserveError> for ZenError {
+    fn from(err: TryReserveError) -> Self {
+        ZenError::TryReserve(err)
+    }
+}
+
+impl From<AsciiError> for ZenError {
+    fn from(err: AsciiError) -> Self {
+        ZenError::NotAscii(err)
+    }
+}
+
+impl From<TryReserveError> for AllocError {
+    fn from(_: TryReserveError) -> Self {
+        AllocError
+    }
+}
+
+// Lossy: only the fact that something failed survives.
+impl From<ZenError> for AllocError {
+    fn from(_: ZenError) -> Self {
+        AllocError
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryReserveError {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AsciiError {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ZenError {}
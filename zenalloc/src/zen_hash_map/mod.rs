@@ -0,0 +1,5 @@
+pub mod raw_table;
+pub mod sip_hasher;
+#[allow(clippy::module_inception)]
+pub mod zen_hash_map;
+pub mod zen_hash_set;
@@ -0,0 +1,597 @@
+use crate::alloc_trait::Allocator;
+use crate::zen_error::zen_error::TryReserveError;
+use crate::System;
+use core::alloc::Layout;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr::{self, NonNull};
+
+// Control bytes. A full slot stores the top 7 bits of its hash (`h2`).
+const EMPTY: u8 = 0xFF;
+const DELETED: u8 = 0x80;
+
+// Number of control bytes probed at once. Groups are matched with plain
+// 64-bit arithmetic, so no SIMD support is needed.
+const GROUP_WIDTH: usize = mem::size_of::<u64>();
+
+const LO_BITS: u64 = 0x0101_0101_0101_0101;
+const HI_BITS: u64 = 0x8080_8080_8080_8080;
+
+// Control bytes of the unallocated table. Lookups see a group of empty
+// slots and stop immediately.
+static EMPTY_GROUP: [u8; GROUP_WIDTH] = [EMPTY; GROUP_WIDTH];
+
+#[inline]
+fn is_full(ctrl: u8) -> bool {
+    ctrl & 0x80 == 0
+}
+
+#[inline]
+fn h1(hash: u64) -> usize {
+    hash as usize
+}
+
+#[inline]
+fn h2(hash: u64) -> u8 {
+    (hash >> 57) as u8
+}
+
+// Bit mask with the high bit of every matching byte set.
+#[derive(Clone, Copy)]
+struct BitMask(u64);
+
+impl BitMask {
+    #[inline]
+    fn any(self) -> bool {
+        self.0 != 0
+    }
+
+    #[inline]
+    fn lowest(self) -> Option<usize> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some(self.0.trailing_zeros() as usize / 8)
+        }
+    }
+
+    #[inline]
+    fn leading_empty(self) -> usize {
+        self.0.leading_zeros() as usize / 8
+    }
+
+    #[inline]
+    fn trailing_empty(self) -> usize {
+        self.0.trailing_zeros() as usize / 8
+    }
+}
+
+impl Iterator for BitMask {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        let bit = self.lowest()?;
+        self.0 &= self.0 - 1;
+        Some(bit)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Group(u64);
+
+impl Group {
+    // # Safety
+    //
+    // `ptr` must be valid for reading `GROUP_WIDTH` bytes.
+    #[inline]
+    unsafe fn load(ptr: *const u8) -> Self {
+        Group(u64::from_le(ptr::read_unaligned(ptr as *const u64)))
+    }
+
+    // May report false positives next to a real match; callers compare the
+    // keys anyway.
+    #[inline]
+    fn match_byte(self, byte: u8) -> BitMask {
+        let cmp = self.0 ^ (LO_BITS * byte as u64);
+        BitMask(cmp.wrapping_sub(LO_BITS) & !cmp & HI_BITS)
+    }
+
+    #[inline]
+    fn match_empty(self) -> BitMask {
+        /* Only EMPTY has both of its top two bits set. */
+        BitMask(self.0 & (self.0 << 1) & HI_BITS)
+    }
+
+    #[inline]
+    fn match_empty_or_deleted(self) -> BitMask {
+        BitMask(self.0 & HI_BITS)
+    }
+}
+
+// Triangular probing over groups. With a power-of-two number of buckets
+// this visits every group exactly once.
+struct ProbeSeq {
+    pos: usize,
+    stride: usize,
+}
+
+impl ProbeSeq {
+    #[inline]
+    fn move_next(&mut self, bucket_mask: usize) {
+        self.stride += GROUP_WIDTH;
+        self.pos = (self.pos + self.stride) & bucket_mask;
+    }
+}
+
+// Number of buckets needed to hold `cap` elements at a 7/8 load factor.
+fn capacity_to_buckets(cap: usize) -> Option<usize> {
+    if cap < GROUP_WIDTH {
+        return Some(GROUP_WIDTH);
+    }
+    let adjusted = cap.checked_mul(8)? / 7;
+    adjusted.checked_next_power_of_two()
+}
+
+fn bucket_mask_to_capacity(bucket_mask: usize) -> usize {
+    if bucket_mask == 0 {
+        0
+    } else {
+        (bucket_mask + 1) / 8 * 7
+    }
+}
+
+// An open-addressing hash table in the style of SwissTable. It stores
+// values of type `T` and leaves hashing and key comparison to the caller.
+//
+// The allocation holds `buckets` slots of `T` followed by `buckets +
+// GROUP_WIDTH` control bytes; the trailing control bytes mirror the first
+// group so a group load never has to wrap around. Tables are never smaller
+// than one group.
+pub struct RawTable<T> {
+    data: NonNull<T>,
+    ctrl: NonNull<u8>,
+    bucket_mask: usize,
+    items: usize,
+    growth_left: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T> RawTable<T> {
+    pub const fn new() -> Self {
+        Self {
+            data: NonNull::dangling(),
+            ctrl: unsafe { NonNull::new_unchecked(EMPTY_GROUP.as_ptr() as *mut u8) },
+            bucket_mask: 0,
+            items: 0,
+            growth_left: 0,
+            marker: PhantomData,
+        }
+    }
+
+    fn layout(buckets: usize) -> Option<(Layout, usize)> {
+        let data = Layout::array::<T>(buckets).ok()?;
+        let ctrl = Layout::array::<u8>(buckets.checked_add(GROUP_WIDTH)?).ok()?;
+        data.extend(ctrl).ok()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        if capacity == 0 {
+            return Ok(Self::new());
+        }
+
+        let buckets = capacity_to_buckets(capacity).ok_or(TryReserveError::CapacityOverflow)?;
+        let (layout, ctrl_offset) =
+            Self::layout(buckets).ok_or(TryReserveError::CapacityOverflow)?;
+        let ptr = System
+            .allocate(layout)
+            .map_err(|_| TryReserveError::AllocError { layout })?
+            .cast::<u8>();
+
+        unsafe {
+            let ctrl = ptr.as_ptr().add(ctrl_offset);
+            ctrl.write_bytes(EMPTY, buckets + GROUP_WIDTH);
+            Ok(Self {
+                data: ptr.cast(),
+                ctrl: NonNull::new_unchecked(ctrl),
+                bucket_mask: buckets - 1,
+                items: 0,
+                growth_left: bucket_mask_to_capacity(buckets - 1),
+                marker: PhantomData,
+            })
+        }
+    }
+
+    #[inline]
+    fn is_unallocated(&self) -> bool {
+        self.bucket_mask == 0
+    }
+
+    #[inline]
+    pub fn buckets(&self) -> usize {
+        if self.is_unallocated() {
+            0
+        } else {
+            self.bucket_mask + 1
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items == 0
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.items + self.growth_left
+    }
+
+    #[inline]
+    unsafe fn ctrl(&self, index: usize) -> *mut u8 {
+        self.ctrl.as_ptr().add(index)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn is_bucket_full(&self, index: usize) -> bool {
+        is_full(*self.ctrl(index))
+    }
+
+    /// # Safety
+    ///
+    /// `index` must be below `buckets()`.
+    #[inline]
+    pub unsafe fn bucket(&self, index: usize) -> *mut T {
+        self.data.as_ptr().add(index)
+    }
+
+    // Writes a control byte and its mirror in the trailing group.
+    #[inline]
+    unsafe fn set_ctrl(&mut self, index: usize, ctrl: u8) {
+        let mirror = (index.wrapping_sub(GROUP_WIDTH) & self.bucket_mask) + GROUP_WIDTH;
+        *self.ctrl(index) = ctrl;
+        *self.ctrl(mirror) = ctrl;
+    }
+
+    #[inline]
+    fn probe_seq(&self, hash: u64) -> ProbeSeq {
+        ProbeSeq {
+            pos: h1(hash) & self.bucket_mask,
+            stride: 0,
+        }
+    }
+
+    // Returns the index of the full bucket for which `eq` returns `true`.
+    pub fn find(&self, hash: u64, mut eq: impl FnMut(&T) -> bool) -> Option<usize> {
+        if self.is_unallocated() {
+            return None;
+        }
+
+        let h2 = h2(hash);
+        let mut probe = self.probe_seq(hash);
+        loop {
+            let group = unsafe { Group::load(self.ctrl(probe.pos)) };
+            for bit in group.match_byte(h2) {
+                let index = (probe.pos + bit) & self.bucket_mask;
+                if unsafe { self.is_bucket_full(index) && eq(&*self.bucket(index)) } {
+                    return Some(index);
+                }
+            }
+            if group.match_empty().any() {
+                return None;
+            }
+            probe.move_next(self.bucket_mask);
+        }
+    }
+
+    // Returns the first empty or deleted bucket on the probe sequence.
+    // There always is one, because the load factor keeps some buckets free.
+    fn find_insert_slot(&self, hash: u64) -> usize {
+        let mut probe = self.probe_seq(hash);
+        loop {
+            let group = unsafe { Group::load(self.ctrl(probe.pos)) };
+            if let Some(bit) = group.match_empty_or_deleted().lowest() {
+                return (probe.pos + bit) & self.bucket_mask;
+            }
+            probe.move_next(self.bucket_mask);
+        }
+    }
+
+    // Makes room for `additional` more elements. On failure the table is
+    // left exactly as it was.
+    pub fn reserve(
+        &mut self,
+        additional: usize,
+        hasher: impl Fn(&T) -> u64,
+    ) -> Result<(), TryReserveError> {
+        if additional <= self.growth_left {
+            return Ok(());
+        }
+
+        let new_items = self
+            .items
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        let full_capacity = bucket_mask_to_capacity(self.bucket_mask);
+        if new_items <= full_capacity / 2 {
+            /* Mostly tombstones: rebuild at the same size to reclaim them. */
+            self.resize(full_capacity, hasher)
+        } else {
+            self.resize(core::cmp::max(new_items, full_capacity + 1), hasher)
+        }
+    }
+
+    // Moves every element into a newly allocated table of at least
+    // `capacity`. The new table is allocated before anything is moved.
+    fn resize(&mut self, capacity: usize, hasher: impl Fn(&T) -> u64) -> Result<(), TryReserveError> {
+        let mut new_table = RawTable::<T>::with_capacity(capacity)?;
+
+        /* `new_table.items` stays 0 until the end, so a panicking hasher only frees its memory. */
+        unsafe {
+            for index in 0..self.buckets() {
+                if !self.is_bucket_full(index) {
+                    continue;
+                }
+                let elem = self.bucket(index);
+                let hash = hasher(&*elem);
+                let slot = new_table.find_insert_slot(hash);
+                new_table.set_ctrl(slot, h2(hash));
+                ptr::copy_nonoverlapping(elem, new_table.bucket(slot), 1);
+            }
+        }
+
+        new_table.growth_left -= self.items;
+        new_table.items = self.items;
+        /* The elements now belong to `new_table`; the old table is freed without dropping them. */
+        self.items = 0;
+        mem::swap(self, &mut new_table);
+        Ok(())
+    }
+
+    // Inserts `value` without growing. The caller must have reserved room.
+    pub fn insert_no_grow(&mut self, hash: u64, value: T) -> usize {
+        let index = self.find_insert_slot(hash);
+        unsafe {
+            let old_ctrl = *self.ctrl(index);
+            self.growth_left -= (old_ctrl == EMPTY) as usize;
+            self.set_ctrl(index, h2(hash));
+            ptr::write(self.bucket(index), value);
+        }
+        self.items += 1;
+        index
+    }
+
+    /// Marks a full bucket as free. A bucket that no probe sequence can have
+    /// passed over becomes EMPTY again; otherwise it becomes a tombstone.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be a full bucket whose value has been moved out.
+    pub unsafe fn erase_no_drop(&mut self, index: usize) {
+        let index_before = index.wrapping_sub(GROUP_WIDTH) & self.bucket_mask;
+        let empty_before = Group::load(self.ctrl(index_before)).match_empty();
+        let empty_after = Group::load(self.ctrl(index)).match_empty();
+
+        /* If the run of non-empty buckets around `index` spans a whole group, a probe may have passed over it. */
+        let ctrl = if empty_before.leading_empty() + empty_after.trailing_empty() >= GROUP_WIDTH {
+            DELETED
+        } else {
+            self.growth_left += 1;
+            EMPTY
+        };
+        self.set_ctrl(index, ctrl);
+        self.items -= 1;
+    }
+
+    /// # Safety
+    ///
+    /// `index` must be a full bucket.
+    pub unsafe fn remove(&mut self, index: usize) -> T {
+        let value = ptr::read(self.bucket(index));
+        self.erase_no_drop(index);
+        value
+    }
+
+    // Marks every bucket empty without dropping anything.
+    fn clear_no_drop(&mut self) {
+        if !self.is_unallocated() {
+            unsafe {
+                self.ctrl(0)
+                    .write_bytes(EMPTY, self.buckets() + GROUP_WIDTH);
+            }
+        }
+        self.items = 0;
+        self.growth_left = bucket_mask_to_capacity(self.bucket_mask);
+    }
+
+    pub fn clear(&mut self) {
+        unsafe { self.drop_elements() };
+        self.clear_no_drop();
+    }
+
+    unsafe fn drop_elements(&mut self) {
+        if mem::needs_drop::<T>() && self.items != 0 {
+            for index in 0..self.buckets() {
+                if self.is_bucket_full(index) {
+                    /* Forget the element before dropping it so a panic can't drop it twice. */
+                    *self.ctrl(index) = DELETED;
+                    self.items -= 1;
+                    ptr::drop_in_place(self.bucket(index));
+                }
+            }
+        }
+    }
+
+    // Keeps only the elements for which `f` returns `true`.
+    pub fn retain(&mut self, mut f: impl FnMut(&mut T) -> bool) {
+        unsafe {
+            for index in 0..self.buckets() {
+                if self.is_bucket_full(index) && !f(&mut *self.bucket(index)) {
+                    drop(self.remove(index));
+                }
+            }
+        }
+    }
+
+    pub fn iter(&self) -> RawIter<T> {
+        RawIter {
+            data: self.data,
+            ctrl: self.ctrl,
+            index: 0,
+            buckets: self.buckets(),
+            items: self.items,
+            marker: PhantomData,
+        }
+    }
+
+    // Removes and yields every element, leaving the allocation in place.
+    pub fn drain(&mut self) -> RawDrain<'_, T> {
+        RawDrain {
+            table: self,
+            index: 0,
+        }
+    }
+}
+
+impl<T> Drop for RawTable<T> {
+    fn drop(&mut self) {
+        if self.is_unallocated() {
+            return;
+        }
+
+        unsafe {
+            self.drop_elements();
+            let (layout, _) = Self::layout(self.buckets()).unwrap_unchecked();
+            System.deallocate(self.data.cast(), layout);
+        }
+    }
+}
+
+impl<T> Default for RawTable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<T: Send> Send for RawTable<T> {}
+unsafe impl<T: Sync> Sync for RawTable<T> {}
+
+// Yields pointers to the full buckets of a table. The caller decides
+// whether they are read as shared, mutable or owned values.
+pub struct RawIter<T> {
+    data: NonNull<T>,
+    ctrl: NonNull<u8>,
+    index: usize,
+    buckets: usize,
+    items: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T> Clone for RawIter<T> {
+    fn clone(&self) -> Self {
+        RawIter { ..*self }
+    }
+}
+
+impl<T> Iterator for RawIter<T> {
+    type Item = NonNull<T>;
+
+    fn next(&mut self) -> Option<NonNull<T>> {
+        if self.items == 0 {
+            return None;
+        }
+
+        while self.index < self.buckets {
+            let index = self.index;
+            self.index += 1;
+            unsafe {
+                if is_full(*self.ctrl.as_ptr().add(index)) {
+                    self.items -= 1;
+                    return Some(NonNull::new_unchecked(self.data.as_ptr().add(index)));
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.items, Some(self.items))
+    }
+}
+
+// Draining iterator over a table. Each element is unlinked from the table
+// before it is handed out, so leaking the iterator only leaks elements.
+pub struct RawDrain<'a, T> {
+    table: &'a mut RawTable<T>,
+    index: usize,
+}
+
+impl<T> Iterator for RawDrain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while self.index < self.table.buckets() {
+            let index = self.index;
+            self.index += 1;
+            unsafe {
+                if self.table.is_bucket_full(index) {
+                    /* A tombstone keeps the remaining probe sequences intact. */
+                    self.table.set_ctrl(index, DELETED);
+                    self.table.items -= 1;
+                    return Some(ptr::read(self.table.bucket(index)));
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.table.items, Some(self.table.items))
+    }
+}
+
+impl<T> Drop for RawDrain<'_, T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+        self.table.clear_no_drop();
+    }
+}
+
+// Owning iterator over a table.
+pub struct RawIntoIter<T> {
+    table: RawTable<T>,
+    index: usize,
+}
+
+impl<T> RawIntoIter<T> {
+    pub fn new(table: RawTable<T>) -> Self {
+        RawIntoIter { table, index: 0 }
+    }
+}
+
+impl<T> Iterator for RawIntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while self.index < self.table.buckets() {
+            let index = self.index;
+            self.index += 1;
+            unsafe {
+                if self.table.is_bucket_full(index) {
+                    *self.table.ctrl(index) = DELETED;
+                    self.table.items -= 1;
+                    return Some(ptr::read(self.table.bucket(index)));
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.table.items, Some(self.table.items))
+    }
+}
@@ -0,0 +1,163 @@
+use core::hash::{BuildHasher, Hasher};
+use core::sync::atomic::{AtomicU64, Ordering};
+
+// SipHash with `C` compression rounds and `D` finalization rounds.
+// `ZenHashMap` uses SipHash-1-3 by default, the same variant `std` uses.
+#[derive(Debug, Clone)]
+pub struct SipHasher<const C: usize, const D: usize> {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    tail: u64,
+    ntail: usize,
+    length: usize,
+}
+
+pub type SipHasher13 = SipHasher<1, 3>;
+pub type SipHasher24 = SipHasher<2, 4>;
+
+#[inline]
+fn read_le(buf: &[u8]) -> u64 {
+    let mut out = 0u64;
+    for (i, byte) in buf.iter().enumerate() {
+        out |= (*byte as u64) << (8 * i);
+    }
+    out
+}
+
+impl<const C: usize, const D: usize> SipHasher<C, D> {
+    pub const fn new_with_keys(k0: u64, k1: u64) -> Self {
+        Self {
+            v0: k0 ^ 0x736f_6d65_7073_6575,
+            v1: k1 ^ 0x646f_7261_6e64_6f6d,
+            v2: k0 ^ 0x6c79_6765_6e65_7261,
+            v3: k1 ^ 0x7465_6462_7974_6573,
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    #[inline]
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13);
+        self.v1 ^= self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16);
+        self.v3 ^= self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21);
+        self.v3 ^= self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17);
+        self.v1 ^= self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    #[inline]
+    fn compress(&mut self, m: u64) {
+        self.v3 ^= m;
+        for _ in 0..C {
+            self.round();
+        }
+        self.v0 ^= m;
+    }
+}
+
+impl<const C: usize, const D: usize> Hasher for SipHasher<C, D> {
+    fn write(&mut self, mut msg: &[u8]) {
+        self.length = self.length.wrapping_add(msg.len());
+
+        if self.ntail != 0 {
+            let needed = 8 - self.ntail;
+            let fill = core::cmp::min(needed, msg.len());
+            self.tail |= read_le(&msg[..fill]) << (8 * self.ntail);
+            if msg.len() < needed {
+                self.ntail += msg.len();
+                return;
+            }
+            let tail = self.tail;
+            self.compress(tail);
+            self.ntail = 0;
+            msg = &msg[fill..];
+        }
+
+        let mut chunks = msg.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.compress(read_le(chunk));
+        }
+
+        let rest = chunks.remainder();
+        self.tail = read_le(rest);
+        self.ntail = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state = self.clone();
+        let b = (((self.length as u64) & 0xff) << 56) | self.tail;
+
+        state.compress(b);
+        state.v2 ^= 0xff;
+        for _ in 0..D {
+            state.round();
+        }
+
+        state.v0 ^ state.v1 ^ state.v2 ^ state.v3
+    }
+}
+
+// Builds keyed `SipHasher13`s. `new` derives its keys without any OS
+// randomness, from a process-wide counter mixed with stack and static
+// addresses, so keys differ between maps and, under ASLR, between runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZenRandomState {
+    k0: u64,
+    k1: u64,
+}
+
+static SEED_COUNTER: AtomicU64 = AtomicU64::new(0x243f_6a88_85a3_08d3);
+
+// The 64-bit finalizer from SplitMix64.
+#[inline]
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+impl ZenRandomState {
+    pub fn new() -> Self {
+        let stack_marker = 0u8;
+        let stack = &stack_marker as *const u8 as u64;
+        let global = &SEED_COUNTER as *const AtomicU64 as u64;
+        let count = SEED_COUNTER.fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed);
+
+        let k0 = mix(count ^ stack.rotate_left(32));
+        let k1 = mix(k0 ^ global ^ count.rotate_left(17));
+        Self { k0, k1 }
+    }
+
+    // Uses fixed keys, e.g. ones supplied by a hardware RNG.
+    pub const fn with_keys(k0: u64, k1: u64) -> Self {
+        Self { k0, k1 }
+    }
+}
+
+impl Default for ZenRandomState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildHasher for ZenRandomState {
+    type Hasher = SipHasher13;
+
+    fn build_hasher(&self) -> SipHasher13 {
+        SipHasher13::new_with_keys(self.k0, self.k1)
+    }
+}
+
+pub type DefaultHashBuilder = ZenRandomState;
@@ -0,0 +1,609 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_error::zen_error::TryReserveError;
+use crate::zen_hash_map::raw_table::{RawDrain, RawIntoIter, RawIter, RawTable};
+use crate::zen_hash_map::sip_hasher::DefaultHashBuilder;
+use crate::zen_vec::zen_vec::ZenVec;
+use core::{
+    alloc::AllocError,
+    borrow::Borrow,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem,
+};
+
+#[inline]
+pub(crate) fn make_hash<Q: Hash + ?Sized, S: BuildHasher>(hash_builder: &S, value: &Q) -> u64 {
+    hash_builder.hash_one(value)
+}
+
+// A hash map with open addressing and SwissTable-style control bytes.
+// Every operation that may allocate is fallible, and a failed allocation
+// while growing leaves the map unchanged.
+pub struct ZenHashMap<K, V, S = DefaultHashBuilder> {
+    hash_builder: S,
+    table: RawTable<(K, V)>,
+}
+
+impl<K, V> ZenHashMap<K, V, DefaultHashBuilder> {
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultHashBuilder::new())
+    }
+
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::new())
+    }
+}
+
+impl<K, V, S> ZenHashMap<K, V, S> {
+    pub const fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            hash_builder,
+            table: RawTable::new(),
+        }
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            hash_builder,
+            table: RawTable::with_capacity(capacity)?,
+        })
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    // Number of elements the map can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.table.retain(|(key, value)| f(key, value));
+    }
+
+    // Removes every entry, keeping the allocation for reuse.
+    pub fn drain(&mut self) -> Drain<'_, K, V> {
+        Drain {
+            inner: self.table.drain(),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.table.iter(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.table.iter(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            inner: self.iter_mut(),
+        }
+    }
+}
+
+impl<K, V, S> ZenHashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let hash_builder = &self.hash_builder;
+        self.table
+            .reserve(additional, |(key, _)| make_hash(hash_builder, key))
+    }
+
+    // Inserts a key-value pair, returning the previous value for `key`.
+    // On allocation failure the map is unchanged and the pair is dropped.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, TryReserveError> {
+        let hash = make_hash(&self.hash_builder, &key);
+        if let Some(index) = self.table.find(hash, |(k, _)| *k == key) {
+            let slot = unsafe { &mut (*self.table.bucket(index)).1 };
+            return Ok(Some(mem::replace(slot, value)));
+        }
+
+        self.try_reserve(1)?;
+        self.table.insert_no_grow(hash, (key, value));
+        Ok(None)
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = make_hash(&self.hash_builder, key);
+        self.table.find(hash, |(k, _)| k.borrow() == key)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.find(key)?;
+        let (k, v) = unsafe { &*self.table.bucket(index) };
+        Some((k, v))
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.find(key)?;
+        unsafe { Some(&mut (*self.table.bucket(index)).1) }
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.find(key)?;
+        unsafe { Some(self.table.remove(index)) }
+    }
+
+    // Gets the entry for `key`. Room for one more element is reserved
+    // before a vacant entry is returned, so inserting through it can't fail.
+    pub fn entry(&mut self, key: K) -> Result<Entry<'_, K, V, S>, TryReserveError> {
+        let hash = make_hash(&self.hash_builder, &key);
+        if let Some(index) = self.table.find(hash, |(k, _)| *k == key) {
+            return Ok(Entry::Occupied(OccupiedEntry {
+                key: Some(key),
+                index,
+                map: self,
+            }));
+        }
+
+        self.try_reserve(1)?;
+        Ok(Entry::Vacant(VacantEntry {
+            hash,
+            key,
+            map: self,
+        }))
+    }
+}
+
+impl<K, V, S: Default> Default for ZenHashMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S> TryClone for ZenHashMap<K, V, S>
+where
+    K: Eq + Hash + TryClone,
+    V: TryClone,
+    S: BuildHasher + Clone,
+{
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut map = ZenHashMap::with_capacity_and_hasher(self.len(), self.hash_builder.clone())?;
+        for (key, value) in self.iter() {
+            map.try_insert(key.try_clone()?, value.try_clone()?)?;
+        }
+        Ok(map)
+    }
+}
+
+impl<K, V, S> TryFromIterator<(K, V)> for ZenHashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn try_from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Result<Self, AllocError> {
+        let mut map = ZenHashMap::with_hasher(S::default());
+        map.try_extend(iter)?;
+        Ok(map)
+    }
+}
+
+// The pairs are collected before the map is touched and room for all of
+// them is reserved up front, so a failed extend leaves the map unchanged.
+impl<K, V, S> TryExtend<(K, V)> for ZenHashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn try_extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let pairs = ZenVec::try_from_iter(iter)?;
+        self.try_reserve(pairs.len())?;
+
+        for (key, value) in pairs {
+            /* Room for every pair was reserved above, so this can't fail. */
+            let _ = self.try_insert(key, value);
+        }
+        Ok(())
+    }
+}
+
+impl<K, V, S> PartialEq for ZenHashMap<K, V, S>
+where
+    K: Eq + Hash,
+    V: PartialEq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key).is_some_and(|v| *value == *v))
+    }
+}
+
+impl<K, V, S> Eq for ZenHashMap<K, V, S>
+where
+    K: Eq + Hash,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for ZenHashMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S> IntoIterator for ZenHashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter {
+            inner: RawIntoIter::new(self.table),
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a ZenHashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut ZenHashMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+pub enum Entry<'a, K, V, S> {
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+pub struct OccupiedEntry<'a, K, V, S> {
+    key: Option<K>,
+    index: usize,
+    map: &'a mut ZenHashMap<K, V, S>,
+}
+
+impl<'a, K, V, S> OccupiedEntry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        unsafe { &(*self.map.table.bucket(self.index)).0 }
+    }
+
+    pub fn get(&self) -> &V {
+        unsafe { &(*self.map.table.bucket(self.index)).1 }
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        unsafe { &mut (*self.map.table.bucket(self.index)).1 }
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        unsafe { &mut (*self.map.table.bucket(self.index)).1 }
+    }
+
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    // Replaces the stored key with the one the entry was looked up with.
+    pub fn replace_key(mut self) -> K {
+        let slot = unsafe { &mut (*self.map.table.bucket(self.index)).0 };
+        match self.key.take() {
+            Some(key) => mem::replace(slot, key),
+            /* `key` is only taken here, and this consumes the entry. */
+            None => unsafe { core::hint::unreachable_unchecked() },
+        }
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        unsafe { self.map.table.remove(self.index) }
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+}
+
+pub struct VacantEntry<'a, K, V, S> {
+    hash: u64,
+    key: K,
+    map: &'a mut ZenHashMap<K, V, S>,
+}
+
+impl<'a, K, V, S> VacantEntry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    // Inserts the value. Room was reserved when the entry was created.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let index = self.map.table.insert_no_grow(self.hash, (self.key, value));
+        unsafe { &mut (*self.map.table.bucket(index)).1 }
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    inner: RawIter<(K, V)>,
+    marker: PhantomData<&'a (K, V)>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        self.inner.next().map(|bucket| {
+            let (key, value) = unsafe { bucket.as_ref() };
+            (key, value)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+unsafe impl<K: Sync, V: Sync> Send for Iter<'_, K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for Iter<'_, K, V> {}
+
+pub struct IterMut<'a, K, V> {
+    inner: RawIter<(K, V)>,
+    marker: PhantomData<&'a mut (K, V)>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        self.inner.next().map(|mut bucket| {
+            let (key, value) = unsafe { bucket.as_mut() };
+            (&*key, value)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+unsafe impl<K: Sync, V: Send> Send for IterMut<'_, K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for IterMut<'_, K, V> {}
+
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<&'a V> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<&'a mut V> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
+
+pub struct IntoIter<K, V> {
+    inner: RawIntoIter<(K, V)>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+pub struct Drain<'a, K, V> {
+    inner: RawDrain<'a, (K, V)>,
+}
+
+impl<K, V> Iterator for Drain<'_, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Drain<'_, K, V> {}
+
+impl<K, V> FusedIterator for Drain<'_, K, V> {}
@@ -0,0 +1,307 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_error::zen_error::TryReserveError;
+use crate::zen_hash_map::sip_hasher::DefaultHashBuilder;
+use crate::zen_hash_map::zen_hash_map::{self, ZenHashMap};
+use core::{
+    alloc::AllocError,
+    borrow::Borrow,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::FusedIterator,
+};
+
+// A hash set built on ZenHashMap with unit values.
+pub struct ZenHashSet<T, S = DefaultHashBuilder> {
+    map: ZenHashMap<T, (), S>,
+}
+
+impl<T> ZenHashSet<T, DefaultHashBuilder> {
+    pub fn new() -> Self {
+        Self {
+            map: ZenHashMap::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            map: ZenHashMap::with_capacity(capacity)?,
+        })
+    }
+}
+
+impl<T, S> ZenHashSet<T, S> {
+    pub const fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: ZenHashMap::with_hasher(hash_builder),
+        }
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            map: ZenHashMap::with_capacity_and_hasher(capacity, hash_builder)?,
+        })
+    }
+
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain(|value, _| f(value));
+    }
+
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain {
+            inner: self.map.drain(),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.map.keys(),
+        }
+    }
+}
+
+impl<T, S> ZenHashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.map.try_reserve(additional)
+    }
+
+    // Adds a value, returning whether it was newly inserted. An existing
+    // equal value is left in place and `value` is dropped.
+    pub fn try_insert(&mut self, value: T) -> Result<bool, TryReserveError> {
+        match self.map.entry(value)? {
+            zen_hash_map::Entry::Occupied(_) => Ok(false),
+            zen_hash_map::Entry::Vacant(entry) => {
+                entry.insert(());
+                Ok(true)
+            }
+        }
+    }
+
+    // Adds a value, replacing and returning an existing equal one.
+    pub fn try_replace(&mut self, value: T) -> Result<Option<T>, TryReserveError> {
+        match self.map.entry(value)? {
+            zen_hash_map::Entry::Occupied(entry) => Ok(Some(entry.replace_key())),
+            zen_hash_map::Entry::Vacant(entry) => {
+                entry.insert(());
+                Ok(None)
+            }
+        }
+    }
+
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get_key_value(value).map(|(key, _)| key)
+    }
+
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove_entry(value).map(|(key, _)| key)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|value| other.contains(value))
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.iter().all(|value| !other.contains(value))
+    }
+}
+
+impl<T, S: Default> Default for ZenHashSet<T, S> {
+    fn default() -> Self {
+        Self {
+            map: ZenHashMap::default(),
+        }
+    }
+}
+
+impl<T, S> TryClone for ZenHashSet<T, S>
+where
+    T: Eq + Hash + TryClone,
+    S: BuildHasher + Clone,
+{
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(Self {
+            map: self.map.try_clone()?,
+        })
+    }
+}
+
+impl<T, S> TryFromIterator<T> for ZenHashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, AllocError> {
+        let mut set = ZenHashSet::with_hasher(S::default());
+        set.try_extend(iter)?;
+        Ok(set)
+    }
+}
+
+// Leaves the set unchanged on failure, like `ZenHashMap::try_extend`.
+impl<T, S> TryExtend<T> for ZenHashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        self.map
+            .try_extend(iter.into_iter().map(|value| (value, ())))
+    }
+}
+
+impl<T, S> PartialEq for ZenHashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+
+impl<T, S> Eq for ZenHashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for ZenHashSet<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S> IntoIterator for ZenHashSet<T, S> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            inner: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a ZenHashSet<T, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: zen_hash_map::Keys<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: zen_hash_map::IntoIter<T, ()>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+pub struct Drain<'a, T> {
+    inner: zen_hash_map::Drain<'a, T, ()>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
@@ -0,0 +1,130 @@
+use crate::zen_arc::zen_arc::ZenArc;
+use crate::zen_box::zen_box::ZenBox;
+use core::fmt;
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+// An owning pointer that can be turned into a raw pointer to its value and
+// back. Intrusive collections keep their elements in the raw form.
+/// # Safety
+///
+/// `from_raw` must accept every pointer `into_raw` returns, and the value
+/// must not move while it is in raw form.
+pub unsafe trait IntrusivePointer {
+    type Target;
+
+    fn into_raw(this: Self) -> *const Self::Target;
+
+    /// # Safety
+    ///
+    /// `ptr` must come from `into_raw` and be turned back only once.
+    unsafe fn from_raw(ptr: *const Self::Target) -> Self;
+}
+
+unsafe impl<T> IntrusivePointer for Pin<ZenBox<T>> {
+    type Target = T;
+
+    fn into_raw(this: Self) -> *const T {
+        ZenBox::into_raw(unsafe { Pin::into_inner_unchecked(this) })
+    }
+
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        Pin::new_unchecked(ZenBox::from_raw(ptr as *mut T))
+    }
+}
+
+unsafe impl<T> IntrusivePointer for ZenArc<T> {
+    type Target = T;
+
+    fn into_raw(this: Self) -> *const T {
+        ZenArc::into_raw(this)
+    }
+
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        ZenArc::from_raw(ptr)
+    }
+}
+
+unsafe impl<T> IntrusivePointer for &'static T {
+    type Target = T;
+
+    fn into_raw(this: Self) -> *const T {
+        this
+    }
+
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        &*ptr
+    }
+}
+
+pub type Target<A> = <<A as Adapter>::Pointer as IntrusivePointer>::Target;
+
+// Ties a collection to the link field embedded in its values. Usually
+// implemented with `intrusive_adapter!`.
+/// # Safety
+///
+/// `get_link` and `get_value` must be inverses, mapping a value to a link
+/// field inside that same value.
+pub unsafe trait Adapter {
+    type Link;
+    type Pointer: IntrusivePointer;
+
+    /// # Safety
+    ///
+    /// `value` must point to a live value.
+    unsafe fn get_link(&self, value: *const Target<Self>) -> NonNull<Self::Link>;
+
+    /// # Safety
+    ///
+    /// `link` must be the link field of a live value.
+    unsafe fn get_value(&self, link: NonNull<Self::Link>) -> *const Target<Self>;
+}
+
+// Extracts the key ordering values in a `RBTree`.
+pub trait KeyAdapter: Adapter {
+    type Key: Ord;
+
+    fn get_key(&self, value: &Target<Self>) -> Self::Key;
+}
+
+// Returned when inserting a value whose link is already part of a
+// collection. Holds the pointer that could not be inserted.
+#[derive(PartialEq, Eq)]
+pub struct AlreadyLinked<P>(pub P);
+
+/* Smart pointers rarely implement `Debug`, so the pointer is not shown. */
+impl<P> fmt::Debug for AlreadyLinked<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AlreadyLinked(..)")
+    }
+}
+
+impl<P> AlreadyLinked<P> {
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+// Defines a unit struct implementing `Adapter` for one link field:
+//
+//     intrusive_adapter!(pub TaskAdapter = Pin<ZenBox<Task>>: Task { link: LinkedListLink });
+#[macro_export]
+macro_rules! intrusive_adapter {
+    ($vis:vis $name:ident = $pointer:ty : $value:ty { $field:ident : $link:ty }) => {
+        #[derive(Clone, Copy, Default)]
+        $vis struct $name;
+
+        unsafe impl $crate::Adapter for $name {
+            type Link = $link;
+            type Pointer = $pointer;
+
+            unsafe fn get_link(&self, value: *const $value) -> ::core::ptr::NonNull<$link> {
+                ::core::ptr::NonNull::new_unchecked(::core::ptr::addr_of!((*value).$field) as *mut $link)
+            }
+
+            unsafe fn get_value(&self, link: ::core::ptr::NonNull<$link>) -> *const $value {
+                (link.as_ptr() as *const u8).sub(::core::mem::offset_of!($value, $field)) as *const $value
+            }
+        }
+    };
+}
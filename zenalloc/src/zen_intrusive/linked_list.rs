@@ -0,0 +1,398 @@
+use crate::zen_intrusive::adapter::{Adapter, AlreadyLinked, IntrusivePointer, Target};
+use core::cell::Cell;
+use core::iter::FusedIterator;
+use core::ptr::NonNull;
+
+type LinkPtr = NonNull<LinkedListLink>;
+
+// Link field for `LinkedList`, embedded in the values.
+pub struct LinkedListLink {
+    next: Cell<Option<LinkPtr>>,
+    prev: Cell<Option<LinkPtr>>,
+    linked: Cell<bool>,
+}
+
+impl LinkedListLink {
+    pub const fn new() -> Self {
+        Self {
+            next: Cell::new(None),
+            prev: Cell::new(None),
+            linked: Cell::new(false),
+        }
+    }
+
+    pub fn is_linked(&self) -> bool {
+        self.linked.get()
+    }
+}
+
+impl Default for LinkedListLink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/* Links are only touched through the list that owns them. */
+unsafe impl Send for LinkedListLink {}
+
+// A doubly linked list whose links live inside the values, so pushing and
+// unlinking never allocate. The list owns the pointers it holds.
+pub struct LinkedList<A: Adapter<Link = LinkedListLink>> {
+    head: Option<LinkPtr>,
+    tail: Option<LinkPtr>,
+    adapter: A,
+}
+
+impl<A: Adapter<Link = LinkedListLink>> LinkedList<A> {
+    pub const fn new(adapter: A) -> Self {
+        Self {
+            head: None,
+            tail: None,
+            adapter,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    // A cursor at the null position, between the back and the front.
+    pub fn cursor(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: None,
+            list: self,
+        }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            current: None,
+            list: self,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must point to a value in this list.
+    pub unsafe fn cursor_from_ptr(&self, ptr: *const Target<A>) -> Cursor<'_, A> {
+        Cursor {
+            current: Some(self.adapter.get_link(ptr)),
+            list: self,
+        }
+    }
+
+    // Gives O(1) access to a value known to be in the list, for example to
+    // unlink it.
+    /// # Safety
+    ///
+    /// `ptr` must point to a value in this list.
+    pub unsafe fn cursor_mut_from_ptr(&mut self, ptr: *const Target<A>) -> CursorMut<'_, A> {
+        CursorMut {
+            current: Some(self.adapter.get_link(ptr)),
+            list: self,
+        }
+    }
+
+    pub fn front(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: self.head,
+            list: self,
+        }
+    }
+
+    pub fn back(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    pub fn front_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            current: self.head,
+            list: self,
+        }
+    }
+
+    pub fn back_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, A> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            list: self,
+        }
+    }
+
+    // Marks the value's link as used, or hands the pointer back.
+    fn acquire(&self, ptr: A::Pointer) -> Result<LinkPtr, AlreadyLinked<A::Pointer>> {
+        let raw = IntrusivePointer::into_raw(ptr);
+        unsafe {
+            let link = self.adapter.get_link(raw);
+            if link.as_ref().is_linked() {
+                return Err(AlreadyLinked(IntrusivePointer::from_raw(raw)));
+            }
+            link.as_ref().linked.set(true);
+            Ok(link)
+        }
+    }
+
+    unsafe fn release(&self, link: LinkPtr) -> A::Pointer {
+        link.as_ref().linked.set(false);
+        IntrusivePointer::from_raw(self.adapter.get_value(link))
+    }
+
+    unsafe fn link_between(&mut self, link: LinkPtr, prev: Option<LinkPtr>, next: Option<LinkPtr>) {
+        link.as_ref().prev.set(prev);
+        link.as_ref().next.set(next);
+        match prev {
+            Some(prev) => prev.as_ref().next.set(Some(link)),
+            None => self.head = Some(link),
+        }
+        match next {
+            Some(next) => next.as_ref().prev.set(Some(link)),
+            None => self.tail = Some(link),
+        }
+    }
+
+    unsafe fn unlink(&mut self, link: LinkPtr) -> A::Pointer {
+        let prev = link.as_ref().prev.get();
+        let next = link.as_ref().next.get();
+        match prev {
+            Some(prev) => prev.as_ref().next.set(next),
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => next.as_ref().prev.set(prev),
+            None => self.tail = prev,
+        }
+        self.release(link)
+    }
+
+    pub fn push_front(&mut self, ptr: A::Pointer) -> Result<(), AlreadyLinked<A::Pointer>> {
+        let link = self.acquire(ptr)?;
+        unsafe { self.link_between(link, None, self.head) };
+        Ok(())
+    }
+
+    pub fn push_back(&mut self, ptr: A::Pointer) -> Result<(), AlreadyLinked<A::Pointer>> {
+        let link = self.acquire(ptr)?;
+        unsafe { self.link_between(link, self.tail, None) };
+        Ok(())
+    }
+
+    pub fn pop_front(&mut self) -> Option<A::Pointer> {
+        let head = self.head?;
+        Some(unsafe { self.unlink(head) })
+    }
+
+    pub fn pop_back(&mut self) -> Option<A::Pointer> {
+        let tail = self.tail?;
+        Some(unsafe { self.unlink(tail) })
+    }
+
+    // Unlinks every value and drops the pointers.
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+impl<A: Adapter<Link = LinkedListLink> + Default> Default for LinkedList<A> {
+    fn default() -> Self {
+        Self::new(A::default())
+    }
+}
+
+impl<A: Adapter<Link = LinkedListLink>> Drop for LinkedList<A> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+unsafe impl<A: Adapter<Link = LinkedListLink> + Send> Send for LinkedList<A> where A::Pointer: Send {}
+
+impl<'a, A: Adapter<Link = LinkedListLink>> IntoIterator for &'a LinkedList<A> {
+    type Item = &'a Target<A>;
+    type IntoIter = Iter<'a, A>;
+
+    fn into_iter(self) -> Iter<'a, A> {
+        self.iter()
+    }
+}
+
+// A position in a list: a value, or the null position between the back
+// and the front.
+pub struct Cursor<'a, A: Adapter<Link = LinkedListLink>> {
+    current: Option<LinkPtr>,
+    list: &'a LinkedList<A>,
+}
+
+impl<A: Adapter<Link = LinkedListLink>> Clone for Cursor<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current,
+            list: self.list,
+        }
+    }
+}
+
+impl<'a, A: Adapter<Link = LinkedListLink>> Cursor<'a, A> {
+    pub fn is_null(&self) -> bool {
+        self.current.is_none()
+    }
+
+    pub fn get(&self) -> Option<&'a Target<A>> {
+        self.current
+            .map(|link| unsafe { &*self.list.adapter.get_value(link) })
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = next_of(self.list, self.current);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = prev_of(self.list, self.current);
+    }
+
+    pub fn peek_next(&self) -> Option<&'a Target<A>> {
+        next_of(self.list, self.current).map(|link| unsafe { &*self.list.adapter.get_value(link) })
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a Target<A>> {
+        prev_of(self.list, self.current).map(|link| unsafe { &*self.list.adapter.get_value(link) })
+    }
+}
+
+fn next_of<A: Adapter<Link = LinkedListLink>>(
+    list: &LinkedList<A>,
+    current: Option<LinkPtr>,
+) -> Option<LinkPtr> {
+    match current {
+        Some(link) => unsafe { link.as_ref().next.get() },
+        None => list.head,
+    }
+}
+
+fn prev_of<A: Adapter<Link = LinkedListLink>>(
+    list: &LinkedList<A>,
+    current: Option<LinkPtr>,
+) -> Option<LinkPtr> {
+    match current {
+        Some(link) => unsafe { link.as_ref().prev.get() },
+        None => list.tail,
+    }
+}
+
+// A cursor that can also unlink and insert values.
+pub struct CursorMut<'a, A: Adapter<Link = LinkedListLink>> {
+    current: Option<LinkPtr>,
+    list: &'a mut LinkedList<A>,
+}
+
+impl<A: Adapter<Link = LinkedListLink>> CursorMut<'_, A> {
+    pub fn is_null(&self) -> bool {
+        self.current.is_none()
+    }
+
+    pub fn get(&self) -> Option<&Target<A>> {
+        self.current
+            .map(|link| unsafe { &*self.list.adapter.get_value(link) })
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = next_of(self.list, self.current);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = prev_of(self.list, self.current);
+    }
+
+    pub fn peek_next(&self) -> Option<&Target<A>> {
+        next_of(self.list, self.current).map(|link| unsafe { &*self.list.adapter.get_value(link) })
+    }
+
+    pub fn peek_prev(&self) -> Option<&Target<A>> {
+        prev_of(self.list, self.current).map(|link| unsafe { &*self.list.adapter.get_value(link) })
+    }
+
+    pub fn as_cursor(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: self.current,
+            list: self.list,
+        }
+    }
+
+    // Unlinks the current value in O(1) and moves to the next one.
+    pub fn remove(&mut self) -> Option<A::Pointer> {
+        let link = self.current?;
+        self.current = unsafe { link.as_ref().next.get() };
+        Some(unsafe { self.list.unlink(link) })
+    }
+
+    // Inserts after the current value, or at the front when null.
+    pub fn insert_after(&mut self, ptr: A::Pointer) -> Result<(), AlreadyLinked<A::Pointer>> {
+        let link = self.list.acquire(ptr)?;
+        let next = next_of(self.list, self.current);
+        unsafe { self.list.link_between(link, self.current, next) };
+        Ok(())
+    }
+
+    // Inserts before the current value, or at the back when null.
+    pub fn insert_before(&mut self, ptr: A::Pointer) -> Result<(), AlreadyLinked<A::Pointer>> {
+        let link = self.list.acquire(ptr)?;
+        let prev = prev_of(self.list, self.current);
+        unsafe { self.list.link_between(link, prev, self.current) };
+        Ok(())
+    }
+}
+
+pub struct Iter<'a, A: Adapter<Link = LinkedListLink>> {
+    head: Option<LinkPtr>,
+    tail: Option<LinkPtr>,
+    list: &'a LinkedList<A>,
+}
+
+impl<A: Adapter<Link = LinkedListLink>> Clone for Iter<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            head: self.head,
+            tail: self.tail,
+            list: self.list,
+        }
+    }
+}
+
+impl<'a, A: Adapter<Link = LinkedListLink>> Iterator for Iter<'a, A> {
+    type Item = &'a Target<A>;
+
+    fn next(&mut self) -> Option<&'a Target<A>> {
+        let link = self.head?;
+        if self.head == self.tail {
+            self.head = None;
+            self.tail = None;
+        } else {
+            self.head = unsafe { link.as_ref().next.get() };
+        }
+        Some(unsafe { &*self.list.adapter.get_value(link) })
+    }
+}
+
+impl<'a, A: Adapter<Link = LinkedListLink>> DoubleEndedIterator for Iter<'a, A> {
+    fn next_back(&mut self) -> Option<&'a Target<A>> {
+        let link = self.tail?;
+        if self.head == self.tail {
+            self.head = None;
+            self.tail = None;
+        } else {
+            self.tail = unsafe { link.as_ref().prev.get() };
+        }
+        Some(unsafe { &*self.list.adapter.get_value(link) })
+    }
+}
+
+impl<A: Adapter<Link = LinkedListLink>> FusedIterator for Iter<'_, A> {}
@@ -0,0 +1,4 @@
+pub mod adapter;
+pub mod linked_list;
+pub mod rb_tree;
+pub mod singly_linked_list;
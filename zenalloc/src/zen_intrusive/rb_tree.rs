@@ -0,0 +1,781 @@
+use crate::zen_intrusive::adapter::{Adapter, AlreadyLinked, IntrusivePointer, KeyAdapter, Target};
+use core::cell::Cell;
+use core::iter::FusedIterator;
+use core::ops::Bound;
+use core::ptr::NonNull;
+
+type LinkPtr = NonNull<RBTreeLink>;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Unlinked,
+    Red,
+    Black,
+}
+
+// Link field for `RBTree`, embedded in the values.
+pub struct RBTreeLink {
+    left: Cell<Option<LinkPtr>>,
+    right: Cell<Option<LinkPtr>>,
+    parent: Cell<Option<LinkPtr>>,
+    color: Cell<Color>,
+}
+
+impl RBTreeLink {
+    pub const fn new() -> Self {
+        Self {
+            left: Cell::new(None),
+            right: Cell::new(None),
+            parent: Cell::new(None),
+            color: Cell::new(Color::Unlinked),
+        }
+    }
+
+    pub fn is_linked(&self) -> bool {
+        self.color.get() != Color::Unlinked
+    }
+}
+
+impl Default for RBTreeLink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/* Links are only touched through the tree that owns them. */
+unsafe impl Send for RBTreeLink {}
+
+#[inline]
+unsafe fn left(link: LinkPtr) -> Option<LinkPtr> {
+    link.as_ref().left.get()
+}
+
+#[inline]
+unsafe fn right(link: LinkPtr) -> Option<LinkPtr> {
+    link.as_ref().right.get()
+}
+
+#[inline]
+unsafe fn parent(link: LinkPtr) -> Option<LinkPtr> {
+    link.as_ref().parent.get()
+}
+
+#[inline]
+unsafe fn set_left(link: LinkPtr, child: Option<LinkPtr>) {
+    link.as_ref().left.set(child);
+}
+
+#[inline]
+unsafe fn set_right(link: LinkPtr, child: Option<LinkPtr>) {
+    link.as_ref().right.set(child);
+}
+
+#[inline]
+unsafe fn set_parent(link: LinkPtr, parent: Option<LinkPtr>) {
+    link.as_ref().parent.set(parent);
+}
+
+#[inline]
+unsafe fn color(link: LinkPtr) -> Color {
+    link.as_ref().color.get()
+}
+
+#[inline]
+unsafe fn set_color(link: LinkPtr, color: Color) {
+    link.as_ref().color.set(color);
+}
+
+// Missing children count as black.
+#[inline]
+unsafe fn is_red(link: Option<LinkPtr>) -> bool {
+    link.is_some_and(|link| color(link) == Color::Red)
+}
+
+unsafe fn first_in(mut link: LinkPtr) -> LinkPtr {
+    while let Some(left) = left(link) {
+        link = left;
+    }
+    link
+}
+
+unsafe fn last_in(mut link: LinkPtr) -> LinkPtr {
+    while let Some(right) = right(link) {
+        link = right;
+    }
+    link
+}
+
+unsafe fn successor(link: LinkPtr) -> Option<LinkPtr> {
+    if let Some(right) = right(link) {
+        return Some(first_in(right));
+    }
+    let mut child = link;
+    while let Some(parent) = parent(child) {
+        if left(parent) == Some(child) {
+            return Some(parent);
+        }
+        child = parent;
+    }
+    None
+}
+
+unsafe fn predecessor(link: LinkPtr) -> Option<LinkPtr> {
+    if let Some(left) = left(link) {
+        return Some(last_in(left));
+    }
+    let mut child = link;
+    while let Some(parent) = parent(child) {
+        if right(parent) == Some(child) {
+            return Some(parent);
+        }
+        child = parent;
+    }
+    None
+}
+
+// A red-black tree whose links live inside the values, ordered by the key
+// the adapter extracts. Equal keys are kept in insertion order.
+pub struct RBTree<A: Adapter<Link = RBTreeLink>> {
+    root: Option<LinkPtr>,
+    adapter: A,
+}
+
+impl<A: Adapter<Link = RBTreeLink>> RBTree<A> {
+    pub const fn new(adapter: A) -> Self {
+        Self {
+            root: None,
+            adapter,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    // A cursor at the null position, between the back and the front.
+    pub fn cursor(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: None,
+            tree: self,
+        }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            current: None,
+            tree: self,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must point to a value in this tree.
+    pub unsafe fn cursor_from_ptr(&self, ptr: *const Target<A>) -> Cursor<'_, A> {
+        Cursor {
+            current: Some(self.adapter.get_link(ptr)),
+            tree: self,
+        }
+    }
+
+    // Gives access to a value known to be in the tree without searching
+    // for it, for example to unlink it.
+    /// # Safety
+    ///
+    /// `ptr` must point to a value in this tree.
+    pub unsafe fn cursor_mut_from_ptr(&mut self, ptr: *const Target<A>) -> CursorMut<'_, A> {
+        CursorMut {
+            current: Some(self.adapter.get_link(ptr)),
+            tree: self,
+        }
+    }
+
+    fn first(&self) -> Option<LinkPtr> {
+        self.root.map(|root| unsafe { first_in(root) })
+    }
+
+    fn last(&self) -> Option<LinkPtr> {
+        self.root.map(|root| unsafe { last_in(root) })
+    }
+
+    pub fn front(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: self.first(),
+            tree: self,
+        }
+    }
+
+    pub fn back(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: self.last(),
+            tree: self,
+        }
+    }
+
+    pub fn front_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            current: self.first(),
+            tree: self,
+        }
+    }
+
+    pub fn back_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            current: self.last(),
+            tree: self,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, A> {
+        Iter {
+            head: self.first(),
+            tail: self.last(),
+            tree: self,
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<A::Pointer> {
+        self.front_mut().remove()
+    }
+
+    pub fn pop_back(&mut self) -> Option<A::Pointer> {
+        self.back_mut().remove()
+    }
+
+    // Unlinks every value and drops the pointers.
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    unsafe fn value(&self, link: LinkPtr) -> &Target<A> {
+        &*self.adapter.get_value(link)
+    }
+
+    unsafe fn rotate_left(&mut self, x: LinkPtr) {
+        let y = right(x).unwrap_unchecked();
+        set_right(x, left(y));
+        if let Some(child) = left(y) {
+            set_parent(child, Some(x));
+        }
+        self.replace_child(x, Some(y));
+        set_left(y, Some(x));
+        set_parent(x, Some(y));
+    }
+
+    unsafe fn rotate_right(&mut self, x: LinkPtr) {
+        let y = left(x).unwrap_unchecked();
+        set_left(x, right(y));
+        if let Some(child) = right(y) {
+            set_parent(child, Some(x));
+        }
+        self.replace_child(x, Some(y));
+        set_right(y, Some(x));
+        set_parent(x, Some(y));
+    }
+
+    // Puts `new` where `old` hangs from its parent.
+    unsafe fn replace_child(&mut self, old: LinkPtr, new: Option<LinkPtr>) {
+        let parent = parent(old);
+        match parent {
+            None => self.root = new,
+            Some(parent) if left(parent) == Some(old) => set_left(parent, new),
+            Some(parent) => set_right(parent, new),
+        }
+        if let Some(new) = new {
+            set_parent(new, parent);
+        }
+    }
+
+    unsafe fn insert_fixup(&mut self, mut z: LinkPtr) {
+        while let Some(mut p) = parent(z).filter(|&p| color(p) == Color::Red) {
+            /* A red node is never the root, so the grandparent exists. */
+            let g = parent(p).unwrap_unchecked();
+            if left(g) == Some(p) {
+                let uncle = right(g);
+                if is_red(uncle) {
+                    set_color(p, Color::Black);
+                    set_color(uncle.unwrap_unchecked(), Color::Black);
+                    set_color(g, Color::Red);
+                    z = g;
+                    continue;
+                }
+                if right(p) == Some(z) {
+                    self.rotate_left(p);
+                    (z, p) = (p, z);
+                }
+                set_color(p, Color::Black);
+                set_color(g, Color::Red);
+                self.rotate_right(g);
+            } else {
+                let uncle = left(g);
+                if is_red(uncle) {
+                    set_color(p, Color::Black);
+                    set_color(uncle.unwrap_unchecked(), Color::Black);
+                    set_color(g, Color::Red);
+                    z = g;
+                    continue;
+                }
+                if left(p) == Some(z) {
+                    self.rotate_right(p);
+                    (z, p) = (p, z);
+                }
+                set_color(p, Color::Black);
+                set_color(g, Color::Red);
+                self.rotate_left(g);
+            }
+        }
+        if let Some(root) = self.root {
+            set_color(root, Color::Black);
+        }
+    }
+
+    // Unlinks `z` and restores the red-black properties. Other values keep
+    // their place in memory; only links change.
+    unsafe fn unlink(&mut self, z: LinkPtr) -> A::Pointer {
+        let mut removed_color = color(z);
+        let x;
+        let x_parent;
+        if left(z).is_none() {
+            x = right(z);
+            x_parent = parent(z);
+            self.replace_child(z, x);
+        } else if right(z).is_none() {
+            x = left(z);
+            x_parent = parent(z);
+            self.replace_child(z, x);
+        } else {
+            let y = first_in(right(z).unwrap_unchecked());
+            removed_color = color(y);
+            x = right(y);
+            if parent(y) == Some(z) {
+                x_parent = Some(y);
+            } else {
+                x_parent = parent(y);
+                self.replace_child(y, x);
+                set_right(y, right(z));
+                set_parent(right(y).unwrap_unchecked(), Some(y));
+            }
+            self.replace_child(z, Some(y));
+            set_left(y, left(z));
+            set_parent(left(y).unwrap_unchecked(), Some(y));
+            set_color(y, color(z));
+        }
+        if removed_color == Color::Black {
+            self.remove_fixup(x, x_parent);
+        }
+
+        set_left(z, None);
+        set_right(z, None);
+        set_parent(z, None);
+        set_color(z, Color::Unlinked);
+        IntrusivePointer::from_raw(self.adapter.get_value(z))
+    }
+
+    // `x` carries an extra black; `parent` is its parent, since `x` may be
+    // a missing child.
+    unsafe fn remove_fixup(&mut self, mut x: Option<LinkPtr>, mut parent: Option<LinkPtr>) {
+        while x != self.root && !is_red(x) {
+            let Some(p) = parent else {
+                break;
+            };
+            if left(p) == x {
+                /* The sibling side has a higher black height, so it exists. */
+                let mut w = right(p).unwrap_unchecked();
+                if color(w) == Color::Red {
+                    set_color(w, Color::Black);
+                    set_color(p, Color::Red);
+                    self.rotate_left(p);
+                    w = right(p).unwrap_unchecked();
+                }
+                if !is_red(left(w)) && !is_red(right(w)) {
+                    set_color(w, Color::Red);
+                    x = Some(p);
+                    parent = self::parent(p);
+                } else {
+                    if !is_red(right(w)) {
+                        set_color(left(w).unwrap_unchecked(), Color::Black);
+                        set_color(w, Color::Red);
+                        self.rotate_right(w);
+                        w = right(p).unwrap_unchecked();
+                    }
+                    set_color(w, color(p));
+                    set_color(p, Color::Black);
+                    set_color(right(w).unwrap_unchecked(), Color::Black);
+                    self.rotate_left(p);
+                    x = self.root;
+                    break;
+                }
+            } else {
+                let mut w = left(p).unwrap_unchecked();
+                if color(w) == Color::Red {
+                    set_color(w, Color::Black);
+                    set_color(p, Color::Red);
+                    self.rotate_right(p);
+                    w = left(p).unwrap_unchecked();
+                }
+                if !is_red(left(w)) && !is_red(right(w)) {
+                    set_color(w, Color::Red);
+                    x = Some(p);
+                    parent = self::parent(p);
+                } else {
+                    if !is_red(left(w)) {
+                        set_color(right(w).unwrap_unchecked(), Color::Black);
+                        set_color(w, Color::Red);
+                        self.rotate_left(w);
+                        w = left(p).unwrap_unchecked();
+                    }
+                    set_color(w, color(p));
+                    set_color(p, Color::Black);
+                    set_color(left(w).unwrap_unchecked(), Color::Black);
+                    self.rotate_right(p);
+                    x = self.root;
+                    break;
+                }
+            }
+        }
+        if let Some(x) = x {
+            set_color(x, Color::Black);
+        }
+    }
+}
+
+impl<A: KeyAdapter<Link = RBTreeLink>> RBTree<A> {
+    // Links a value into the tree, after any values with an equal key.
+    pub fn insert(&mut self, ptr: A::Pointer) -> Result<(), AlreadyLinked<A::Pointer>> {
+        let raw = IntrusivePointer::into_raw(ptr);
+        unsafe {
+            let link = self.adapter.get_link(raw);
+            if link.as_ref().is_linked() {
+                return Err(AlreadyLinked(IntrusivePointer::from_raw(raw)));
+            }
+
+            let key = self.adapter.get_key(&*raw);
+            let mut parent = None;
+            let mut go_left = false;
+            let mut next = self.root;
+            while let Some(node) = next {
+                parent = Some(node);
+                go_left = key < self.adapter.get_key(self.value(node));
+                next = if go_left { left(node) } else { right(node) };
+            }
+
+            set_left(link, None);
+            set_right(link, None);
+            set_parent(link, parent);
+            set_color(link, Color::Red);
+            match parent {
+                None => self.root = Some(link),
+                Some(parent) if go_left => set_left(parent, Some(link)),
+                Some(parent) => set_right(parent, Some(link)),
+            }
+            self.insert_fixup(link);
+        }
+        Ok(())
+    }
+
+    // The first value whose key is above `bound`.
+    fn lower_bound_link(&self, bound: Bound<&A::Key>) -> Option<LinkPtr> {
+        let mut result = None;
+        let mut next = self.root;
+        while let Some(node) = next {
+            let key = self.adapter.get_key(unsafe { self.value(node) });
+            let above = match bound {
+                Bound::Included(bound) => key >= *bound,
+                Bound::Excluded(bound) => key > *bound,
+                Bound::Unbounded => true,
+            };
+            next = unsafe {
+                if above {
+                    result = Some(node);
+                    left(node)
+                } else {
+                    right(node)
+                }
+            };
+        }
+        result
+    }
+
+    // The last value whose key is below `bound`.
+    fn upper_bound_link(&self, bound: Bound<&A::Key>) -> Option<LinkPtr> {
+        let mut result = None;
+        let mut next = self.root;
+        while let Some(node) = next {
+            let key = self.adapter.get_key(unsafe { self.value(node) });
+            let below = match bound {
+                Bound::Included(bound) => key <= *bound,
+                Bound::Excluded(bound) => key < *bound,
+                Bound::Unbounded => true,
+            };
+            next = unsafe {
+                if below {
+                    result = Some(node);
+                    right(node)
+                } else {
+                    left(node)
+                }
+            };
+        }
+        result
+    }
+
+    // A cursor at the first value with this key, or at the null position.
+    pub fn find(&self, key: &A::Key) -> Cursor<'_, A> {
+        let current = self
+            .lower_bound_link(Bound::Included(key))
+            .filter(|&link| self.adapter.get_key(unsafe { self.value(link) }) == *key);
+        Cursor {
+            current,
+            tree: self,
+        }
+    }
+
+    pub fn find_mut(&mut self, key: &A::Key) -> CursorMut<'_, A> {
+        let current = self.find(key).current;
+        CursorMut {
+            current,
+            tree: self,
+        }
+    }
+
+    pub fn lower_bound(&self, bound: Bound<&A::Key>) -> Cursor<'_, A> {
+        Cursor {
+            current: self.lower_bound_link(bound),
+            tree: self,
+        }
+    }
+
+    pub fn upper_bound(&self, bound: Bound<&A::Key>) -> Cursor<'_, A> {
+        Cursor {
+            current: self.upper_bound_link(bound),
+            tree: self,
+        }
+    }
+
+    pub fn lower_bound_mut(&mut self, bound: Bound<&A::Key>) -> CursorMut<'_, A> {
+        CursorMut {
+            current: self.lower_bound_link(bound),
+            tree: self,
+        }
+    }
+
+    pub fn upper_bound_mut(&mut self, bound: Bound<&A::Key>) -> CursorMut<'_, A> {
+        CursorMut {
+            current: self.upper_bound_link(bound),
+            tree: self,
+        }
+    }
+}
+
+#[cfg(test)]
+impl<A: KeyAdapter<Link = RBTreeLink>> RBTree<A> {
+    // Checks parent links, key order and the red-black properties.
+    pub(crate) fn assert_valid(&self) {
+        unsafe fn black_height<A: KeyAdapter<Link = RBTreeLink>>(
+            tree: &RBTree<A>,
+            link: Option<LinkPtr>,
+        ) -> usize {
+            let Some(link) = link else {
+                return 1;
+            };
+            assert!(color(link) != Color::Unlinked);
+            if color(link) == Color::Red {
+                assert!(!is_red(left(link)) && !is_red(right(link)), "red node with red child");
+            }
+            for child in [left(link), right(link)].into_iter().flatten() {
+                assert!(parent(child) == Some(link));
+            }
+            let key = tree.adapter.get_key(tree.value(link));
+            if let Some(l) = left(link) {
+                assert!(tree.adapter.get_key(tree.value(l)) <= key);
+            }
+            if let Some(r) = right(link) {
+                assert!(tree.adapter.get_key(tree.value(r)) >= key);
+            }
+            let height = black_height(tree, left(link));
+            assert_eq!(height, black_height(tree, right(link)), "unequal black height");
+            height + (color(link) == Color::Black) as usize
+        }
+
+        if let Some(root) = self.root {
+            unsafe {
+                assert!(parent(root).is_none());
+                assert!(color(root) == Color::Black);
+            }
+        }
+        unsafe { black_height(self, self.root) };
+    }
+}
+
+impl<A: Adapter<Link = RBTreeLink> + Default> Default for RBTree<A> {
+    fn default() -> Self {
+        Self::new(A::default())
+    }
+}
+
+impl<A: Adapter<Link = RBTreeLink>> Drop for RBTree<A> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+unsafe impl<A: Adapter<Link = RBTreeLink> + Send> Send for RBTree<A> where A::Pointer: Send {}
+
+impl<'a, A: Adapter<Link = RBTreeLink>> IntoIterator for &'a RBTree<A> {
+    type Item = &'a Target<A>;
+    type IntoIter = Iter<'a, A>;
+
+    fn into_iter(self) -> Iter<'a, A> {
+        self.iter()
+    }
+}
+
+fn next_of<A: Adapter<Link = RBTreeLink>>(tree: &RBTree<A>, current: Option<LinkPtr>) -> Option<LinkPtr> {
+    match current {
+        Some(link) => unsafe { successor(link) },
+        None => tree.first(),
+    }
+}
+
+fn prev_of<A: Adapter<Link = RBTreeLink>>(tree: &RBTree<A>, current: Option<LinkPtr>) -> Option<LinkPtr> {
+    match current {
+        Some(link) => unsafe { predecessor(link) },
+        None => tree.last(),
+    }
+}
+
+// A position in a tree: a value, or the null position between the back
+// and the front.
+pub struct Cursor<'a, A: Adapter<Link = RBTreeLink>> {
+    current: Option<LinkPtr>,
+    tree: &'a RBTree<A>,
+}
+
+impl<A: Adapter<Link = RBTreeLink>> Clone for Cursor<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current,
+            tree: self.tree,
+        }
+    }
+}
+
+impl<'a, A: Adapter<Link = RBTreeLink>> Cursor<'a, A> {
+    pub fn is_null(&self) -> bool {
+        self.current.is_none()
+    }
+
+    pub fn get(&self) -> Option<&'a Target<A>> {
+        self.current.map(|link| unsafe { self.tree.value(link) })
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = next_of(self.tree, self.current);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = prev_of(self.tree, self.current);
+    }
+
+    pub fn peek_next(&self) -> Option<&'a Target<A>> {
+        next_of(self.tree, self.current).map(|link| unsafe { self.tree.value(link) })
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a Target<A>> {
+        prev_of(self.tree, self.current).map(|link| unsafe { self.tree.value(link) })
+    }
+}
+
+// A cursor that can also unlink values.
+pub struct CursorMut<'a, A: Adapter<Link = RBTreeLink>> {
+    current: Option<LinkPtr>,
+    tree: &'a mut RBTree<A>,
+}
+
+impl<A: Adapter<Link = RBTreeLink>> CursorMut<'_, A> {
+    pub fn is_null(&self) -> bool {
+        self.current.is_none()
+    }
+
+    pub fn get(&self) -> Option<&Target<A>> {
+        self.current.map(|link| unsafe { self.tree.value(link) })
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = next_of(self.tree, self.current);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = prev_of(self.tree, self.current);
+    }
+
+    pub fn peek_next(&self) -> Option<&Target<A>> {
+        next_of(self.tree, self.current).map(|link| unsafe { self.tree.value(link) })
+    }
+
+    pub fn peek_prev(&self) -> Option<&Target<A>> {
+        prev_of(self.tree, self.current).map(|link| unsafe { self.tree.value(link) })
+    }
+
+    pub fn as_cursor(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: self.current,
+            tree: self.tree,
+        }
+    }
+
+    // Unlinks the current value without searching for it and moves to the
+    // next one.
+    pub fn remove(&mut self) -> Option<A::Pointer> {
+        let link = self.current?;
+        unsafe {
+            self.current = successor(link);
+            Some(self.tree.unlink(link))
+        }
+    }
+}
+
+pub struct Iter<'a, A: Adapter<Link = RBTreeLink>> {
+    head: Option<LinkPtr>,
+    tail: Option<LinkPtr>,
+    tree: &'a RBTree<A>,
+}
+
+impl<A: Adapter<Link = RBTreeLink>> Clone for Iter<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            head: self.head,
+            tail: self.tail,
+            tree: self.tree,
+        }
+    }
+}
+
+impl<'a, A: Adapter<Link = RBTreeLink>> Iterator for Iter<'a, A> {
+    type Item = &'a Target<A>;
+
+    fn next(&mut self) -> Option<&'a Target<A>> {
+        let link = self.head?;
+        if self.head == self.tail {
+            self.head = None;
+            self.tail = None;
+        } else {
+            self.head = unsafe { successor(link) };
+        }
+        Some(unsafe { self.tree.value(link) })
+    }
+}
+
+impl<'a, A: Adapter<Link = RBTreeLink>> DoubleEndedIterator for Iter<'a, A> {
+    fn next_back(&mut self) -> Option<&'a Target<A>> {
+        let link = self.tail?;
+        if self.head == self.tail {
+            self.head = None;
+            self.tail = None;
+        } else {
+            self.tail = unsafe { predecessor(link) };
+        }
+        Some(unsafe { self.tree.value(link) })
+    }
+}
+
+impl<A: Adapter<Link = RBTreeLink>> FusedIterator for Iter<'_, A> {}
@@ -0,0 +1,301 @@
+use crate::zen_intrusive::adapter::{Adapter, AlreadyLinked, IntrusivePointer, Target};
+use core::cell::Cell;
+use core::iter::FusedIterator;
+use core::ptr::NonNull;
+
+type LinkPtr = NonNull<SinglyLinkedListLink>;
+
+// Link field for `SinglyLinkedList`, embedded in the values.
+pub struct SinglyLinkedListLink {
+    next: Cell<Option<LinkPtr>>,
+    linked: Cell<bool>,
+}
+
+impl SinglyLinkedListLink {
+    pub const fn new() -> Self {
+        Self {
+            next: Cell::new(None),
+            linked: Cell::new(false),
+        }
+    }
+
+    pub fn is_linked(&self) -> bool {
+        self.linked.get()
+    }
+}
+
+impl Default for SinglyLinkedListLink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/* Links are only touched through the list that owns them. */
+unsafe impl Send for SinglyLinkedListLink {}
+
+// A singly linked list whose links live inside the values. Values can be
+// unlinked in O(1) from a cursor at the value before them.
+pub struct SinglyLinkedList<A: Adapter<Link = SinglyLinkedListLink>> {
+    head: Option<LinkPtr>,
+    adapter: A,
+}
+
+impl<A: Adapter<Link = SinglyLinkedListLink>> SinglyLinkedList<A> {
+    pub const fn new(adapter: A) -> Self {
+        Self {
+            head: None,
+            adapter,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    // A cursor at the null position, just before the front.
+    pub fn cursor(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: None,
+            list: self,
+        }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            current: None,
+            list: self,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must point to a value in this list.
+    pub unsafe fn cursor_from_ptr(&self, ptr: *const Target<A>) -> Cursor<'_, A> {
+        Cursor {
+            current: Some(self.adapter.get_link(ptr)),
+            list: self,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must point to a value in this list.
+    pub unsafe fn cursor_mut_from_ptr(&mut self, ptr: *const Target<A>) -> CursorMut<'_, A> {
+        CursorMut {
+            current: Some(self.adapter.get_link(ptr)),
+            list: self,
+        }
+    }
+
+    pub fn front(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: self.head,
+            list: self,
+        }
+    }
+
+    pub fn front_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            current: self.head,
+            list: self,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, A> {
+        Iter {
+            current: self.head,
+            list: self,
+        }
+    }
+
+    fn acquire(&self, ptr: A::Pointer) -> Result<LinkPtr, AlreadyLinked<A::Pointer>> {
+        let raw = IntrusivePointer::into_raw(ptr);
+        unsafe {
+            let link = self.adapter.get_link(raw);
+            if link.as_ref().is_linked() {
+                return Err(AlreadyLinked(IntrusivePointer::from_raw(raw)));
+            }
+            link.as_ref().linked.set(true);
+            Ok(link)
+        }
+    }
+
+    unsafe fn release(&self, link: LinkPtr) -> A::Pointer {
+        link.as_ref().linked.set(false);
+        IntrusivePointer::from_raw(self.adapter.get_value(link))
+    }
+
+    pub fn push_front(&mut self, ptr: A::Pointer) -> Result<(), AlreadyLinked<A::Pointer>> {
+        let link = self.acquire(ptr)?;
+        unsafe { link.as_ref().next.set(self.head) };
+        self.head = Some(link);
+        Ok(())
+    }
+
+    pub fn pop_front(&mut self) -> Option<A::Pointer> {
+        let head = self.head?;
+        unsafe {
+            self.head = head.as_ref().next.get();
+            Some(self.release(head))
+        }
+    }
+
+    // Unlinks every value and drops the pointers.
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+impl<A: Adapter<Link = SinglyLinkedListLink> + Default> Default for SinglyLinkedList<A> {
+    fn default() -> Self {
+        Self::new(A::default())
+    }
+}
+
+impl<A: Adapter<Link = SinglyLinkedListLink>> Drop for SinglyLinkedList<A> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+unsafe impl<A: Adapter<Link = SinglyLinkedListLink> + Send> Send for SinglyLinkedList<A> where
+    A::Pointer: Send
+{
+}
+
+impl<'a, A: Adapter<Link = SinglyLinkedListLink>> IntoIterator for &'a SinglyLinkedList<A> {
+    type Item = &'a Target<A>;
+    type IntoIter = Iter<'a, A>;
+
+    fn into_iter(self) -> Iter<'a, A> {
+        self.iter()
+    }
+}
+
+fn next_of<A: Adapter<Link = SinglyLinkedListLink>>(
+    list: &SinglyLinkedList<A>,
+    current: Option<LinkPtr>,
+) -> Option<LinkPtr> {
+    match current {
+        Some(link) => unsafe { link.as_ref().next.get() },
+        None => list.head,
+    }
+}
+
+// A position in a list: a value, or the null position before the front.
+pub struct Cursor<'a, A: Adapter<Link = SinglyLinkedListLink>> {
+    current: Option<LinkPtr>,
+    list: &'a SinglyLinkedList<A>,
+}
+
+impl<A: Adapter<Link = SinglyLinkedListLink>> Clone for Cursor<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current,
+            list: self.list,
+        }
+    }
+}
+
+impl<'a, A: Adapter<Link = SinglyLinkedListLink>> Cursor<'a, A> {
+    pub fn is_null(&self) -> bool {
+        self.current.is_none()
+    }
+
+    pub fn get(&self) -> Option<&'a Target<A>> {
+        self.current
+            .map(|link| unsafe { &*self.list.adapter.get_value(link) })
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = next_of(self.list, self.current);
+    }
+
+    pub fn peek_next(&self) -> Option<&'a Target<A>> {
+        next_of(self.list, self.current).map(|link| unsafe { &*self.list.adapter.get_value(link) })
+    }
+}
+
+// A cursor that can also unlink and insert values after its position.
+pub struct CursorMut<'a, A: Adapter<Link = SinglyLinkedListLink>> {
+    current: Option<LinkPtr>,
+    list: &'a mut SinglyLinkedList<A>,
+}
+
+impl<A: Adapter<Link = SinglyLinkedListLink>> CursorMut<'_, A> {
+    pub fn is_null(&self) -> bool {
+        self.current.is_none()
+    }
+
+    pub fn get(&self) -> Option<&Target<A>> {
+        self.current
+            .map(|link| unsafe { &*self.list.adapter.get_value(link) })
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = next_of(self.list, self.current);
+    }
+
+    pub fn peek_next(&self) -> Option<&Target<A>> {
+        next_of(self.list, self.current).map(|link| unsafe { &*self.list.adapter.get_value(link) })
+    }
+
+    pub fn as_cursor(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: self.current,
+            list: self.list,
+        }
+    }
+
+    // Unlinks the value after the cursor in O(1), or the front when null.
+    pub fn remove_next(&mut self) -> Option<A::Pointer> {
+        let next = next_of(self.list, self.current)?;
+        unsafe {
+            let after = next.as_ref().next.get();
+            match self.current {
+                Some(link) => link.as_ref().next.set(after),
+                None => self.list.head = after,
+            }
+            Some(self.list.release(next))
+        }
+    }
+
+    // Inserts after the cursor, or at the front when null.
+    pub fn insert_after(&mut self, ptr: A::Pointer) -> Result<(), AlreadyLinked<A::Pointer>> {
+        let link = self.list.acquire(ptr)?;
+        unsafe {
+            link.as_ref().next.set(next_of(self.list, self.current));
+            match self.current {
+                Some(current) => current.as_ref().next.set(Some(link)),
+                None => self.list.head = Some(link),
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Iter<'a, A: Adapter<Link = SinglyLinkedListLink>> {
+    current: Option<LinkPtr>,
+    list: &'a SinglyLinkedList<A>,
+}
+
+impl<A: Adapter<Link = SinglyLinkedListLink>> Clone for Iter<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current,
+            list: self.list,
+        }
+    }
+}
+
+impl<'a, A: Adapter<Link = SinglyLinkedListLink>> Iterator for Iter<'a, A> {
+    type Item = &'a Target<A>;
+
+    fn next(&mut self) -> Option<&'a Target<A>> {
+        let link = self.current?;
+        self.current = unsafe { link.as_ref().next.get() };
+        Some(unsafe { &*self.list.adapter.get_value(link) })
+    }
+}
+
+impl<A: Adapter<Link = SinglyLinkedListLink>> FusedIterator for Iter<'_, A> {}
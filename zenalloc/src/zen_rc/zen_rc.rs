@@ -1,10 +1,15 @@
 use core::cell::Cell;
 use core::ptr::NonNull;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
 use core::ops::Deref;
 use core::alloc::{Layout, AllocError};
 use core::ptr;
 use crate::System;
 use crate::alloc_trait::Allocator;
+use crate::try_clone::TryClone;
 
 pub struct ZenRc<T> {
     ptr: NonNull<ZenRcBox<T>>,
@@ -31,18 +36,32 @@ impl<T> ZenRc<T> {
         }
     }
 
+    // `Default` can't report allocation failure, so this stands in for it.
+    pub fn try_default() -> Result<Self, AllocError>
+    where
+        T: Default,
+    {
+        Self::new(T::default())
+    }
+
     pub fn strong_count(this: &Self) -> usize {
         unsafe { this.ptr.as_ref().ref_count.get() }
     }
+}
 
-    pub fn clone(this: &Self) -> Self {
-        let count = ZenRc::strong_count(this);
+impl<T> Clone for ZenRc<T> {
+    fn clone(&self) -> Self {
+        let count = ZenRc::strong_count(self);
         unsafe {
-            this.ptr.as_ref().ref_count.set(count + 1);
-        }
-        ZenRc {
-            ptr: this.ptr,
+            self.ptr.as_ref().ref_count.set(count + 1);
         }
+        ZenRc { ptr: self.ptr }
+    }
+}
+
+impl<T> TryClone for ZenRc<T> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(self.clone())
     }
 }
 
@@ -71,5 +90,61 @@ impl<T> Drop for ZenRc<T> {
     }
 }
 
+impl<T: fmt::Debug> fmt::Debug for ZenRc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for ZenRc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T> fmt::Pointer for ZenRc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&(&**self as *const T), f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for ZenRc<T> {
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(&**self, &**other)
+    }
+}
+
+impl<T: Eq> Eq for ZenRc<T> {}
+
+impl<T: PartialOrd> PartialOrd for ZenRc<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord> Ord for ZenRc<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T: Hash> Hash for ZenRc<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T> Borrow<T> for ZenRc<T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T> AsRef<T> for ZenRc<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
 unsafe impl<T: Send + Sync> Send for ZenRc<T> {}
 unsafe impl<T: Sync> Sync for ZenRc<T> {}
\ No newline at end of file
@@ -0,0 +1,356 @@
+use crate::macros::try_box;
+use crate::zen_arc::zen_arc::ZenArc;
+use crate::zen_box::zen_box::ZenBox;
+use crate::zen_btree_map::zen_btree_map::ZenBTreeMap;
+use crate::zen_btree_map::zen_btree_set::ZenBTreeSet;
+use crate::zen_error::zen_error::ZenError;
+use crate::zen_hash_map::zen_hash_map::ZenHashMap;
+use crate::zen_hash_map::zen_hash_set::ZenHashSet;
+use crate::zen_rc::zen_rc::ZenRc;
+use crate::zen_string::zen_ascii_string::ZenAsciiString;
+use crate::zen_string::zen_string::ZenString;
+use crate::zen_vec::zen_vec::ZenVec;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use core::mem;
+use serde::de::{
+    self, Deserialize, DeserializeSeed, Deserializer, Error as _, MapAccess, SeqAccess, Unexpected, Visitor,
+};
+use serde::ser::{Serialize, Serializer};
+
+// Serialization goes through the same shapes as the `alloc` equivalents, so
+// the wire format is interchangeable with `Vec`, `Box`, `String` and the
+// std maps. Deserialization never aborts: allocation failure becomes the
+// deserializer's own error.
+
+// Length prefixes come from the input, so a hostile one can ask for any
+// capacity it likes. Deserialized containers reserve at most this many
+// bytes up front and grow as elements actually arrive.
+pub const DEFAULT_MAX_PREALLOC_BYTES: usize = 1024 * 1024;
+
+// Deserializes a `T` whose up-front reservation is capped at `max_bytes`
+// instead of `DEFAULT_MAX_PREALLOC_BYTES`. The cap belongs to this one call
+// and only covers the outer container; its elements use their own impls.
+pub struct MaxPrealloc<T> {
+    max_bytes: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T> MaxPrealloc<T> {
+    pub const fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            marker: PhantomData,
+        }
+    }
+}
+
+// How many `T`s to reserve for a length hint.
+fn cautious_capacity<T>(hint: Option<usize>, max_bytes: usize) -> usize {
+    match mem::size_of::<T>() {
+        0 => 0,
+        size => hint.unwrap_or(0).min(max_bytes / size),
+    }
+}
+
+impl<T: Serialize> Serialize for ZenVec<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for ZenVec<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        MaxPrealloc::<Self>::new(DEFAULT_MAX_PREALLOC_BYTES).deserialize(deserializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for MaxPrealloc<ZenVec<T>> {
+    type Value = ZenVec<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<ZenVec<T>, D::Error> {
+        struct VecVisitor<T>(usize, PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for VecVisitor<T> {
+            type Value = ZenVec<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ZenVec<T>, A::Error> {
+                let capacity = cautious_capacity::<T>(seq.size_hint(), self.0);
+                let mut vec = ZenVec::with_capacity(capacity).map_err(A::Error::custom)?;
+                while let Some(value) = seq.next_element()? {
+                    vec.push(value).map_err(A::Error::custom)?;
+                }
+                Ok(vec)
+            }
+        }
+
+        deserializer.deserialize_seq(VecVisitor(self.max_bytes, PhantomData))
+    }
+}
+
+impl<T: Serialize> Serialize for ZenBox<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for ZenBox<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        try_box(T::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+// Shared pointers serialize their value; a deserialized pointer is always a
+// fresh allocation, so sharing between pointers is not preserved.
+impl<T: Serialize> Serialize for ZenArc<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for ZenArc<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ZenArc::new(T::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl<T: Serialize> Serialize for ZenRc<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for ZenRc<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ZenRc::new(T::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for ZenAsciiString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ZenAsciiString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AsciiVisitor;
+
+        impl Visitor<'_> for AsciiVisitor {
+            type Value = ZenAsciiString;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an ASCII string")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<ZenAsciiString, E> {
+                ZenAsciiString::from_str(v).map_err(|err| match err {
+                    ZenError::NotAscii(_) => E::invalid_value(Unexpected::Str(v), &self),
+                    err => E::custom(err),
+                })
+            }
+        }
+
+        deserializer.deserialize_str(AsciiVisitor)
+    }
+}
+
+impl Serialize for ZenString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ZenString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StringVisitor;
+
+        impl Visitor<'_> for StringVisitor {
+            type Value = ZenString;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<ZenString, E> {
+                ZenString::from_str(v).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(StringVisitor)
+    }
+}
+
+impl<K: Serialize, V: Serialize, S> Serialize for ZenHashMap<K, V, S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<'de, K, V, S> Deserialize<'de> for ZenHashMap<K, V, S>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        MaxPrealloc::<Self>::new(DEFAULT_MAX_PREALLOC_BYTES).deserialize(deserializer)
+    }
+}
+
+impl<'de, K, V, S> DeserializeSeed<'de> for MaxPrealloc<ZenHashMap<K, V, S>>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    type Value = ZenHashMap<K, V, S>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        struct MapVisitor<K, V, S>(usize, PhantomData<(K, V, S)>);
+
+        impl<'de, K, V, S> Visitor<'de> for MapVisitor<K, V, S>
+        where
+            K: Deserialize<'de> + Eq + Hash,
+            V: Deserialize<'de>,
+            S: BuildHasher + Default,
+        {
+            type Value = ZenHashMap<K, V, S>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let capacity = cautious_capacity::<(K, V)>(access.size_hint(), self.0);
+                let mut map = ZenHashMap::with_capacity_and_hasher(capacity, S::default())
+                    .map_err(A::Error::custom)?;
+                while let Some((key, value)) = access.next_entry()? {
+                    map.try_insert(key, value).map_err(A::Error::custom)?;
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(MapVisitor(self.max_bytes, PhantomData))
+    }
+}
+
+impl<T: Serialize, S> Serialize for ZenHashSet<T, S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T, S> Deserialize<'de> for ZenHashSet<T, S>
+where
+    T: Deserialize<'de> + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        MaxPrealloc::<Self>::new(DEFAULT_MAX_PREALLOC_BYTES).deserialize(deserializer)
+    }
+}
+
+impl<'de, T, S> DeserializeSeed<'de> for MaxPrealloc<ZenHashSet<T, S>>
+where
+    T: Deserialize<'de> + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    type Value = ZenHashSet<T, S>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        struct SetVisitor<T, S>(usize, PhantomData<(T, S)>);
+
+        impl<'de, T, S> Visitor<'de> for SetVisitor<T, S>
+        where
+            T: Deserialize<'de> + Eq + Hash,
+            S: BuildHasher + Default,
+        {
+            type Value = ZenHashSet<T, S>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let capacity = cautious_capacity::<T>(seq.size_hint(), self.0);
+                let mut set = ZenHashSet::with_capacity_and_hasher(capacity, S::default())
+                    .map_err(A::Error::custom)?;
+                while let Some(value) = seq.next_element()? {
+                    set.try_insert(value).map_err(A::Error::custom)?;
+                }
+                Ok(set)
+            }
+        }
+
+        deserializer.deserialize_seq(SetVisitor(self.max_bytes, PhantomData))
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for ZenBTreeMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+// B-tree nodes are allocated as entries arrive, so there is nothing to
+// pre-allocate from the length hint.
+impl<'de, K: Deserialize<'de> + Ord, V: Deserialize<'de>> Deserialize<'de> for ZenBTreeMap<K, V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MapVisitor<K, V>(PhantomData<(K, V)>);
+
+        impl<'de, K: Deserialize<'de> + Ord, V: Deserialize<'de>> Visitor<'de> for MapVisitor<K, V> {
+            type Value = ZenBTreeMap<K, V>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut map = ZenBTreeMap::new();
+                while let Some((key, value)) = access.next_entry()? {
+                    map.try_insert(key, value).map_err(A::Error::custom)?;
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(MapVisitor(PhantomData))
+    }
+}
+
+impl<T: Serialize> Serialize for ZenBTreeSet<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for ZenBTreeSet<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SetVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de> + Ord> Visitor<'de> for SetVisitor<T> {
+            type Value = ZenBTreeSet<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut set = ZenBTreeSet::new();
+                while let Some(value) = seq.next_element()? {
+                    set.try_insert(value).map_err(A::Error::custom)?;
+                }
+                Ok(set)
+            }
+        }
+
+        deserializer.deserialize_seq(SetVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,540 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_error::zen_error::TryReserveError;
+use crate::zen_vec::zen_vec::{ZenVec, ZenVecIter, ZenVecIterMut};
+use crate::zen_vec::into_iter::IntoIter as VecIntoIter;
+use core::{alloc::AllocError, fmt, iter::Enumerate, iter::FusedIterator, mem};
+
+// Marks the end of the free list.
+const NO_FREE: u32 = u32::MAX;
+
+// A handle to a value in a `ZenSlab`. The generation tells apart values
+// that occupied the same slot at different times, so a key that outlived
+// its value never reaches the slot's next occupant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SlabKey {
+    index: u32,
+    generation: u32,
+}
+
+impl SlabKey {
+    pub const fn index(self) -> u32 {
+        self.index
+    }
+
+    pub const fn generation(self) -> u32 {
+        self.generation
+    }
+
+    // Packs the key into one integer, e.g. to hand it to user space.
+    pub const fn to_bits(self) -> u64 {
+        ((self.generation as u64) << 32) | self.index as u64
+    }
+
+    pub const fn from_bits(bits: u64) -> Self {
+        Self {
+            index: bits as u32,
+            generation: (bits >> 32) as u32,
+        }
+    }
+}
+
+enum Slot<T> {
+    Occupied(T),
+    Vacant { next_free: u32 },
+}
+
+struct Entry<T> {
+    generation: u32,
+    slot: Slot<T>,
+}
+
+// A slot map backed by a `ZenVec`. Freed slots are kept on a free list and
+// reused in O(1); each reuse bumps the slot's generation. A slot whose
+// generation is exhausted is retired instead of wrapping around.
+pub struct ZenSlab<T> {
+    entries: ZenVec<Entry<T>>,
+    free_head: u32,
+    /* Length of the free list, so capacity checks needn't walk it. */
+    free_count: usize,
+    len: usize,
+}
+
+impl<T> ZenSlab<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: ZenVec::new(),
+            free_head: NO_FREE,
+            free_count: 0,
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            entries: ZenVec::with_capacity(capacity)?,
+            free_head: NO_FREE,
+            free_count: 0,
+            len: 0,
+        })
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Number of values the slab can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.entries.cap() - self.entries.len() + self.free_count
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let needed = additional.saturating_sub(self.free_count);
+        self.entries.try_reserve(needed)
+    }
+
+    // Stores a value and returns its key.
+    pub fn try_insert(&mut self, value: T) -> Result<SlabKey, TryReserveError> {
+        self.try_insert_with_key(|_| value)
+    }
+
+    // Like `try_insert`, for values that need to know their own key. If `f`
+    // panics the slab is left as it was.
+    pub fn try_insert_with_key<F>(&mut self, f: F) -> Result<SlabKey, TryReserveError>
+    where
+        F: FnOnce(SlabKey) -> T,
+    {
+        if self.free_head != NO_FREE {
+            let index = self.free_head;
+            let key = SlabKey {
+                index,
+                generation: self.entries[index as usize].generation,
+            };
+            /* The slot is unlinked only once `f` has returned. */
+            let value = f(key);
+            let entry = &mut self.entries[index as usize];
+            if let Slot::Vacant { next_free } = entry.slot {
+                self.free_head = next_free;
+            }
+            entry.slot = Slot::Occupied(value);
+            self.free_count -= 1;
+            self.len += 1;
+            return Ok(key);
+        }
+
+        /* Every index has to fit in a key and stay below the free-list sentinel. */
+        let index = self.entries.len();
+        if index >= NO_FREE as usize {
+            return Err(TryReserveError::CapacityOverflow);
+        }
+        self.entries.try_reserve(1)?;
+        let key = SlabKey {
+            index: index as u32,
+            generation: 0,
+        };
+        let entry = Entry {
+            generation: 0,
+            slot: Slot::Occupied(f(key)),
+        };
+        /* Room was reserved above, so this can't fail. */
+        let _ = self.entries.push(entry);
+        self.len += 1;
+        Ok(key)
+    }
+
+    pub fn contains_key(&self, key: SlabKey) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn get(&self, key: SlabKey) -> Option<&T> {
+        match self.entries.get(key.index as usize)? {
+            Entry {
+                generation,
+                slot: Slot::Occupied(value),
+            } if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, key: SlabKey) -> Option<&mut T> {
+        match self.entries.get_mut(key.index as usize)? {
+            Entry {
+                generation,
+                slot: Slot::Occupied(value),
+            } if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    // Mutable access to several values at once. Returns `None` if any key
+    // is stale or two keys name the same value.
+    pub fn get_disjoint_mut<const N: usize>(&mut self, keys: [SlabKey; N]) -> Option<[&mut T; N]> {
+        for (i, key) in keys.iter().enumerate() {
+            if !self.contains_key(*key) || keys[..i].iter().any(|k| k.index == key.index) {
+                return None;
+            }
+        }
+
+        let entries = self.entries.ptr();
+        Some(core::array::from_fn(|i| unsafe {
+            match &mut (*entries.add(keys[i].index as usize)).slot {
+                Slot::Occupied(value) => &mut *(value as *mut T),
+                /* Every key was checked to be live above. */
+                Slot::Vacant { .. } => core::hint::unreachable_unchecked(),
+            }
+        }))
+    }
+
+    // Removes a value. Its key, and every copy of it, stops working.
+    pub fn remove(&mut self, key: SlabKey) -> Option<T> {
+        let index = key.index as usize;
+        let entry = self.entries.get_mut(index)?;
+        if entry.generation != key.generation || matches!(entry.slot, Slot::Vacant { .. }) {
+            return None;
+        }
+        Some(self.remove_at(index))
+    }
+
+    fn remove_at(&mut self, index: usize) -> T {
+        let entry = &mut self.entries[index];
+        let next_free = if entry.generation == u32::MAX {
+            /* Retired: reusing the slot would let old keys match again. */
+            NO_FREE
+        } else {
+            self.free_head
+        };
+        let slot = mem::replace(&mut entry.slot, Slot::Vacant { next_free });
+        if entry.generation != u32::MAX {
+            entry.generation += 1;
+            self.free_head = index as u32;
+            self.free_count += 1;
+        }
+        self.len -= 1;
+        match slot {
+            Slot::Occupied(value) => value,
+            /* Callers only pass occupied slots. */
+            Slot::Vacant { .. } => unsafe { core::hint::unreachable_unchecked() },
+        }
+    }
+
+    // Removes the values a failed `try_extend` inserted, given their keys in
+    // insertion order. Reused slots go back on the free list in reverse, which
+    // restores its order; slots past `original_entries` are dropped outright.
+    fn undo_extend<K>(&mut self, original_entries: usize, keys: K)
+    where
+        K: DoubleEndedIterator<Item = SlabKey>,
+    {
+        for key in keys.rev() {
+            if (key.index as usize) < original_entries {
+                let _ = self.remove_at(key.index as usize);
+            }
+        }
+        self.len -= self.entries.len() - original_entries;
+        self.entries.truncate(original_entries);
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(SlabKey, &mut T) -> bool,
+    {
+        for index in 0..self.entries.len() {
+            let entry = &mut self.entries[index];
+            let key = SlabKey {
+                index: index as u32,
+                generation: entry.generation,
+            };
+            if let Slot::Occupied(value) = &mut entry.slot {
+                if !f(key, value) {
+                    drop(self.remove_at(index));
+                }
+            }
+        }
+    }
+
+    // Removes every value. Generations are kept, so old keys stay invalid.
+    pub fn clear(&mut self) {
+        self.drain().for_each(drop);
+    }
+
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain {
+            slab: self,
+            index: 0,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            entries: self.entries.iter().enumerate(),
+            remaining: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            remaining: self.len,
+            entries: self.entries.iter_mut().enumerate(),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = SlabKey> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.iter_mut().map(|(_, value)| value)
+    }
+}
+
+impl<T> Default for ZenSlab<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The clone hands out the same keys for the same values.
+impl<T: TryClone> TryClone for ZenSlab<T> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut entries = ZenVec::with_capacity(self.entries.len())?;
+        for entry in self.entries.iter() {
+            let slot = match &entry.slot {
+                Slot::Occupied(value) => Slot::Occupied(value.try_clone()?),
+                Slot::Vacant { next_free } => Slot::Vacant {
+                    next_free: *next_free,
+                },
+            };
+            /* Room was reserved above, so this can't fail. */
+            let _ = entries.push(Entry {
+                generation: entry.generation,
+                slot,
+            });
+        }
+        Ok(Self {
+            entries,
+            free_head: self.free_head,
+            free_count: self.free_count,
+            len: self.len,
+        })
+    }
+}
+
+impl<T> TryFromIterator<T> for ZenSlab<T> {
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, AllocError> {
+        let mut slab = ZenSlab::new();
+        slab.try_extend(iter)?;
+        Ok(slab)
+    }
+}
+
+// The keys are not returned; use `try_insert` to keep them. On failure the
+// inserted values are removed again, so the slab holds what it held before.
+impl<T> TryExtend<T> for ZenSlab<T> {
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let iter = iter.into_iter();
+        let lower = iter.size_hint().0;
+        self.try_reserve(lower)?;
+        let mut inserted = ZenVec::with_capacity(lower)?;
+
+        let original_entries = self.entries.len();
+        for value in iter {
+            let result = match self.try_insert(value) {
+                Ok(key) => inserted.push(key).map_err(|err| (Some(key), err.into())),
+                Err(err) => Err((None, err.into())),
+            };
+            if let Err((unrecorded, err)) = result {
+                self.undo_extend(original_entries, inserted.iter().copied().chain(unrecorded));
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ZenSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ZenSlab<T> {
+    type Item = (SlabKey, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut ZenSlab<T> {
+    type Item = (SlabKey, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for ZenSlab<T> {
+    type Item = (SlabKey, T);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            remaining: self.len,
+            entries: self.entries.into_iter().enumerate(),
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    entries: Enumerate<ZenVecIter<'a, Entry<T>>>,
+    remaining: usize,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (SlabKey, &'a T);
+
+    fn next(&mut self) -> Option<(SlabKey, &'a T)> {
+        for (index, entry) in self.entries.by_ref() {
+            if let Slot::Occupied(value) = &entry.slot {
+                self.remaining -= 1;
+                let key = SlabKey {
+                    index: index as u32,
+                    generation: entry.generation,
+                };
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    entries: Enumerate<ZenVecIterMut<'a, Entry<T>>>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (SlabKey, &'a mut T);
+
+    fn next(&mut self) -> Option<(SlabKey, &'a mut T)> {
+        for (index, entry) in self.entries.by_ref() {
+            if let Slot::Occupied(value) = &mut entry.slot {
+                self.remaining -= 1;
+                let key = SlabKey {
+                    index: index as u32,
+                    generation: entry.generation,
+                };
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    entries: Enumerate<VecIntoIter<Entry<T>>>,
+    remaining: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (SlabKey, T);
+
+    fn next(&mut self) -> Option<(SlabKey, T)> {
+        for (index, entry) in self.entries.by_ref() {
+            if let Slot::Occupied(value) = entry.slot {
+                self.remaining -= 1;
+                let key = SlabKey {
+                    index: index as u32,
+                    generation: entry.generation,
+                };
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+// Removes values one slot at a time, so generations and the free list
+// stay consistent even if the iterator is leaked.
+pub struct Drain<'a, T> {
+    slab: &'a mut ZenSlab<T>,
+    index: usize,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = (SlabKey, T);
+
+    fn next(&mut self) -> Option<(SlabKey, T)> {
+        while self.index < self.slab.entries.len() {
+            let index = self.index;
+            self.index += 1;
+            let entry = &self.slab.entries[index];
+            if let Slot::Occupied(_) = entry.slot {
+                let key = SlabKey {
+                    index: index as u32,
+                    generation: entry.generation,
+                };
+                return Some((key, self.slab.remove_at(index)));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.slab.len, Some(self.slab.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
@@ -0,0 +1,751 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_vec::raw_vec::RawVec;
+use crate::zen_error::zen_error::{TryReserveError, ZenError};
+use crate::zen_vec::zen_vec::check_range;
+use core::{
+    alloc::AllocError,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut, RangeBounds},
+    ptr, slice,
+};
+
+// Storage for `ZenSmallVec`: up to `N` elements inline, or a heap buffer
+// once the vector has outgrown it. Elements are never moved back inline.
+enum SmallVecData<T, const N: usize> {
+    Inline([MaybeUninit<T>; N]),
+    Heap(RawVec<T>),
+}
+
+impl<T, const N: usize> SmallVecData<T, N> {
+    fn ptr(&self) -> *mut T {
+        match self {
+            SmallVecData::Inline(data) => data.as_ptr() as *mut T,
+            SmallVecData::Heap(buf) => buf.ptr().as_ptr(),
+        }
+    }
+
+    fn mut_ptr(&mut self) -> *mut T {
+        match self {
+            SmallVecData::Inline(data) => data.as_mut_ptr() as *mut T,
+            SmallVecData::Heap(buf) => buf.ptr().as_ptr(),
+        }
+    }
+}
+
+pub struct ZenSmallVec<T, const N: usize> {
+    data: SmallVecData<T, N>,
+    len: usize,
+}
+
+impl<T, const N: usize> ZenSmallVec<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            data: SmallVecData::Inline([const { MaybeUninit::uninit() }; N]),
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        if capacity <= N {
+            return Ok(Self::new());
+        }
+
+        Ok(Self {
+            data: SmallVecData::Heap(RawVec::with_capacity(capacity)?),
+            len: 0,
+        })
+    }
+
+    pub fn cap(&self) -> usize {
+        match &self.data {
+            SmallVecData::Inline(_) => N,
+            SmallVecData::Heap(buf) => buf.capacity(),
+        }
+    }
+
+    // Returns `true` once the elements have been moved to the heap.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, SmallVecData::Heap(_))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        match &mut self.data {
+            SmallVecData::Heap(buf) => buf.reserve(self.len, additional),
+            SmallVecData::Inline(_) => {
+                let required = self
+                    .len
+                    .checked_add(additional)
+                    .ok_or(TryReserveError::CapacityOverflow)?;
+                if required <= N {
+                    return Ok(());
+                }
+                self.spill(core::cmp::max(required, N.saturating_mul(2)))
+            }
+        }
+    }
+
+    // Like `try_reserve`, but without rounding up: a spill or reallocation
+    // makes room for exactly `additional` more elements.
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        match &mut self.data {
+            SmallVecData::Heap(buf) => buf.reserve_exact(self.len, additional),
+            SmallVecData::Inline(_) => {
+                let required = self
+                    .len
+                    .checked_add(additional)
+                    .ok_or(TryReserveError::CapacityOverflow)?;
+                if required <= N {
+                    return Ok(());
+                }
+                self.spill(required)
+            }
+        }
+    }
+
+    // Moves the inline elements into a heap buffer of `capacity` elements.
+    fn spill(&mut self, capacity: usize) -> Result<(), TryReserveError> {
+        let buf = RawVec::with_capacity(capacity)?;
+        unsafe {
+            ptr::copy_nonoverlapping(self.data.ptr(), buf.ptr().as_ptr(), self.len);
+        }
+        /* The inline slots are `MaybeUninit`, so replacing them drops nothing. */
+        self.data = SmallVecData::Heap(buf);
+        Ok(())
+    }
+
+    fn grow_one(&mut self) -> Result<(), TryReserveError> {
+        match &mut self.data {
+            SmallVecData::Heap(buf) => buf.grow(),
+            SmallVecData::Inline(_) => self.spill(core::cmp::max(N.saturating_mul(2), 1)),
+        }
+    }
+
+    pub fn push(&mut self, elem: T) -> Result<(), ZenError> {
+        if self.len == self.cap() {
+            self.grow_one()?;
+        }
+
+        unsafe {
+            ptr::write(self.data.mut_ptr().add(self.len), elem);
+        }
+
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            unsafe { Some(ptr::read(self.data.ptr().add(self.len))) }
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, elem: T) -> Result<(), ZenError> {
+        if index > self.len {
+            return Err(ZenError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+
+        if self.len == self.cap() {
+            self.grow_one()?;
+        }
+
+        unsafe {
+            let base = self.data.mut_ptr();
+            ptr::copy(base.add(index), base.add(index + 1), self.len - index);
+            ptr::write(base.add(index), elem);
+        }
+
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<T, ZenError> {
+        if index >= self.len {
+            return Err(ZenError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+
+        self.len -= 1;
+
+        unsafe {
+            let base = self.data.mut_ptr();
+            let result = ptr::read(base.add(index));
+            ptr::copy(base.add(index + 1), base.add(index), self.len - index);
+            Ok(result)
+        }
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, ZenError> {
+        if index >= self.len {
+            return Err(ZenError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+
+        unsafe {
+            let base = self.data.mut_ptr();
+            let value = ptr::read(base.add(index));
+            ptr::copy(base.add(self.len - 1), base.add(index), 1);
+            self.len -= 1;
+            Ok(value)
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let remaining = self.len - len;
+        unsafe {
+            let tail = ptr::slice_from_raw_parts_mut(self.data.mut_ptr().add(len), remaining);
+            self.len = len;
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let original_len = self.len;
+        if original_len == 0 {
+            return;
+        }
+
+        /* Hide the elements from the vector until the guard has closed the gaps. */
+        self.len = 0;
+
+        struct BackshiftOnDrop<'a, T, const N: usize> {
+            vec: &'a mut ZenSmallVec<T, N>,
+            processed: usize,
+            deleted: usize,
+            original_len: usize,
+        }
+
+        impl<T, const N: usize> Drop for BackshiftOnDrop<'_, T, N> {
+            fn drop(&mut self) {
+                if self.deleted > 0 {
+                    unsafe {
+                        let base = self.vec.data.mut_ptr();
+                        ptr::copy(
+                            base.add(self.processed),
+                            base.add(self.processed - self.deleted),
+                            self.original_len - self.processed,
+                        );
+                    }
+                }
+                self.vec.len = self.original_len - self.deleted;
+            }
+        }
+
+        let mut guard = BackshiftOnDrop {
+            vec: self,
+            processed: 0,
+            deleted: 0,
+            original_len,
+        };
+
+        while guard.processed != original_len {
+            let cur = unsafe { &mut *guard.vec.data.mut_ptr().add(guard.processed) };
+            if !f(cur) {
+                guard.processed += 1;
+                guard.deleted += 1;
+                unsafe { ptr::drop_in_place(cur) };
+                continue;
+            }
+            if guard.deleted > 0 {
+                unsafe {
+                    let hole = guard.vec.data.mut_ptr().add(guard.processed - guard.deleted);
+                    ptr::copy_nonoverlapping(cur, hole, 1);
+                }
+            }
+            guard.processed += 1;
+        }
+    }
+
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let len = self.len;
+        if len <= 1 {
+            return;
+        }
+
+        struct FillGapOnDrop<'a, T, const N: usize> {
+            read: usize,
+            write: usize,
+            vec: &'a mut ZenSmallVec<T, N>,
+        }
+
+        impl<T, const N: usize> Drop for FillGapOnDrop<'_, T, N> {
+            fn drop(&mut self) {
+                /* Only reached if `same_bucket` or a destructor panicked. */
+                unsafe {
+                    let ptr = self.vec.data.mut_ptr();
+                    let len = self.vec.len;
+                    let items_left = len - self.read;
+                    ptr::copy(ptr.add(self.read), ptr.add(self.write), items_left);
+                    self.vec.len = len - (self.read - self.write);
+                }
+            }
+        }
+
+        let mut gap = FillGapOnDrop {
+            read: 1,
+            write: 1,
+            vec: self,
+        };
+        let ptr = gap.vec.data.mut_ptr();
+
+        unsafe {
+            while gap.read < len {
+                let read_ptr = ptr.add(gap.read);
+                let prev_ptr = ptr.add(gap.write - 1);
+
+                if same_bucket(&mut *read_ptr, &mut *prev_ptr) {
+                    gap.read += 1;
+                    ptr::drop_in_place(read_ptr);
+                } else {
+                    ptr::copy(read_ptr, ptr.add(gap.write), 1);
+                    gap.write += 1;
+                    gap.read += 1;
+                }
+            }
+
+            gap.vec.len = gap.write;
+            mem::forget(gap);
+        }
+    }
+
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: PartialEq,
+        F: FnMut(&mut T) -> K,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    pub fn drain<R>(&mut self, range: R) -> Result<Drain<'_, T, N>, ZenError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let (start, end) = check_range(range, len)?;
+
+        /* Leaking the Drain leaks the drained and tail elements, never double drops them. */
+        self.len = start;
+        Ok(Drain {
+            vec: self,
+            start,
+            end,
+            tail_start: end,
+            tail_len: len - end,
+        })
+    }
+
+    // Moves `[at..]` into a new vector, which is inline if it fits.
+    pub fn try_split_off(&mut self, at: usize) -> Result<Self, ZenError> {
+        if at > self.len {
+            return Err(ZenError::IndexOutOfBounds {
+                index: at,
+                len: self.len,
+            });
+        }
+
+        let other_len = self.len - at;
+        let mut other = ZenSmallVec::with_capacity(other_len)?;
+
+        unsafe {
+            self.len = at;
+            ptr::copy_nonoverlapping(self.data.ptr().add(at), other.data.mut_ptr(), other_len);
+            other.len = other_len;
+        }
+
+        Ok(other)
+    }
+
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), ZenError> {
+        let count = other.len;
+        self.try_reserve(count)?;
+
+        unsafe {
+            ptr::copy_nonoverlapping(other.data.ptr(), self.data.mut_ptr().add(self.len), count);
+            other.len = 0;
+        }
+
+        self.len += count;
+        Ok(())
+    }
+
+    // Replaces `range` with the items of `replace_with` and returns the
+    // removed elements. Everything that can fail happens before the vector
+    // is touched, so on error `self` is left unchanged.
+    pub fn try_splice<R, I>(&mut self, range: R, replace_with: I) -> Result<Self, ZenError>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let (start, end) = check_range(range, self.len)?;
+
+        let iter = replace_with.into_iter();
+        let mut replacement = ZenSmallVec::<T, N>::new();
+        replacement.try_reserve(iter.size_hint().0)?;
+        for item in iter {
+            replacement.push(item)?;
+        }
+
+        let removed_len = end - start;
+        let mut removed = ZenSmallVec::with_capacity(removed_len)?;
+        if replacement.len > removed_len {
+            self.try_reserve(replacement.len - removed_len)?;
+        }
+
+        unsafe {
+            let base = self.data.mut_ptr();
+            let tail_len = self.len - end;
+
+            ptr::copy_nonoverlapping(base.add(start), removed.data.mut_ptr(), removed_len);
+            removed.len = removed_len;
+
+            ptr::copy(base.add(end), base.add(start + replacement.len), tail_len);
+            ptr::copy_nonoverlapping(replacement.data.ptr(), base.add(start), replacement.len);
+
+            self.len = start + replacement.len + tail_len;
+            replacement.len = 0;
+        }
+
+        Ok(removed)
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data.ptr(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.data.mut_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Drop for ZenSmallVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for ZenSmallVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for ZenSmallVec<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ZenSmallVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: TryClone, const N: usize> TryClone for ZenSmallVec<T, N> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut vec = ZenSmallVec::with_capacity(self.len)?;
+        for elem in self.iter() {
+            /* Capacity is already reserved, so this can only fail in `try_clone`. */
+            let _ = vec.push(elem.try_clone()?);
+        }
+        Ok(vec)
+    }
+}
+
+impl<T, const N: usize> TryFromIterator<T> for ZenSmallVec<T, N> {
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, AllocError> {
+        let mut vec = ZenSmallVec::new();
+        vec.try_extend(iter)?;
+        Ok(vec)
+    }
+}
+
+impl<T, const N: usize> TryExtend<T> for ZenSmallVec<T, N> {
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let iter = iter.into_iter();
+        let original_len = self.len;
+
+        self.try_reserve(iter.size_hint().0)?;
+        for elem in iter {
+            if self.push(elem).is_err() {
+                self.truncate(original_len);
+                return Err(AllocError);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq<[T]> for ZenSmallVec<T, N> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq, const N: usize, const M: usize> PartialEq<[T; M]> for ZenSmallVec<T, N> {
+    fn eq(&self, other: &[T; M]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq, const N: usize, const M: usize> PartialEq<ZenSmallVec<T, M>>
+    for ZenSmallVec<T, N>
+{
+    fn eq(&self, other: &ZenSmallVec<T, M>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ZenSmallVec<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for ZenSmallVec<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for ZenSmallVec<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+// Hashes like the equivalent slice, whether inline or spilled.
+impl<T: Hash, const N: usize> Hash for ZenSmallVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ZenSmallVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ZenSmallVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        let this = ManuallyDrop::new(self);
+        /* `this` is never dropped, so the storage is moved exactly once. */
+        let data = unsafe { ptr::read(&this.data) };
+        IntoIter {
+            data,
+            start: 0,
+            end: this.len,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ZenSmallVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ZenSmallVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+// Owning iterator returned by `ZenSmallVec::into_iter`.
+pub struct IntoIter<T, const N: usize> {
+    data: SmallVecData<T, N>,
+    start: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data.ptr().add(self.start), self.end - self.start) }
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start < self.end {
+            let item = unsafe { ptr::read(self.data.ptr().add(self.start)) };
+            self.start += 1;
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start < self.end {
+            self.end -= 1;
+            unsafe { Some(ptr::read(self.data.ptr().add(self.end))) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let start = mem::replace(&mut self.start, self.end);
+        unsafe {
+            let remaining =
+                ptr::slice_from_raw_parts_mut(self.data.mut_ptr().add(start), self.end - start);
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
+
+// Iterator returned by `ZenSmallVec::drain`. The drained range is removed
+// even if the iterator is not fully consumed; the tail is moved back into
+// place when it is dropped.
+pub struct Drain<'a, T, const N: usize> {
+    vec: &'a mut ZenSmallVec<T, N>,
+    start: usize,
+    end: usize,
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<T, const N: usize> Drain<'_, T, N> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.vec.data.ptr().add(self.start), self.end - self.start) }
+    }
+}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start < self.end {
+            let item = unsafe { ptr::read(self.vec.data.ptr().add(self.start)) };
+            self.start += 1;
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start < self.end {
+            self.end -= 1;
+            unsafe { Some(ptr::read(self.vec.data.ptr().add(self.end))) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Drain<'_, T, N> {}
+
+impl<T, const N: usize> FusedIterator for Drain<'_, T, N> {}
+
+impl<T, const N: usize> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        /* Moves the tail back even if dropping a remaining element panics. */
+        struct MoveTail<'r, 'a, T, const N: usize>(&'r mut Drain<'a, T, N>);
+
+        impl<T, const N: usize> Drop for MoveTail<'_, '_, T, N> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                unsafe {
+                    let base = drain.vec.data.mut_ptr();
+                    let len = drain.vec.len;
+                    ptr::copy(base.add(drain.tail_start), base.add(len), drain.tail_len);
+                    drain.vec.len = len + drain.tail_len;
+                }
+            }
+        }
+
+        let start = mem::replace(&mut self.start, self.end);
+        let end = self.end;
+        let guard = MoveTail(self);
+        unsafe {
+            let remaining =
+                ptr::slice_from_raw_parts_mut(guard.0.vec.data.mut_ptr().add(start), end - start);
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
@@ -0,0 +1,460 @@
+use crate::zen_error::zen_error::{AsciiError, TryReserveError};
+use crate::zen_string::zen_ascii_char::ZenAsciiChar;
+use crate::zen_string::zen_ascii_string::ZenAsciiString;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::{Index, IndexMut};
+use core::slice::SliceIndex;
+use core::{slice, str};
+
+// Borrowed ASCII text, the `str` of `ZenAsciiString`. Every byte is below
+// 0x80, so the same memory is always valid as `str` and `[u8]` too.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct AsciiStr {
+    chars: [ZenAsciiChar],
+}
+
+// Index of the first byte that is not ASCII.
+const fn first_non_ascii(bytes: &[u8]) -> Option<usize> {
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii() {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+impl AsciiStr {
+    // For literals: `const GREETING: &AsciiStr = AsciiStr::new("hi");`.
+    // Non-ASCII text panics, which in a const is a compile error.
+    pub const fn new(s: &str) -> &AsciiStr {
+        match first_non_ascii(s.as_bytes()) {
+            None => unsafe { AsciiStr::from_str_unchecked(s) },
+            Some(_) => panic!("AsciiStr::new given non-ASCII text"),
+        }
+    }
+
+    pub const fn from_bytes(bytes: &[u8]) -> Result<&AsciiStr, AsciiError> {
+        match first_non_ascii(bytes) {
+            None => Ok(unsafe { AsciiStr::from_bytes_unchecked(bytes) }),
+            Some(valid_up_to) => Err(AsciiError { valid_up_to }),
+        }
+    }
+
+    pub fn from_bytes_mut(bytes: &mut [u8]) -> Result<&mut AsciiStr, AsciiError> {
+        match first_non_ascii(bytes) {
+            None => Ok(unsafe { &mut *(bytes as *mut [u8] as *mut AsciiStr) }),
+            Some(valid_up_to) => Err(AsciiError { valid_up_to }),
+        }
+    }
+
+    pub const fn from_str(s: &str) -> Result<&AsciiStr, AsciiError> {
+        AsciiStr::from_bytes(s.as_bytes())
+    }
+
+    /// # Safety
+    ///
+    /// Every byte must be ASCII.
+    pub const unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &AsciiStr {
+        &*(bytes as *const [u8] as *const AsciiStr)
+    }
+
+    pub const fn from_chars(chars: &[ZenAsciiChar]) -> &AsciiStr {
+        unsafe { &*(chars as *const [ZenAsciiChar] as *const AsciiStr) }
+    }
+
+    pub fn from_chars_mut(chars: &mut [ZenAsciiChar]) -> &mut AsciiStr {
+        unsafe { &mut *(chars as *mut [ZenAsciiChar] as *mut AsciiStr) }
+    }
+
+    /// # Safety
+    ///
+    /// Every byte of `s` must be ASCII.
+    pub const unsafe fn from_str_unchecked(s: &str) -> &AsciiStr {
+        AsciiStr::from_bytes_unchecked(s.as_bytes())
+    }
+
+    #[inline]
+    pub const fn as_chars(&self) -> &[ZenAsciiChar] {
+        &self.chars
+    }
+
+    #[inline]
+    pub fn as_mut_chars(&mut self) -> &mut [ZenAsciiChar] {
+        &mut self.chars
+    }
+
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8] {
+        /* `ZenAsciiChar` is a transparent `u8`. */
+        unsafe { &*(self as *const AsciiStr as *const [u8]) }
+    }
+
+    #[inline]
+    pub const fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    pub fn get<I>(&self, index: I) -> Option<&AsciiStr>
+    where
+        I: SliceIndex<[ZenAsciiChar], Output = [ZenAsciiChar]>,
+    {
+        self.chars.get(index).map(AsciiStr::from_chars)
+    }
+
+    pub fn get_mut<I>(&mut self, index: I) -> Option<&mut AsciiStr>
+    where
+        I: SliceIndex<[ZenAsciiChar], Output = [ZenAsciiChar]>,
+    {
+        self.chars.get_mut(index).map(AsciiStr::from_chars_mut)
+    }
+
+    pub fn first(&self) -> Option<ZenAsciiChar> {
+        self.chars.first().copied()
+    }
+
+    pub fn last(&self) -> Option<ZenAsciiChar> {
+        self.chars.last().copied()
+    }
+
+    // Splits into `[..mid]` and `[mid..]`; panics if `mid > len`.
+    pub fn split_at(&self, mid: usize) -> (&AsciiStr, &AsciiStr) {
+        let (left, right) = self.chars.split_at(mid);
+        (AsciiStr::from_chars(left), AsciiStr::from_chars(right))
+    }
+
+    pub fn chars(&self) -> Chars<'_> {
+        Chars {
+            inner: self.chars.iter(),
+        }
+    }
+
+    pub fn chars_mut(&mut self) -> slice::IterMut<'_, ZenAsciiChar> {
+        self.chars.iter_mut()
+    }
+
+    pub fn bytes(&self) -> core::iter::Copied<slice::Iter<'_, u8>> {
+        self.as_bytes().iter().copied()
+    }
+
+    // Case mapping leaves non-letters alone, so the result is still ASCII.
+    fn as_mut_bytes(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.chars.as_mut_ptr().cast::<u8>(), self.chars.len()) }
+    }
+
+    pub fn make_ascii_uppercase(&mut self) {
+        self.as_mut_bytes().make_ascii_uppercase();
+    }
+
+    pub fn make_ascii_lowercase(&mut self) {
+        self.as_mut_bytes().make_ascii_lowercase();
+    }
+
+    pub fn eq_ignore_ascii_case(&self, other: &str) -> bool {
+        self.as_str().eq_ignore_ascii_case(other)
+    }
+
+    pub fn try_to_ascii_string(&self) -> Result<ZenAsciiString, TryReserveError> {
+        let mut string = ZenAsciiString::with_capacity(self.len())?;
+        string.try_push_str(self)?;
+        Ok(string)
+    }
+
+    pub fn to_ascii_uppercase(&self) -> Result<ZenAsciiString, TryReserveError> {
+        let mut string = self.try_to_ascii_string()?;
+        string.make_ascii_uppercase();
+        Ok(string)
+    }
+
+    pub fn to_ascii_lowercase(&self) -> Result<ZenAsciiString, TryReserveError> {
+        let mut string = self.try_to_ascii_string()?;
+        string.make_ascii_lowercase();
+        Ok(string)
+    }
+
+    // Any piece of ASCII text is ASCII, so slices of `as_str` can be cast
+    // straight back.
+    #[inline]
+    fn rewrap<'a>(&self, s: &'a str) -> &'a AsciiStr {
+        unsafe { AsciiStr::from_str_unchecked(s) }
+    }
+
+    // Without leading and trailing ASCII whitespace.
+    pub fn trim(&self) -> &AsciiStr {
+        self.rewrap(self.as_str().trim_ascii())
+    }
+
+    pub fn trim_start(&self) -> &AsciiStr {
+        self.rewrap(self.as_str().trim_ascii_start())
+    }
+
+    pub fn trim_end(&self) -> &AsciiStr {
+        self.rewrap(self.as_str().trim_ascii_end())
+    }
+
+    // Byte index of the first match of `needle`.
+    pub fn find(&self, needle: &str) -> Option<usize> {
+        self.as_str().find(needle)
+    }
+
+    // Byte index of the last match of `needle`.
+    pub fn rfind(&self, needle: &str) -> Option<usize> {
+        self.as_str().rfind(needle)
+    }
+
+    pub fn contains(&self, needle: &str) -> bool {
+        self.as_str().contains(needle)
+    }
+
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.as_str().starts_with(prefix)
+    }
+
+    pub fn ends_with(&self, suffix: &str) -> bool {
+        self.as_str().ends_with(suffix)
+    }
+
+    // Pieces between occurrences of `separator`, including empty ones.
+    pub fn split(&self, separator: ZenAsciiChar) -> Split<'_> {
+        Split {
+            inner: self.as_str().split(separator.as_char()),
+        }
+    }
+
+    // Pieces separated by runs of ASCII whitespace, never empty.
+    pub fn split_whitespace(&self) -> SplitWhitespace<'_> {
+        SplitWhitespace {
+            inner: self.as_str().split_ascii_whitespace(),
+        }
+    }
+
+    // A copy with every non-overlapping `from` replaced by `to`.
+    pub fn replace(&self, from: &str, to: &AsciiStr) -> Result<ZenAsciiString, TryReserveError> {
+        let mut string = ZenAsciiString::with_capacity(self.len())?;
+        let mut last = 0;
+        for (start, matched) in self.as_str().match_indices(from) {
+            string.try_push_str(self.rewrap(&self.as_str()[last..start]))?;
+            string.try_push_str(to)?;
+            last = start + matched.len();
+        }
+        string.try_push_str(self.rewrap(&self.as_str()[last..]))?;
+        Ok(string)
+    }
+}
+
+// Slicing by range, like `str`; a single index gives a `ZenAsciiChar`
+// through `as_chars` instead.
+impl<I> Index<I> for AsciiStr
+where
+    I: SliceIndex<[ZenAsciiChar], Output = [ZenAsciiChar]>,
+{
+    type Output = AsciiStr;
+
+    fn index(&self, index: I) -> &AsciiStr {
+        AsciiStr::from_chars(&self.chars[index])
+    }
+}
+
+impl<I> IndexMut<I> for AsciiStr
+where
+    I: SliceIndex<[ZenAsciiChar], Output = [ZenAsciiChar]>,
+{
+    fn index_mut(&mut self, index: I) -> &mut AsciiStr {
+        AsciiStr::from_chars_mut(&mut self.chars[index])
+    }
+}
+
+impl Default for &AsciiStr {
+    fn default() -> Self {
+        AsciiStr::new("")
+    }
+}
+
+impl<'a> TryFrom<&'a str> for &'a AsciiStr {
+    type Error = AsciiError;
+
+    fn try_from(s: &'a str) -> Result<Self, AsciiError> {
+        AsciiStr::from_str(s)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for &'a AsciiStr {
+    type Error = AsciiError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, AsciiError> {
+        AsciiStr::from_bytes(bytes)
+    }
+}
+
+impl<'a> From<&'a AsciiStr> for &'a str {
+    fn from(s: &'a AsciiStr) -> Self {
+        s.as_str()
+    }
+}
+
+impl<'a> From<&'a AsciiStr> for &'a [u8] {
+    fn from(s: &'a AsciiStr) -> Self {
+        s.as_bytes()
+    }
+}
+
+impl<'a> IntoIterator for &'a AsciiStr {
+    type Item = ZenAsciiChar;
+    type IntoIter = Chars<'a>;
+
+    fn into_iter(self) -> Chars<'a> {
+        self.chars()
+    }
+}
+
+impl fmt::Display for AsciiStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Debug for AsciiStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq<str> for AsciiStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for AsciiStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<AsciiStr> for str {
+    fn eq(&self, other: &AsciiStr) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<AsciiStr> for &str {
+    fn eq(&self, other: &AsciiStr) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl AsRef<str> for AsciiStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for AsciiStr {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<[ZenAsciiChar]> for AsciiStr {
+    fn as_ref(&self) -> &[ZenAsciiChar] {
+        self.as_chars()
+    }
+}
+
+// Iterator returned by `AsciiStr::chars`.
+#[derive(Clone)]
+pub struct Chars<'a> {
+    inner: slice::Iter<'a, ZenAsciiChar>,
+}
+
+impl<'a> Chars<'a> {
+    // What is left to iterate.
+    pub fn as_ascii_str(&self) -> &'a AsciiStr {
+        AsciiStr::from_chars(self.inner.as_slice())
+    }
+}
+
+impl Iterator for Chars<'_> {
+    type Item = ZenAsciiChar;
+
+    fn next(&mut self) -> Option<ZenAsciiChar> {
+        self.inner.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Chars<'_> {
+    fn next_back(&mut self) -> Option<ZenAsciiChar> {
+        self.inner.next_back().copied()
+    }
+}
+
+impl ExactSizeIterator for Chars<'_> {}
+
+impl FusedIterator for Chars<'_> {}
+
+// Iterator returned by `AsciiStr::split`.
+pub struct Split<'a> {
+    inner: str::Split<'a, char>,
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = &'a AsciiStr;
+
+    fn next(&mut self) -> Option<&'a AsciiStr> {
+        self.inner
+            .next()
+            .map(|s| unsafe { AsciiStr::from_str_unchecked(s) })
+    }
+}
+
+impl<'a> DoubleEndedIterator for Split<'a> {
+    fn next_back(&mut self) -> Option<&'a AsciiStr> {
+        self.inner
+            .next_back()
+            .map(|s| unsafe { AsciiStr::from_str_unchecked(s) })
+    }
+}
+
+impl FusedIterator for Split<'_> {}
+
+// Iterator returned by `AsciiStr::split_whitespace`.
+pub struct SplitWhitespace<'a> {
+    inner: str::SplitAsciiWhitespace<'a>,
+}
+
+impl<'a> Iterator for SplitWhitespace<'a> {
+    type Item = &'a AsciiStr;
+
+    fn next(&mut self) -> Option<&'a AsciiStr> {
+        self.inner
+            .next()
+            .map(|s| unsafe { AsciiStr::from_str_unchecked(s) })
+    }
+}
+
+impl<'a> DoubleEndedIterator for SplitWhitespace<'a> {
+    fn next_back(&mut self) -> Option<&'a AsciiStr> {
+        self.inner
+            .next_back()
+            .map(|s| unsafe { AsciiStr::from_str_unchecked(s) })
+    }
+}
+
+impl FusedIterator for SplitWhitespace<'_> {}
@@ -1,2 +1,6 @@
+pub mod ascii_str;
+pub mod try_to_string;
 pub mod zen_ascii_string;
 pub mod zen_ascii_char;
+#[allow(clippy::module_inception)]
+pub mod zen_string;
\ No newline at end of file
@@ -0,0 +1,22 @@
+use crate::zen_string::zen_string::ZenString;
+use core::alloc::AllocError;
+use core::fmt;
+
+// Fallible counterpart of `ToString`, for anything that implements
+// `Display`. Running out of memory is an `Err`, never a panic.
+pub trait TryToString {
+    fn try_to_string(&self) -> Result<ZenString, AllocError>;
+}
+
+impl<T: fmt::Display + ?Sized> TryToString for T {
+    fn try_to_string(&self) -> Result<ZenString, AllocError> {
+        try_format(format_args!("{}", self))
+    }
+}
+
+// What `try_format!` expands to.
+pub fn try_format(args: fmt::Arguments<'_>) -> Result<ZenString, AllocError> {
+    let mut string = ZenString::new();
+    string.try_write_fmt(args)?;
+    Ok(string)
+}
@@ -1,7 +1,49 @@
+use crate::zen_error::zen_error::AsciiError;
+use core::fmt::{self, Write};
+
+// Always a byte below 0x80, so a run of these is valid UTF-8.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
 pub struct ZenAsciiChar(u8);
 
 impl ZenAsciiChar {
-    pub fn new(c: char) -> Option<Self> {
+    // The control characters, by their usual abbreviations.
+    pub const NUL: Self = Self(0x00);
+    pub const SOH: Self = Self(0x01);
+    pub const STX: Self = Self(0x02);
+    pub const ETX: Self = Self(0x03);
+    pub const EOT: Self = Self(0x04);
+    pub const ENQ: Self = Self(0x05);
+    pub const ACK: Self = Self(0x06);
+    pub const BEL: Self = Self(0x07);
+    pub const BS: Self = Self(0x08);
+    pub const TAB: Self = Self(0x09);
+    pub const LF: Self = Self(0x0a);
+    pub const VT: Self = Self(0x0b);
+    pub const FF: Self = Self(0x0c);
+    pub const CR: Self = Self(0x0d);
+    pub const SO: Self = Self(0x0e);
+    pub const SI: Self = Self(0x0f);
+    pub const DLE: Self = Self(0x10);
+    pub const DC1: Self = Self(0x11);
+    pub const DC2: Self = Self(0x12);
+    pub const DC3: Self = Self(0x13);
+    pub const DC4: Self = Self(0x14);
+    pub const NAK: Self = Self(0x15);
+    pub const SYN: Self = Self(0x16);
+    pub const ETB: Self = Self(0x17);
+    pub const CAN: Self = Self(0x18);
+    pub const EM: Self = Self(0x19);
+    pub const SUB: Self = Self(0x1a);
+    pub const ESC: Self = Self(0x1b);
+    pub const FS: Self = Self(0x1c);
+    pub const GS: Self = Self(0x1d);
+    pub const RS: Self = Self(0x1e);
+    pub const US: Self = Self(0x1f);
+    pub const SPACE: Self = Self(b' ');
+    pub const DEL: Self = Self(0x7f);
+
+    pub const fn new(c: char) -> Option<Self> {
         if c.is_ascii() {
             Some(ZenAsciiChar(c as u8))
         } else {
@@ -9,11 +51,156 @@ impl ZenAsciiChar {
         }
     }
 
-    pub fn as_char(self) -> char {
+    pub const fn from_u8(byte: u8) -> Option<Self> {
+        if byte.is_ascii() {
+            Some(ZenAsciiChar(byte))
+        } else {
+            None
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `byte` must be below 0x80.
+    pub const unsafe fn from_u8_unchecked(byte: u8) -> Self {
+        ZenAsciiChar(byte)
+    }
+
+    pub const fn as_char(self) -> char {
         self.0 as char
     }
 
-    pub fn as_u8(self) -> u8 {
+    pub const fn as_u8(self) -> u8 {
         self.0
     }
+
+    pub const fn is_alphabetic(self) -> bool {
+        self.0.is_ascii_alphabetic()
+    }
+
+    pub const fn is_alphanumeric(self) -> bool {
+        self.0.is_ascii_alphanumeric()
+    }
+
+    pub const fn is_digit(self) -> bool {
+        self.0.is_ascii_digit()
+    }
+
+    pub const fn is_hexdigit(self) -> bool {
+        self.0.is_ascii_hexdigit()
+    }
+
+    pub const fn is_uppercase(self) -> bool {
+        self.0.is_ascii_uppercase()
+    }
+
+    pub const fn is_lowercase(self) -> bool {
+        self.0.is_ascii_lowercase()
+    }
+
+    // Space, tab, line feed, form feed and carriage return; not VT, as
+    // with `u8::is_ascii_whitespace`.
+    pub const fn is_whitespace(self) -> bool {
+        self.0.is_ascii_whitespace()
+    }
+
+    pub const fn is_control(self) -> bool {
+        self.0.is_ascii_control()
+    }
+
+    // Visible characters: everything from `!` to `~`.
+    pub const fn is_graphic(self) -> bool {
+        self.0.is_ascii_graphic()
+    }
+
+    // Graphic characters plus space.
+    pub const fn is_printable(self) -> bool {
+        self.0 == b' ' || self.0.is_ascii_graphic()
+    }
+
+    pub const fn is_punctuation(self) -> bool {
+        self.0.is_ascii_punctuation()
+    }
+
+    // The value of a digit in base 16, for `0-9`, `a-f` and `A-F`.
+    pub const fn to_hex_digit(self) -> Option<u8> {
+        match self.0 {
+            b'0'..=b'9' => Some(self.0 - b'0'),
+            b'a'..=b'f' => Some(self.0 - b'a' + 10),
+            b'A'..=b'F' => Some(self.0 - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    pub const fn to_uppercase(self) -> Self {
+        ZenAsciiChar(self.0.to_ascii_uppercase())
+    }
+
+    pub const fn to_lowercase(self) -> Self {
+        ZenAsciiChar(self.0.to_ascii_lowercase())
+    }
+
+    pub fn make_uppercase(&mut self) {
+        self.0.make_ascii_uppercase();
+    }
+
+    pub fn make_lowercase(&mut self) {
+        self.0.make_ascii_lowercase();
+    }
+
+    pub const fn eq_ignore_case(self, other: Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl TryFrom<u8> for ZenAsciiChar {
+    type Error = AsciiError;
+
+    fn try_from(byte: u8) -> Result<Self, AsciiError> {
+        ZenAsciiChar::from_u8(byte).ok_or(AsciiError { valid_up_to: 0 })
+    }
+}
+
+impl TryFrom<char> for ZenAsciiChar {
+    type Error = AsciiError;
+
+    fn try_from(c: char) -> Result<Self, AsciiError> {
+        ZenAsciiChar::new(c).ok_or(AsciiError { valid_up_to: 0 })
+    }
+}
+
+impl From<ZenAsciiChar> for u8 {
+    fn from(c: ZenAsciiChar) -> u8 {
+        c.as_u8()
+    }
+}
+
+impl From<ZenAsciiChar> for char {
+    fn from(c: ZenAsciiChar) -> char {
+        c.as_char()
+    }
+}
+
+impl PartialEq<u8> for ZenAsciiChar {
+    fn eq(&self, other: &u8) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<char> for ZenAsciiChar {
+    fn eq(&self, other: &char) -> bool {
+        self.as_char() == *other
+    }
+}
+
+impl fmt::Debug for ZenAsciiChar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.as_char(), f)
+    }
+}
+
+impl fmt::Display for ZenAsciiChar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char(self.as_char())
+    }
 }
@@ -1,11 +1,16 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_error::zen_error::{AsciiError, TryReserveError, ZenError};
+use crate::zen_string::ascii_str::AsciiStr;
 use crate::zen_string::zen_ascii_char::ZenAsciiChar;
+use crate::zen_string::zen_string::{tracked_result, TrackingWriter, ZenString};
 use crate::zen_vec::zen_vec::ZenVec;
+use core::borrow::{Borrow, BorrowMut};
+use core::fmt::{self, Write};
+use core::ops::{Deref, DerefMut, Index, IndexMut};
+use core::slice::SliceIndex;
 
-pub enum ZenAsciiStringError {
-    InvalidStr,
-    AllocationError(core::alloc::AllocError),
-}
-
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ZenAsciiString {
     vec: ZenVec<ZenAsciiChar>,
 }
@@ -16,23 +21,38 @@ impl ZenAsciiString {
         Self { vec: ZenVec::new() }
     }
 
-    pub fn with_capacity(capacity: usize) -> Result<Self, ZenAsciiStringError> {
-        match ZenVec::with_capacity(capacity) {
-            Ok(vec) => Ok(Self { vec }),
-            Err(alloc_err) => Err(ZenAsciiStringError::AllocationError(alloc_err)),
-        }
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            vec: ZenVec::with_capacity(capacity)?,
+        })
     }
 
-    pub fn from_str(s: &str) -> Result<Self, ZenAsciiStringError> {
-        let mut obj = Self::with_capacity(s.len())?;
-        for c in s.chars() {
-            if let Some(ascii_char) = ZenAsciiChar::new(c) {
-                obj.vec.push(ascii_char);
-            } else {
-                return Err(ZenAsciiStringError::InvalidStr);
-            }
-        }
-        Ok(obj)
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, ZenError> {
+        Self::from_bytes(s.as_bytes())
+    }
+
+    // Copies `bytes` if they are all ASCII. Otherwise the error says where
+    // the first offending byte is.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ZenError> {
+        Ok(AsciiStr::from_bytes(bytes)?.try_to_ascii_string()?)
+    }
+
+    // The same buffer, viewed as bytes.
+    pub fn into_bytes(self) -> ZenVec<u8> {
+        let (ptr, len, cap) = self.vec.into_raw_parts();
+        /* `ZenAsciiChar` is a transparent `u8`. */
+        unsafe { ZenVec::from_raw_parts(ptr.cast::<u8>(), len, cap) }
+    }
+
+    #[inline]
+    pub fn as_ascii_str(&self) -> &AsciiStr {
+        AsciiStr::from_chars(&self.vec)
+    }
+
+    #[inline]
+    pub fn as_mut_ascii_str(&mut self) -> &mut AsciiStr {
+        AsciiStr::from_chars_mut(&mut self.vec)
     }
 
     #[inline]
@@ -40,14 +60,59 @@ impl ZenAsciiString {
         self.vec.cap()
     }
 
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(additional)
+    }
+
     #[inline]
     pub fn pop(&mut self) -> Option<ZenAsciiChar> {
         self.vec.pop()
     }
 
+    pub fn try_push(&mut self, c: ZenAsciiChar) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(1)?;
+        /* Room was reserved above, so this can't fail. */
+        let _ = self.vec.push(c);
+        Ok(())
+    }
+
+    // Same as `try_push`; the result used to be thrown away here.
     #[inline]
-    pub fn push(&mut self, c: ZenAsciiChar) {
-        self.vec.push(c);
+    pub fn push(&mut self, c: ZenAsciiChar) -> Result<(), TryReserveError> {
+        self.try_push(c)
+    }
+
+    // Appends all of `s` or, if the allocation fails, nothing.
+    pub fn try_push_str(&mut self, s: &AsciiStr) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(s.len())?;
+        for &c in s.as_chars() {
+            let _ = self.vec.push(c);
+        }
+        Ok(())
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len);
+    }
+
+    // Appends formatted text, or nothing if some of it isn't ASCII or memory
+    // runs out partway. Panics if a formatting impl returns an error on its
+    // own, as `format!` does.
+    pub fn try_write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), ZenError> {
+        let original_len = self.len();
+        let mut writer = TrackingWriter::new(|s: &str| -> Result<(), ZenError> {
+            Ok(self.try_push_str(AsciiStr::from_str(s)?)?)
+        });
+        let result = fmt::write(&mut writer, args);
+        let error = writer.error;
+        if error.is_some() || result.is_err() {
+            self.vec.truncate(original_len);
+        }
+        tracked_result(result, error)
+    }
+
+    pub fn clear(&mut self) {
+        self.vec.clear();
     }
 
     #[inline]
@@ -59,4 +124,252 @@ impl ZenAsciiString {
     pub fn is_empty(&self) -> bool {
         self.vec.is_empty()
     }
+
+    pub fn as_slice(&self) -> &[ZenAsciiChar] {
+        self.vec.as_slice()
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [ZenAsciiChar] {
+        self.vec.as_mut_slice()
+    }
+}
+
+impl fmt::Display for ZenAsciiString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ascii_str(), f)
+    }
+}
+
+// `write!` support. Each piece of text is appended whole or not at all, but
+// pieces written before a failing one stay; `try_write_fmt` takes those
+// back and says why it failed.
+impl Write for ZenAsciiString {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let s = AsciiStr::from_str(s).map_err(|_| fmt::Error)?;
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl fmt::Debug for ZenAsciiString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ascii_str(), f)
+    }
+}
+
+impl Deref for ZenAsciiString {
+    type Target = AsciiStr;
+
+    fn deref(&self) -> &AsciiStr {
+        self.as_ascii_str()
+    }
+}
+
+impl DerefMut for ZenAsciiString {
+    fn deref_mut(&mut self) -> &mut AsciiStr {
+        self.as_mut_ascii_str()
+    }
+}
+
+impl PartialEq<str> for ZenAsciiString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ZenAsciiString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<ZenAsciiString> for str {
+    fn eq(&self, other: &ZenAsciiString) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<ZenAsciiString> for &str {
+    fn eq(&self, other: &ZenAsciiString) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<AsciiStr> for ZenAsciiString {
+    fn eq(&self, other: &AsciiStr) -> bool {
+        self.as_ascii_str() == other
+    }
+}
+
+impl PartialEq<&AsciiStr> for ZenAsciiString {
+    fn eq(&self, other: &&AsciiStr) -> bool {
+        self.as_ascii_str() == *other
+    }
+}
+
+impl<I: SliceIndex<[ZenAsciiChar]>> Index<I> for ZenAsciiString {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &I::Output {
+        &self.vec[index]
+    }
 }
+
+impl<I: SliceIndex<[ZenAsciiChar]>> IndexMut<I> for ZenAsciiString {
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.vec[index]
+    }
+}
+
+impl Borrow<[ZenAsciiChar]> for ZenAsciiString {
+    fn borrow(&self) -> &[ZenAsciiChar] {
+        self.as_slice()
+    }
+}
+
+impl Borrow<AsciiStr> for ZenAsciiString {
+    fn borrow(&self) -> &AsciiStr {
+        self.as_ascii_str()
+    }
+}
+
+impl BorrowMut<AsciiStr> for ZenAsciiString {
+    fn borrow_mut(&mut self) -> &mut AsciiStr {
+        self.as_mut_ascii_str()
+    }
+}
+
+impl AsRef<AsciiStr> for ZenAsciiString {
+    fn as_ref(&self) -> &AsciiStr {
+        self.as_ascii_str()
+    }
+}
+
+impl AsRef<str> for ZenAsciiString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for ZenAsciiString {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<[ZenAsciiChar]> for ZenAsciiString {
+    fn as_ref(&self) -> &[ZenAsciiChar] {
+        self.as_slice()
+    }
+}
+
+impl AsMut<[ZenAsciiChar]> for ZenAsciiString {
+    fn as_mut(&mut self) -> &mut [ZenAsciiChar] {
+        self.as_mut_slice()
+    }
+}
+
+impl TryFrom<&str> for ZenAsciiString {
+    type Error = ZenError;
+
+    fn try_from(s: &str) -> Result<Self, ZenError> {
+        Self::from_str(s)
+    }
+}
+
+impl TryFrom<&AsciiStr> for ZenAsciiString {
+    type Error = TryReserveError;
+
+    fn try_from(s: &AsciiStr) -> Result<Self, TryReserveError> {
+        s.try_to_ascii_string()
+    }
+}
+
+// Reuses the buffer once every byte is checked to be ASCII. A string that
+// isn't ASCII comes back in the error.
+impl TryFrom<ZenString> for ZenAsciiString {
+    type Error = FromStringError;
+
+    fn try_from(s: ZenString) -> Result<Self, FromStringError> {
+        if let Err(error) = AsciiStr::from_bytes(s.as_bytes()) {
+            return Err(FromStringError { string: s, error });
+        }
+        let (ptr, len, cap) = s.into_bytes().into_raw_parts();
+        Ok(Self {
+            vec: unsafe { ZenVec::from_raw_parts(ptr.cast::<ZenAsciiChar>(), len, cap) },
+        })
+    }
+}
+
+impl<const N: usize> TryFrom<[ZenAsciiChar; N]> for ZenAsciiString {
+    type Error = TryReserveError;
+
+    fn try_from(chars: [ZenAsciiChar; N]) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            vec: ZenVec::try_from(chars)?,
+        })
+    }
+}
+
+impl Default for ZenAsciiString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryClone for ZenAsciiString {
+    fn try_clone(&self) -> Result<Self, core::alloc::AllocError> {
+        Ok(Self {
+            vec: self.vec.try_clone()?,
+        })
+    }
+}
+
+impl TryFromIterator<ZenAsciiChar> for ZenAsciiString {
+    fn try_from_iter<I: IntoIterator<Item = ZenAsciiChar>>(
+        iter: I,
+    ) -> Result<Self, core::alloc::AllocError> {
+        Ok(Self {
+            vec: ZenVec::try_from_iter(iter)?,
+        })
+    }
+}
+
+impl TryExtend<ZenAsciiChar> for ZenAsciiString {
+    fn try_extend<I: IntoIterator<Item = ZenAsciiChar>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), core::alloc::AllocError> {
+        self.vec.try_extend(iter)
+    }
+}
+
+// A `ZenString` that `ZenAsciiString::try_from` turned down, handed back
+// together with where its first non-ASCII byte is.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FromStringError {
+    string: ZenString,
+    error: AsciiError,
+}
+
+impl FromStringError {
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+
+    pub fn into_string(self) -> ZenString {
+        self.string
+    }
+
+    pub fn ascii_error(&self) -> AsciiError {
+        self.error
+    }
+}
+
+impl fmt::Display for FromStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromStringError {}
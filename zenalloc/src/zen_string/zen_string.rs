@@ -0,0 +1,543 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_error::zen_error::{TryReserveError, ZenError};
+use crate::zen_string::zen_ascii_string::ZenAsciiString;
+use crate::zen_vec::zen_vec::{check_range, ZenVec};
+use core::alloc::AllocError;
+use core::borrow::{Borrow, BorrowMut};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::ops::{Deref, DerefMut, RangeBounds};
+use core::ptr::{self, NonNull};
+use core::str::{self, Chars, Utf8Error};
+
+// A growable UTF-8 string. The bytes are always valid UTF-8; every method
+// that takes a byte index checks that it falls on a char boundary.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub struct ZenString {
+    vec: ZenVec<u8>,
+}
+
+impl ZenString {
+    #[inline]
+    pub fn new() -> Self {
+        Self { vec: ZenVec::new() }
+    }
+
+    // Room for `capacity` bytes.
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            vec: ZenVec::with_capacity(capacity)?,
+        })
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, TryReserveError> {
+        let mut string = Self::with_capacity(s.len())?;
+        string.try_push_str(s)?;
+        Ok(string)
+    }
+
+    // Takes over `bytes` if they are valid UTF-8. Otherwise the bytes come
+    // back in the error, along with where the invalid sequence starts.
+    pub fn from_utf8(bytes: ZenVec<u8>) -> Result<Self, FromUtf8Error> {
+        match str::from_utf8(&bytes) {
+            Ok(_) => Ok(Self { vec: bytes }),
+            Err(error) => Err(FromUtf8Error { bytes, error }),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `bytes` must be valid UTF-8.
+    pub unsafe fn from_utf8_unchecked(bytes: ZenVec<u8>) -> Self {
+        Self { vec: bytes }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec
+    }
+
+    pub fn into_bytes(self) -> ZenVec<u8> {
+        self.vec
+    }
+
+    // Length in bytes, not chars.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.cap()
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(additional)
+    }
+
+    pub fn try_push(&mut self, c: char) -> Result<(), TryReserveError> {
+        self.try_push_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    // Appends all of `s` or, if the allocation fails, nothing.
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(s.len())?;
+        for &byte in s.as_bytes() {
+            /* Room was reserved above, so this can't fail. */
+            let _ = self.vec.push(byte);
+        }
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.chars().next_back()?;
+        self.vec.truncate(self.len() - c.len_utf8());
+        Some(c)
+    }
+
+    // Errors if `index` is past the end or inside a char.
+    fn check_boundary(&self, index: usize) -> Result<(), ZenError> {
+        if index > self.len() {
+            return Err(ZenError::IndexOutOfBounds {
+                index,
+                len: self.len(),
+            });
+        }
+        if !self.is_char_boundary(index) {
+            return Err(ZenError::InvalidInput("index is not on a char boundary"));
+        }
+        Ok(())
+    }
+
+    pub fn insert(&mut self, index: usize, c: char) -> Result<(), ZenError> {
+        self.insert_str(index, c.encode_utf8(&mut [0; 4]))
+    }
+
+    pub fn insert_str(&mut self, index: usize, s: &str) -> Result<(), ZenError> {
+        self.check_boundary(index)?;
+        self.vec.try_reserve(s.len())?;
+        for &byte in s.as_bytes() {
+            /* Room was reserved above, so this can't fail. */
+            let _ = self.vec.push(byte);
+        }
+        self.vec[index..].rotate_right(s.len());
+        Ok(())
+    }
+
+    // Removes and returns the char starting at byte `index`.
+    pub fn remove(&mut self, index: usize) -> Result<char, ZenError> {
+        self.check_boundary(index)?;
+        let c = self[index..].chars().next().ok_or(ZenError::IndexOutOfBounds {
+            index,
+            len: self.len(),
+        })?;
+        let _ = self.vec.drain(index..index + c.len_utf8());
+        Ok(c)
+    }
+
+    // Keeps the chars for which `f` returns true, moving each kept char
+    // back over the removed ones in a single pass.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(char) -> bool,
+    {
+        /* If `f` panics, the string keeps the chars compacted so far. */
+        struct SetLenOnDrop<'a> {
+            string: &'a mut ZenString,
+            index: usize,
+            deleted: usize,
+        }
+
+        impl Drop for SetLenOnDrop<'_> {
+            fn drop(&mut self) {
+                unsafe { self.string.vec.set_len(self.index - self.deleted) };
+            }
+        }
+
+        let len = self.len();
+        let mut guard = SetLenOnDrop {
+            string: self,
+            index: 0,
+            deleted: 0,
+        };
+
+        while guard.index < len {
+            let c = unsafe {
+                let rest = str::from_utf8_unchecked(&guard.string.vec.as_slice()[guard.index..len]);
+                rest.chars().next().unwrap_unchecked()
+            };
+            let char_len = c.len_utf8();
+
+            if !f(c) {
+                guard.deleted += char_len;
+            } else if guard.deleted > 0 {
+                unsafe {
+                    let base = guard.string.vec.ptr();
+                    ptr::copy(base.add(guard.index), base.add(guard.index - guard.deleted), char_len);
+                }
+            }
+            guard.index += char_len;
+        }
+    }
+
+    // Shortens the string to `new_len` bytes. Panics if `new_len` is inside
+    // a char, as cutting there would leave invalid UTF-8.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            assert!(
+                self.is_char_boundary(new_len),
+                "truncate index {} is not on a char boundary",
+                new_len
+            );
+            self.vec.truncate(new_len);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    // Appends formatted text, or nothing if memory runs out partway. Unlike
+    // `write!`, which can only report `fmt::Error`, this says why it failed.
+    // Panics if a formatting impl returns an error on its own, as `format!`
+    // does.
+    pub fn try_write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), TryReserveError> {
+        if let Some(s) = args.as_str() {
+            return self.try_push_str(s);
+        }
+
+        let original_len = self.len();
+        let mut writer = TrackingWriter::new(|s: &str| self.try_push_str(s));
+        let result = fmt::write(&mut writer, args);
+        let error = writer.error;
+        if error.is_some() || result.is_err() {
+            self.vec.truncate(original_len);
+        }
+        tracked_result(result, error)
+    }
+
+    // Removes a byte range and yields its chars. Both ends must be char
+    // boundaries. As with `ZenVec::drain`, the range is removed even if the
+    // iterator is dropped early.
+    pub fn drain<R>(&mut self, range: R) -> Result<Drain<'_>, ZenError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = check_range(range, self.len())?;
+        self.check_boundary(start)?;
+        self.check_boundary(end)?;
+
+        let string = NonNull::from(&mut *self);
+        /* The chars borrow from the string, which `Drain` only edits on drop. */
+        let chars = unsafe { (*string.as_ptr()).as_str()[start..end].chars() };
+        Ok(Drain {
+            string,
+            start,
+            end,
+            chars,
+        })
+    }
+}
+
+// Remembers the failure that `fmt::Error` can't carry. A formatting impl
+// may swallow the `fmt::Error` and carry on, so the caller must look at
+// `error` and not only at what `fmt::write` returned. Nothing more is
+// pushed after the first failure.
+pub(crate) struct TrackingWriter<F, E> {
+    push: F,
+    pub(crate) error: Option<E>,
+}
+
+impl<F, E> TrackingWriter<F, E>
+where
+    F: FnMut(&str) -> Result<(), E>,
+{
+    pub(crate) fn new(push: F) -> Self {
+        Self { push, error: None }
+    }
+}
+
+impl<F, E> fmt::Write for TrackingWriter<F, E>
+where
+    F: FnMut(&str) -> Result<(), E>,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.error.is_some() {
+            return Err(fmt::Error);
+        }
+        (self.push)(s).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+// The outcome of a `fmt::write` through a `TrackingWriter`. Panics if a
+// formatting impl failed without the writer failing first, as `format!`
+// does.
+pub(crate) fn tracked_result<E>(result: fmt::Result, error: Option<E>) -> Result<(), E> {
+    match (error, result) {
+        (Some(err), _) => Err(err),
+        (None, Ok(())) => Ok(()),
+        (None, Err(_)) => panic!("a formatting trait implementation returned an error"),
+    }
+}
+
+// `write!` support. Allocation failure surfaces as a bare `fmt::Error`;
+// use `try_write_fmt` to find out why.
+impl fmt::Write for ZenString {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.try_push(c).map_err(|_| fmt::Error)
+    }
+}
+
+impl Default for ZenString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryClone for ZenString {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(Self {
+            vec: self.vec.try_clone()?,
+        })
+    }
+}
+
+impl Deref for ZenString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl DerefMut for ZenString {
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl fmt::Display for ZenString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Debug for ZenString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+// Hashes like `str`, so `&str` can look up `ZenString` keys.
+impl Hash for ZenString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl PartialEq<str> for ZenString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ZenString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<ZenString> for str {
+    fn eq(&self, other: &ZenString) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<ZenString> for &str {
+    fn eq(&self, other: &ZenString) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl Borrow<str> for ZenString {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl BorrowMut<str> for ZenString {
+    fn borrow_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl AsRef<str> for ZenString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for ZenString {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl TryFrom<&str> for ZenString {
+    type Error = TryReserveError;
+
+    fn try_from(s: &str) -> Result<Self, TryReserveError> {
+        Self::from_str(s)
+    }
+}
+
+// ASCII is a subset of UTF-8, so this reuses the buffer as is.
+impl From<ZenAsciiString> for ZenString {
+    fn from(s: ZenAsciiString) -> Self {
+        Self {
+            vec: s.into_bytes(),
+        }
+    }
+}
+
+impl TryFromIterator<char> for ZenString {
+    fn try_from_iter<I: IntoIterator<Item = char>>(iter: I) -> Result<Self, AllocError> {
+        let mut string = ZenString::new();
+        string.try_extend(iter)?;
+        Ok(string)
+    }
+}
+
+impl TryExtend<char> for ZenString {
+    fn try_extend<I: IntoIterator<Item = char>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let iter = iter.into_iter();
+        let original_len = self.len();
+
+        self.try_reserve(iter.size_hint().0)?;
+        for c in iter {
+            if let Err(err) = self.try_push(c) {
+                self.vec.truncate(original_len);
+                return Err(err.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> TryExtend<&'a str> for ZenString {
+    fn try_extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let original_len = self.len();
+        for s in iter {
+            if let Err(err) = self.try_push_str(s) {
+                self.vec.truncate(original_len);
+                return Err(err.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+// Iterator returned by `ZenString::drain`.
+pub struct Drain<'a> {
+    string: NonNull<ZenString>,
+    start: usize,
+    end: usize,
+    chars: Chars<'a>,
+}
+
+impl Drain<'_> {
+    pub fn as_str(&self) -> &str {
+        self.chars.as_str()
+    }
+}
+
+impl Iterator for Drain<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.chars.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Drain<'_> {
+    fn next_back(&mut self) -> Option<char> {
+        self.chars.next_back()
+    }
+}
+
+impl FusedIterator for Drain<'_> {}
+
+impl Drop for Drain<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            let string = self.string.as_mut();
+            let _ = string.vec.drain(self.start..self.end);
+        }
+    }
+}
+
+// Bytes that `ZenString::from_utf8` turned down, handed back together with
+// the reason.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FromUtf8Error {
+    bytes: ZenVec<u8>,
+    error: Utf8Error,
+}
+
+impl FromUtf8Error {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> ZenVec<u8> {
+        self.bytes
+    }
+
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+
+    // Length of the prefix that was valid UTF-8.
+    pub fn valid_up_to(&self) -> usize {
+        self.error.valid_up_to()
+    }
+}
+
+impl fmt::Display for FromUtf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromUtf8Error {}
@@ -0,0 +1,94 @@
+use crate::zen_vec::zen_vec::ZenVec;
+use core::iter::FusedIterator;
+use core::ptr::{self, NonNull};
+use core::{mem, slice};
+
+// Iterator returned by `ZenVec::drain`. The drained range is removed from
+// the vector even if the iterator is not fully consumed; the tail is moved
+// back into place when the `Drain` is dropped.
+pub struct Drain<'a, T> {
+    pub(super) tail_start: usize,
+    pub(super) tail_len: usize,
+    pub(super) iter: slice::Iter<'a, T>,
+    pub(super) vec: NonNull<ZenVec<T>>,
+}
+
+impl<T> Drain<'_, T> {
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|elem| unsafe { ptr::read(elem) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|elem| unsafe { ptr::read(elem) })
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        /* Moves the tail back even if dropping a remaining element panics. */
+        struct DropGuard<'r, 'a, T>(&'r mut Drain<'a, T>);
+
+        impl<T> Drop for DropGuard<'_, '_, T> {
+            fn drop(&mut self) {
+                if self.0.tail_len == 0 {
+                    return;
+                }
+
+                unsafe {
+                    let vec = self.0.vec.as_mut();
+                    let start = vec.len();
+                    if self.0.tail_start != start {
+                        ptr::copy(
+                            vec.ptr().add(self.0.tail_start),
+                            vec.ptr().add(start),
+                            self.0.tail_len,
+                        );
+                    }
+                    vec.set_len(start + self.0.tail_len);
+                }
+            }
+        }
+
+        let iter = mem::take(&mut self.iter);
+        let drop_len = iter.len();
+        let vec = self.vec;
+        let _guard = DropGuard(self);
+
+        if drop_len == 0 {
+            return;
+        }
+
+        unsafe {
+            /* Rebuild the pointer from the vector so the drop doesn't go through a shared borrow. */
+            let vec = vec.as_ref();
+            let offset = if mem::size_of::<T>() == 0 {
+                0
+            } else {
+                iter.as_slice().as_ptr().offset_from(vec.ptr()) as usize
+            };
+            let to_drop = ptr::slice_from_raw_parts_mut(vec.ptr().add(offset), drop_len);
+            ptr::drop_in_place(to_drop);
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for Drain<'_, T> {}
+unsafe impl<T: Sync> Sync for Drain<'_, T> {}
@@ -0,0 +1,64 @@
+use crate::zen_vec::zen_vec::ZenVec;
+use core::iter::FusedIterator;
+use core::ptr;
+
+// Iterator returned by `ZenVec::extract_if`. Elements for which the
+// predicate returns `true` are yielded; the rest are compacted in place.
+// Dropping the iterator early keeps every element that was not yielded.
+pub struct ExtractIf<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    pub(super) vec: &'a mut ZenVec<T>,
+    pub(super) idx: usize,
+    pub(super) del: usize,
+    pub(super) old_len: usize,
+    pub(super) pred: F,
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe {
+            while self.idx < self.old_len {
+                let base = self.vec.ptr();
+                let cur = base.add(self.idx);
+                let drained = (self.pred)(&mut *cur);
+                /* Advance only after the predicate so a panic keeps the element. */
+                self.idx += 1;
+                if drained {
+                    self.del += 1;
+                    return Some(ptr::read(cur));
+                } else if self.del > 0 {
+                    ptr::copy_nonoverlapping(cur, cur.sub(self.del), 1);
+                }
+            }
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.old_len - self.idx))
+    }
+}
+
+impl<T, F> FusedIterator for ExtractIf<'_, T, F> where F: FnMut(&mut T) -> bool {}
+
+impl<T, F> Drop for ExtractIf<'_, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        unsafe {
+            if self.idx < self.old_len && self.del > 0 {
+                let src = self.vec.ptr().add(self.idx);
+                ptr::copy(src, src.sub(self.del), self.old_len - self.idx);
+            }
+            self.vec.set_len(self.old_len - self.del);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::zen_vec::raw_vec::RawVec;
+use core::iter::FusedIterator;
+use core::{ptr, slice};
+
+// Owning iterator returned by `ZenVec::into_iter`. It keeps the vector's
+// buffer and reads elements out of it, so no new allocation is made.
+pub struct IntoIter<T> {
+    pub(super) buf: RawVec<T>,
+    pub(super) start: usize,
+    pub(super) end: usize,
+}
+
+impl<T> IntoIter<T> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.ptr().as_ptr().add(self.start), self.len()) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.buf.ptr().as_ptr().add(self.start), self.len()) }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start < self.end {
+            let item = unsafe { ptr::read(self.buf.ptr().as_ptr().add(self.start)) };
+            self.start += 1;
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start < self.end {
+            self.end -= 1;
+            unsafe { Some(ptr::read(self.buf.ptr().as_ptr().add(self.end))) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        let remaining = self.as_mut_slice() as *mut [T];
+        /* Mark everything consumed first so a panicking destructor can't double drop. */
+        self.start = self.end;
+        unsafe { ptr::drop_in_place(remaining) };
+        // The buffer itself is released by `RawVec`'s own `Drop`.
+    }
+}
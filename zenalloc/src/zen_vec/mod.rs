@@ -1,3 +1,8 @@
+pub mod drain;
+pub mod extract_if;
+pub mod into_iter;
 pub mod raw_vec;
+mod sort;
 // pub mod zen_slice;
+#[allow(clippy::module_inception)]
 pub mod zen_vec;
@@ -1,6 +1,9 @@
-use core::alloc::{Layout, AllocError};
+use core::alloc::Layout;
+use core::iter::FusedIterator;
+use core::mem;
 use core::ptr::NonNull;
 use crate::alloc_trait::Allocator;
+use crate::zen_error::zen_error::TryReserveError;
 use crate::System;
 
 pub struct RawVec<T> {
@@ -9,25 +12,32 @@ pub struct RawVec<T> {
 }
 
 impl<T> RawVec<T> {
+    // Zero-sized elements need no memory, so their buffer never allocates
+    // and has room for as many elements as a length can count.
+    const IS_ZST: bool = mem::size_of::<T>() == 0;
+
     pub fn new() -> Self {
         RawVec {
             ptr: NonNull::dangling(), // Initializes the pointer to a non-null dangling pointer
-            cap: 0,                   // Initializes the capacity to 0
+            cap: if Self::IS_ZST { usize::MAX } else { 0 },
         }
     }
 
     // Creates a new `RawVec` with the specified capacity.
-    pub fn with_capacity(capacity: usize) -> Result<Self, AllocError> {
-        // If the requested capacity is 0, return an empty `RawVec`.
-        if capacity == 0 {
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        // If the requested capacity is 0, or no memory is needed, return an empty `RawVec`.
+        if capacity == 0 || Self::IS_ZST {
             return Ok(Self::new());
         }
 
         // Calculate the layout for the requested capacity.
-        let layout = Layout::array::<T>(capacity).map_err(|_| AllocError)?;
+        let layout =
+            Layout::array::<T>(capacity).map_err(|_| TryReserveError::CapacityOverflow)?;
 
         // Allocate the memory using the custom allocator.
-        let ptr = System.allocate(layout)?;
+        let ptr = System
+            .allocate(layout)
+            .map_err(|_| TryReserveError::AllocError { layout })?;
 
         Ok(RawVec {
             ptr: ptr.cast(),
@@ -36,16 +46,59 @@ impl<T> RawVec<T> {
     }
 
     // Grows the capacity of the `RawVec` by doubling it.
-    pub fn grow(&mut self) -> Result<(), AllocError> {
+    pub fn grow(&mut self) -> Result<(), TryReserveError> {
         // Calculate the new capacity (double the current capacity, or 1 if the current capacity is 0).
-        let new_capacity = if self.cap == 0 { 1 } else { 2 * self.cap };
+        let new_capacity = if self.cap == 0 {
+            1
+        } else {
+            self.cap
+                .checked_mul(2)
+                .ok_or(TryReserveError::CapacityOverflow)?
+        };
+
+        self.grow_to(new_capacity)
+    }
+
+    // Ensures the buffer can hold `len + additional` elements, doubling the
+    // capacity when that is larger so repeated reservations stay amortized O(1).
+    pub fn reserve(&mut self, len: usize, additional: usize) -> Result<(), TryReserveError> {
+        let required = len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if required <= self.cap {
+            return Ok(());
+        }
+
+        self.grow_to(core::cmp::max(self.cap.saturating_mul(2), required))
+    }
+
+    // Ensures the buffer can hold exactly `len + additional` elements.
+    pub fn reserve_exact(&mut self, len: usize, additional: usize) -> Result<(), TryReserveError> {
+        let required = len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if required <= self.cap {
+            return Ok(());
+        }
+
+        self.grow_to(required)
+    }
+
+    fn grow_to(&mut self, new_capacity: usize) -> Result<(), TryReserveError> {
+        // A zero-sized buffer is already as large as it can get.
+        if Self::IS_ZST {
+            return Err(TryReserveError::CapacityOverflow);
+        }
 
         // Calculate the layouts for the old and new capacities.
-        let old_layout = Layout::array::<T>(self.cap).map_err(|_| AllocError)?;
-        let new_layout = Layout::array::<T>(new_capacity).map_err(|_| AllocError)?;
+        let old_layout =
+            Layout::array::<T>(self.cap).map_err(|_| TryReserveError::CapacityOverflow)?;
+        let new_layout =
+            Layout::array::<T>(new_capacity).map_err(|_| TryReserveError::CapacityOverflow)?;
 
         // Reallocate the memory to the new capacity using the custom allocator.
-        let new_ptr = unsafe { System.grow(self.ptr.cast(), old_layout, new_layout)? };
+        let new_ptr = unsafe { System.grow(self.ptr.cast(), old_layout, new_layout) }
+            .map_err(|_| TryReserveError::AllocError { layout: new_layout })?;
 
         // Update the pointer and capacity to the new values.
         self.ptr = new_ptr.cast();
@@ -54,6 +107,17 @@ impl<T> RawVec<T> {
         Ok(())
     }
 
+    /// # Safety
+    ///
+    /// `ptr` and `cap` must describe a buffer allocated by `System` with the
+    /// layout of `[T; cap]`, or be `NonNull::dangling()` with a capacity of 0.
+    pub unsafe fn from_raw_parts(ptr: NonNull<T>, cap: usize) -> Self {
+        RawVec {
+            ptr,
+            cap: if Self::IS_ZST { usize::MAX } else { cap },
+        }
+    }
+
     // Returns the current capacity of the `RawVec`.
     pub fn capacity(&self) -> usize {
         self.cap
@@ -64,7 +128,10 @@ impl<T> RawVec<T> {
         self.ptr
     }
 
-    pub fn iter(&self) -> RawVecIter<T> {
+    // Walks every slot up to the capacity, so it is only sound once all of
+    // them are initialized. Nothing in the crate needs that yet.
+    #[allow(dead_code)]
+    pub fn iter(&self) -> RawVecIter<'_, T> {
         RawVecIter {
             raw_vec: self,
             index: 0,
@@ -72,17 +139,24 @@ impl<T> RawVec<T> {
     }
 }
 
+impl<T> Default for RawVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // Implements the `Drop` trait for `RawVec` to ensure memory is properly deallocated.
 impl<T> Drop for RawVec<T> {
     fn drop(&mut self) {
         // If the capacity is not 0, deallocate the memory.
-        if self.cap != 0 {
+        if self.cap != 0 && !Self::IS_ZST {
             let layout = Layout::array::<T>(self.cap).unwrap();
             unsafe { System.deallocate(self.ptr.cast(), layout) };
         }
     }
 }
 
+#[allow(dead_code)]
 pub struct RawVecIter<'a, T> {
     raw_vec: &'a RawVec<T>,
     index: usize,
@@ -102,7 +176,16 @@ impl<'a, T> Iterator for RawVecIter<'a, T> {
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.raw_vec.capacity() - self.index;
+        (remaining, Some(remaining))
+    }
 }
 
+impl<T> ExactSizeIterator for RawVecIter<'_, T> {}
+
+impl<T> FusedIterator for RawVecIter<'_, T> {}
+
 unsafe impl<T: Send> Send for RawVec<T> {}
 unsafe impl<T: Sync> Sync for RawVec<T> {}
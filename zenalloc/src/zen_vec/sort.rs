@@ -0,0 +1,145 @@
+use crate::zen_error::zen_error::TryReserveError;
+use crate::zen_vec::raw_vec::RawVec;
+use core::mem::{self, ManuallyDrop};
+use core::ptr;
+
+// Runs this short are sorted by insertion, which needs no scratch space.
+const INSERTION_THRESHOLD: usize = 20;
+
+// Stable merge sort. The scratch buffer is taken before anything moves, so
+// if that fails the slice is untouched. If `is_less` panics, every element
+// is still in the slice exactly once, in some order.
+pub(super) fn try_merge_sort<T, F>(v: &mut [T], is_less: &mut F) -> Result<(), TryReserveError>
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    /* Zero-sized values can't be told apart, so any order is sorted. */
+    if v.len() < 2 || mem::size_of::<T>() == 0 {
+        return Ok(());
+    }
+
+    let scratch = if v.len() <= INSERTION_THRESHOLD {
+        0
+    } else {
+        v.len() / 2
+    };
+    let buf = RawVec::<T>::with_capacity(scratch)?;
+    unsafe { merge_sort(v, buf.ptr().as_ptr(), is_less) };
+    Ok(())
+}
+
+// `buf` must have room for `v.len() / 2` elements.
+unsafe fn merge_sort<T, F>(v: &mut [T], buf: *mut T, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let len = v.len();
+    if len <= INSERTION_THRESHOLD {
+        insertion_sort(v, is_less);
+        return;
+    }
+
+    let mid = len / 2;
+    merge_sort(&mut v[..mid], buf, is_less);
+    merge_sort(&mut v[mid..], buf, is_less);
+    /* The halves are already in order. */
+    if !is_less(&v[mid], &v[mid - 1]) {
+        return;
+    }
+    merge(v, mid, buf, is_less);
+}
+
+fn insertion_sort<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    for i in 1..v.len() {
+        unsafe { insert_tail(&mut v[..=i], is_less) };
+    }
+}
+
+// Moves the last element left into place within the sorted prefix.
+unsafe fn insert_tail<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let arr = v.as_mut_ptr();
+    let i = v.len() - 1;
+    if !is_less(&*arr.add(i), &*arr.add(i - 1)) {
+        return;
+    }
+
+    let tmp = ManuallyDrop::new(ptr::read(arr.add(i)));
+    /* Whatever happens below, the hole gets `tmp` back when this drops. */
+    let mut hole = Hole {
+        src: &*tmp,
+        dest: arr.add(i - 1),
+    };
+    ptr::copy_nonoverlapping(arr.add(i - 1), arr.add(i), 1);
+
+    for j in (0..i - 1).rev() {
+        if !is_less(&*tmp, &*arr.add(j)) {
+            break;
+        }
+        ptr::copy_nonoverlapping(arr.add(j), arr.add(j + 1), 1);
+        hole.dest = arr.add(j);
+    }
+}
+
+struct Hole<T> {
+    src: *const T,
+    dest: *mut T,
+}
+
+impl<T> Drop for Hole<T> {
+    fn drop(&mut self) {
+        unsafe { ptr::copy_nonoverlapping(self.src, self.dest, 1) };
+    }
+}
+
+// Merges the sorted runs `v[..mid]` and `v[mid..]`. The left run is moved
+// to `buf` and merged back from the front; ties go to the left run, which
+// keeps the sort stable.
+unsafe fn merge<T, F>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let arr = v.as_mut_ptr();
+    let right_end = arr.add(v.len());
+    ptr::copy_nonoverlapping(arr, buf, mid);
+
+    /* Whatever is left of the left run is copied back when this drops. */
+    let mut hole = MergeHole {
+        start: buf,
+        end: buf.add(mid),
+        dest: arr,
+    };
+    let mut right = arr.add(mid);
+
+    while hole.start < hole.end && right < right_end {
+        let take_right = is_less(&*right, &*hole.start);
+        let src = if take_right { right } else { hole.start };
+        ptr::copy_nonoverlapping(src, hole.dest, 1);
+        hole.dest = hole.dest.add(1);
+        if take_right {
+            right = right.add(1);
+        } else {
+            hole.start = hole.start.add(1);
+        }
+    }
+}
+
+struct MergeHole<T> {
+    start: *mut T,
+    end: *mut T,
+    dest: *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let len = self.end.offset_from(self.start) as usize;
+            ptr::copy_nonoverlapping(self.start, self.dest, len);
+        }
+    }
+}
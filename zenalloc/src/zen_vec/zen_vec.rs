@@ -1,16 +1,24 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_error::zen_error::{TryReserveError, ZenError};
+use crate::zen_vec::drain::Drain;
+use crate::zen_vec::extract_if::ExtractIf;
+use crate::zen_vec::into_iter::IntoIter;
 use crate::zen_vec::raw_vec::RawVec;
+use crate::zen_vec::sort;
 use core::{
     alloc::AllocError,
-    ops::{Deref, DerefMut, Drop},
-    ptr, slice,
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    mem,
+    ops::{Bound, Deref, DerefMut, Drop, Index, IndexMut, RangeBounds},
+    ptr::{self, NonNull},
+    slice,
 };
 
-#[derive(Debug, PartialEq)]
-pub enum VecError {
-    IndexOutOfBounds,
-    AllocationError(AllocError),
-}
-
 pub struct ZenVec<T> {
     buf: RawVec<T>,
     len: usize,
@@ -24,13 +32,48 @@ impl<T> ZenVec<T> {
         }
     }
 
-    pub fn with_capacity(capacity: usize) -> Result<Self, core::alloc::AllocError> {
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
         Ok(Self {
             buf: RawVec::with_capacity(capacity)?,
             len: 0,
         })
     }
 
+    // `n` copies of `elem`, allocated once.
+    pub fn try_from_elem(elem: T, n: usize) -> Result<Self, TryReserveError>
+    where
+        T: Clone,
+    {
+        let mut vec = Self::with_capacity(n)?;
+        if n > 0 {
+            for _ in 1..n {
+                /* Room was reserved above, so this can't fail. */
+                let _ = vec.push(elem.clone());
+            }
+            let _ = vec.push(elem);
+        }
+        Ok(vec)
+    }
+
+    // Takes the vector apart without dropping anything. The caller owns the
+    // `(ptr, len, cap)` buffer afterwards.
+    pub fn into_raw_parts(self) -> (*mut T, usize, usize) {
+        let vec = mem::ManuallyDrop::new(self);
+        (vec.ptr(), vec.len, vec.cap())
+    }
+
+    /// # Safety
+    ///
+    /// The parts must come from `into_raw_parts` on a `ZenVec` of a type
+    /// with the same size and alignment as `T`, and the first `len` elements
+    /// must be valid values of `T`.
+    pub unsafe fn from_raw_parts(ptr: *mut T, len: usize, cap: usize) -> Self {
+        Self {
+            buf: RawVec::from_raw_parts(NonNull::new_unchecked(ptr), cap),
+            len,
+        }
+    }
+
     pub fn ptr(&self) -> *mut T {
         self.buf.ptr().as_ptr()
     }
@@ -39,11 +82,9 @@ impl<T> ZenVec<T> {
         self.buf.capacity()
     }
 
-    pub fn push(&mut self, elem: T) -> Result<(), VecError> {
+    pub fn push(&mut self, elem: T) -> Result<(), ZenError> {
         if self.len == self.cap() {
-            if let Err(alloc_err) = self.buf.grow() {
-                return Err(VecError::AllocationError(alloc_err));
-            }
+            self.buf.grow()?;
         }
 
         unsafe {
@@ -64,15 +105,16 @@ impl<T> ZenVec<T> {
         }
     }
 
-    pub fn insert(&mut self, index: usize, elem: T) -> Result<(), VecError> {
+    pub fn insert(&mut self, index: usize, elem: T) -> Result<(), ZenError> {
         if index > self.len {
-            return Err(VecError::IndexOutOfBounds);
+            return Err(ZenError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
         }
 
         if self.len == self.cap() {
-            if let Err(alloc_err) = self.buf.grow() {
-                return Err(VecError::AllocationError(alloc_err));
-            }
+            self.buf.grow()?;
         }
 
         unsafe {
@@ -88,9 +130,12 @@ impl<T> ZenVec<T> {
         Ok(())
     }
 
-    pub fn remove(&mut self, index: usize) -> Result<T, VecError> {
+    pub fn remove(&mut self, index: usize) -> Result<T, ZenError> {
         if index >= self.len {
-            return Err(VecError::IndexOutOfBounds);
+            return Err(ZenError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
         }
 
         self.len -= 1;
@@ -110,7 +155,7 @@ impl<T> ZenVec<T> {
         unsafe { slice::from_raw_parts(self.ptr(), self.len) }
     }
 
-    pub fn as_mut_slice(&self) -> &mut [T] {
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
         unsafe { slice::from_raw_parts_mut(self.ptr(), self.len) }
     }
 
@@ -119,24 +164,405 @@ impl<T> ZenVec<T> {
         self.len
     }
 
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sets the length without dropping or initializing anything.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must not exceed the capacity and the elements in
+    /// `old_len..new_len` must be initialized.
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        self.len = new_len;
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.buf.reserve(self.len, additional)
+    }
+
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.buf.reserve_exact(self.len, additional)
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let remaining = self.len - len;
+        unsafe {
+            let tail = ptr::slice_from_raw_parts_mut(self.ptr().add(len), remaining);
+            /* Shorten first so a panicking destructor can't cause a double drop. */
+            self.len = len;
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, ZenError> {
+        if index >= self.len {
+            return Err(ZenError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+
+        unsafe {
+            let value = ptr::read(self.ptr().add(index));
+            ptr::copy(self.ptr().add(self.len - 1), self.ptr().add(index), 1);
+            self.len -= 1;
+            Ok(value)
+        }
+    }
+
+    pub fn drain<R>(&mut self, range: R) -> Result<Drain<'_, T>, ZenError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let (start, end) = check_range(range, len)?;
+
+        unsafe {
+            /* Leaking the Drain leaks the drained and tail elements, never double drops them. */
+            self.len = start;
+            let iter = slice::from_raw_parts(self.ptr().add(start), end - start).iter();
+            Ok(Drain {
+                tail_start: end,
+                tail_len: len - end,
+                iter,
+                vec: NonNull::from(self),
+            })
+        }
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let original_len = self.len;
+        if original_len == 0 {
+            return;
+        }
+
+        /* Hide the elements from the vector until the guard has closed the gaps. */
+        self.len = 0;
+
+        struct BackshiftOnDrop<'a, T> {
+            vec: &'a mut ZenVec<T>,
+            processed: usize,
+            deleted: usize,
+            original_len: usize,
+        }
+
+        impl<T> Drop for BackshiftOnDrop<'_, T> {
+            fn drop(&mut self) {
+                if self.deleted > 0 {
+                    unsafe {
+                        ptr::copy(
+                            self.vec.ptr().add(self.processed),
+                            self.vec.ptr().add(self.processed - self.deleted),
+                            self.original_len - self.processed,
+                        );
+                    }
+                }
+                self.vec.len = self.original_len - self.deleted;
+            }
+        }
+
+        let mut guard = BackshiftOnDrop {
+            vec: self,
+            processed: 0,
+            deleted: 0,
+            original_len,
+        };
+
+        while guard.processed != original_len {
+            let cur = unsafe { &mut *guard.vec.ptr().add(guard.processed) };
+            if !f(cur) {
+                guard.processed += 1;
+                guard.deleted += 1;
+                unsafe { ptr::drop_in_place(cur) };
+                continue;
+            }
+            if guard.deleted > 0 {
+                unsafe {
+                    let hole = guard.vec.ptr().add(guard.processed - guard.deleted);
+                    ptr::copy_nonoverlapping(cur, hole, 1);
+                }
+            }
+            guard.processed += 1;
+        }
+    }
+
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let len = self.len;
+        if len <= 1 {
+            return;
+        }
+
+        struct FillGapOnDrop<'a, T> {
+            read: usize,
+            write: usize,
+            vec: &'a mut ZenVec<T>,
+        }
+
+        impl<T> Drop for FillGapOnDrop<'_, T> {
+            fn drop(&mut self) {
+                /* Only reached if `same_bucket` or a destructor panicked. */
+                unsafe {
+                    let ptr = self.vec.ptr();
+                    let len = self.vec.len;
+                    let items_left = len - self.read;
+                    ptr::copy(ptr.add(self.read), ptr.add(self.write), items_left);
+                    self.vec.len = len - (self.read - self.write);
+                }
+            }
+        }
+
+        let mut gap = FillGapOnDrop {
+            read: 1,
+            write: 1,
+            vec: self,
+        };
+        let ptr = gap.vec.ptr();
+
+        unsafe {
+            while gap.read < len {
+                let read_ptr = ptr.add(gap.read);
+                let prev_ptr = ptr.add(gap.write - 1);
+
+                if same_bucket(&mut *read_ptr, &mut *prev_ptr) {
+                    gap.read += 1;
+                    ptr::drop_in_place(read_ptr);
+                } else {
+                    ptr::copy(read_ptr, ptr.add(gap.write), 1);
+                    gap.write += 1;
+                    gap.read += 1;
+                }
+            }
+
+            gap.vec.len = gap.write;
+            mem::forget(gap);
+        }
+    }
+
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: PartialEq,
+        F: FnMut(&mut T) -> K,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    // Stable sort. The merge scratch buffer is allocated up front; if that
+    // fails the error is returned and the contents are left as they were.
+    pub fn try_sort(&mut self) -> Result<(), TryReserveError>
+    where
+        T: Ord,
+    {
+        self.try_sort_by(|a, b| a.cmp(b))
+    }
+
+    pub fn try_sort_by<F>(&mut self, mut compare: F) -> Result<(), TryReserveError>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        sort::try_merge_sort(self.as_mut_slice(), &mut |a, b| {
+            compare(a, b) == Ordering::Less
+        })
+    }
+
+    pub fn try_sort_by_key<K, F>(&mut self, mut key: F) -> Result<(), TryReserveError>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.try_sort_by(|a, b| key(a).cmp(&key(b)))
+    }
+
+    pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let old_len = self.len;
+        /* Leaking the iterator leaks the elements instead of exposing moved-out slots. */
+        self.len = 0;
+        ExtractIf {
+            vec: self,
+            idx: 0,
+            del: 0,
+            old_len,
+            pred: filter,
+        }
+    }
+
+    pub fn try_split_off(&mut self, at: usize) -> Result<Self, ZenError> {
+        if at > self.len {
+            return Err(ZenError::IndexOutOfBounds {
+                index: at,
+                len: self.len,
+            });
+        }
+
+        let other_len = self.len - at;
+        let mut other = ZenVec::with_capacity(other_len)?;
+
+        unsafe {
+            self.len = at;
+            ptr::copy_nonoverlapping(self.ptr().add(at), other.ptr(), other_len);
+            other.len = other_len;
+        }
+
+        Ok(other)
+    }
+
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), ZenError> {
+        let count = other.len;
+        self.try_reserve(count)?;
+
+        unsafe {
+            ptr::copy_nonoverlapping(other.ptr(), self.ptr().add(self.len), count);
+            other.len = 0;
+        }
+
+        self.len += count;
+        Ok(())
+    }
+
+    // Replaces `range` with the items of `replace_with` and returns the
+    // removed elements. Everything that can fail happens before the vector
+    // is touched, so on error `self` is left unchanged.
+    pub fn try_splice<R, I>(&mut self, range: R, replace_with: I) -> Result<ZenVec<T>, ZenError>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let (start, end) = check_range(range, self.len)?;
+
+        let iter = replace_with.into_iter();
+        let mut replacement = ZenVec::new();
+        replacement.try_reserve(iter.size_hint().0)?;
+        for item in iter {
+            replacement.push(item)?;
+        }
+
+        let removed_len = end - start;
+        let mut removed = ZenVec::with_capacity(removed_len)?;
+        if replacement.len > removed_len {
+            self.try_reserve(replacement.len - removed_len)?;
+        }
+
+        unsafe {
+            let base = self.ptr();
+            let tail_len = self.len - end;
+
+            ptr::copy_nonoverlapping(base.add(start), removed.ptr(), removed_len);
+            removed.len = removed_len;
+
+            ptr::copy(base.add(end), base.add(start + replacement.len), tail_len);
+            ptr::copy_nonoverlapping(replacement.ptr(), base.add(start), replacement.len);
+
+            self.len = start + replacement.len + tail_len;
+            replacement.len = 0;
+        }
+
+        Ok(removed)
+    }
+
     pub fn iter(&self) -> ZenVecIter<'_, T> {
         ZenVecIter {
             zen_vec: self,
             index: 0,
+            end: self.len,
         }
     }
 
     pub fn iter_mut(&mut self) -> ZenVecIterMut<'_, T> {
+        let end = self.len;
         ZenVecIterMut {
             zen_vec: self,
             index: 0,
+            end,
         }
     }
 }
 
 impl<T> Drop for ZenVec<T> {
     fn drop(&mut self) {
-        while let Some(_) = self.pop() {}
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T> Default for ZenVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: TryClone> TryClone for ZenVec<T> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut vec = ZenVec::with_capacity(self.len)?;
+        for elem in self.iter() {
+            /* Capacity is already reserved, so this can only fail in `try_clone`. */
+            let _ = vec.push(elem.try_clone()?);
+        }
+        Ok(vec)
+    }
+}
+
+impl<T> TryFromIterator<T> for ZenVec<T> {
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, AllocError> {
+        let mut vec = ZenVec::new();
+        vec.try_extend(iter)?;
+        Ok(vec)
+    }
+}
+
+impl<T> TryExtend<T> for ZenVec<T> {
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let iter = iter.into_iter();
+        let original_len = self.len;
+
+        self.buf.reserve(self.len, iter.size_hint().0)?;
+        for elem in iter {
+            if let Err(err) = self.push(elem) {
+                self.truncate(original_len);
+                return Err(err.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, T: Copy + 'a> TryExtend<&'a T> for ZenVec<T> {
+    fn try_extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        self.try_extend(iter.into_iter().copied())
     }
 }
 
@@ -190,16 +616,146 @@ where
     }
 }
 
+impl<T: Eq> Eq for ZenVec<T> {}
+
+impl<T: PartialOrd> PartialOrd for ZenVec<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T: Ord> Ord for ZenVec<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+// Hashes like the equivalent slice, so `Borrow<[T]>` lookups work.
+impl<T: Hash> Hash for ZenVec<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ZenVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, I: slice::SliceIndex<[T]>> Index<I> for ZenVec<T> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &I::Output {
+        &self.as_slice()[index]
+    }
+}
+
+impl<T, I: slice::SliceIndex<[T]>> IndexMut<I> for ZenVec<T> {
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.as_mut_slice()[index]
+    }
+}
+
+impl<T> Borrow<[T]> for ZenVec<T> {
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> BorrowMut<[T]> for ZenVec<T> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T> AsRef<[T]> for ZenVec<T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> AsMut<[T]> for ZenVec<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+// Moves the elements in; allocating the buffer is the only way to fail.
+impl<T, const N: usize> TryFrom<[T; N]> for ZenVec<T> {
+    type Error = TryReserveError;
+
+    fn try_from(array: [T; N]) -> Result<Self, TryReserveError> {
+        let mut vec = ZenVec::with_capacity(N)?;
+        let array = mem::ManuallyDrop::new(array);
+        unsafe {
+            ptr::copy_nonoverlapping(array.as_ptr(), vec.ptr(), N);
+            vec.set_len(N);
+        }
+        Ok(vec)
+    }
+}
+
+// Clones the elements, as `From<&[T]>` does for `Vec`, so the buffer is the
+// only allocation that can fail. Elements that copy fallibly go through
+// `try_clone` on a `ZenVec` instead.
+impl<T: Clone> TryFrom<&[T]> for ZenVec<T> {
+    type Error = TryReserveError;
+
+    fn try_from(items: &[T]) -> Result<Self, TryReserveError> {
+        let mut vec = ZenVec::with_capacity(items.len())?;
+        for item in items {
+            /* Capacity is already reserved, so this can't fail. */
+            let _ = vec.push(item.clone());
+        }
+        Ok(vec)
+    }
+}
+
+// Resolves `range` against a collection of length `len`, rejecting reversed
+// or out-of-range bounds instead of panicking.
+pub(crate) fn check_range<R>(range: R, len: usize) -> Result<(usize, usize), ZenError>
+where
+    R: RangeBounds<usize>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start
+            .checked_add(1)
+            .ok_or(ZenError::IndexOutOfBounds { index: start, len })?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end
+            .checked_add(1)
+            .ok_or(ZenError::IndexOutOfBounds { index: end, len })?,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    if end > len {
+        return Err(ZenError::IndexOutOfBounds { index: end, len });
+    }
+    /* A reversed range reports its start against its end, like std's
+    "slice index starts at .. but ends at .." panic. */
+    if start > end {
+        return Err(ZenError::IndexOutOfBounds { index: start, len: end });
+    }
+
+    Ok((start, end))
+}
+
 pub struct ZenVecIter<'a, T> {
     zen_vec: &'a ZenVec<T>,
     index: usize,
+    end: usize,
 }
 
 impl<'a, T> Iterator for ZenVecIter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.zen_vec.len() {
+        if self.index < self.end {
             unsafe {
                 let item = &*self.zen_vec.ptr().add(self.index);
                 self.index += 1;
@@ -209,18 +765,49 @@ impl<'a, T> Iterator for ZenVecIter<'a, T> {
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for ZenVecIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index < self.end {
+            self.end -= 1;
+            unsafe { Some(&*self.zen_vec.ptr().add(self.end)) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for ZenVecIter<'_, T> {}
+
+impl<T> FusedIterator for ZenVecIter<'_, T> {}
+
+impl<T> Clone for ZenVecIter<'_, T> {
+    fn clone(&self) -> Self {
+        ZenVecIter {
+            zen_vec: self.zen_vec,
+            index: self.index,
+            end: self.end,
+        }
+    }
 }
 
 pub struct ZenVecIterMut<'a, T> {
     zen_vec: &'a mut ZenVec<T>,
     index: usize,
+    end: usize,
 }
 
 impl<'a, T> Iterator for ZenVecIterMut<'a, T> {
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.zen_vec.len() {
+        if self.index < self.end {
             unsafe {
                 let item = &mut *self.zen_vec.ptr().add(self.index);
                 self.index += 1;
@@ -230,6 +817,56 @@ impl<'a, T> Iterator for ZenVecIterMut<'a, T> {
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for ZenVecIterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index < self.end {
+            self.end -= 1;
+            unsafe { Some(&mut *self.zen_vec.ptr().add(self.end)) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for ZenVecIterMut<'_, T> {}
+
+impl<T> FusedIterator for ZenVecIterMut<'_, T> {}
+
+impl<T> IntoIterator for ZenVec<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> IntoIter<T> {
+        /* Hand the buffer over to the iterator and leave an empty vector to drop. */
+        let buf = mem::take(&mut self.buf);
+        let end = mem::take(&mut self.len);
+        IntoIter { buf, start: 0, end }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ZenVec<T> {
+    type Item = &'a T;
+    type IntoIter = ZenVecIter<'a, T>;
+
+    fn into_iter(self) -> ZenVecIter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut ZenVec<T> {
+    type Item = &'a mut T;
+    type IntoIter = ZenVecIterMut<'a, T>;
+
+    fn into_iter(self) -> ZenVecIterMut<'a, T> {
+        self.iter_mut()
+    }
 }
 
 unsafe impl<T: Send> Send for ZenVec<T> {}
@@ -0,0 +1,644 @@
+use crate::try_clone::TryClone;
+use crate::try_collect::{TryExtend, TryFromIterator};
+use crate::zen_vec::raw_vec::RawVec;
+use crate::zen_error::zen_error::{TryReserveError, ZenError};
+use crate::zen_vec::zen_vec::check_range;
+use core::{
+    alloc::AllocError,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops::RangeBounds,
+    ptr::{self, NonNull},
+    slice,
+};
+
+// A double-ended queue implemented as a growable ring buffer. The
+// elements occupy `len` slots starting at `head`, wrapping around the end
+// of the buffer.
+pub struct ZenVecDeque<T> {
+    buf: RawVec<T>,
+    head: usize,
+    len: usize,
+}
+
+impl<T> ZenVecDeque<T> {
+    pub fn new() -> Self {
+        Self {
+            buf: RawVec::new(),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            buf: RawVec::with_capacity(capacity)?,
+            head: 0,
+            len: 0,
+        })
+    }
+
+    fn ptr(&self) -> *mut T {
+        self.buf.ptr().as_ptr()
+    }
+
+    pub fn cap(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Maps a logical index to its slot in the buffer.
+    #[inline]
+    fn to_physical(&self, index: usize) -> usize {
+        let cap = self.cap();
+        let offset = cap - self.head;
+        if index >= offset {
+            index - offset
+        } else {
+            self.head + index
+        }
+    }
+
+    #[inline]
+    fn is_contiguous(&self) -> bool {
+        self.len <= self.cap() - self.head
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let old_cap = self.cap();
+        self.buf.reserve(self.len, additional)?;
+        self.handle_capacity_increase(old_cap);
+        Ok(())
+    }
+
+    // After the buffer grew, moves the wrapped part of the ring so the
+    // elements are again in order starting at `head`.
+    fn handle_capacity_increase(&mut self, old_cap: usize) {
+        let new_cap = self.cap();
+        if self.len <= old_cap - self.head {
+            return;
+        }
+
+        let head_len = old_cap - self.head;
+        let tail_len = self.len - head_len;
+        unsafe {
+            if tail_len < head_len && tail_len <= new_cap - old_cap {
+                /* [. . . H H H T T] -> append the short tail after the old end */
+                ptr::copy_nonoverlapping(self.ptr(), self.ptr().add(old_cap), tail_len);
+            } else {
+                /* Move the head segment to the end of the new buffer. */
+                let new_head = new_cap - head_len;
+                ptr::copy(self.ptr().add(self.head), self.ptr().add(new_head), head_len);
+                self.head = new_head;
+            }
+        }
+    }
+
+    fn grow_if_full(&mut self) -> Result<(), TryReserveError> {
+        if self.len == self.cap() {
+            let old_cap = self.cap();
+            self.buf.grow()?;
+            self.handle_capacity_increase(old_cap);
+        }
+        Ok(())
+    }
+
+    pub fn try_push_back(&mut self, value: T) -> Result<(), TryReserveError> {
+        self.grow_if_full()?;
+
+        unsafe {
+            ptr::write(self.ptr().add(self.to_physical(self.len)), value);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn try_push_front(&mut self, value: T) -> Result<(), TryReserveError> {
+        self.grow_if_full()?;
+
+        self.head = if self.head == 0 {
+            self.cap() - 1
+        } else {
+            self.head - 1
+        };
+        unsafe {
+            ptr::write(self.ptr().add(self.head), value);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let old_head = self.head;
+        self.head = self.to_physical(1);
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.ptr().add(old_head))) }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.ptr().add(self.to_physical(self.len)))) }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            unsafe { Some(&*self.ptr().add(self.to_physical(index))) }
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            unsafe { Some(&mut *self.ptr().add(self.to_physical(index))) }
+        } else {
+            None
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.len.checked_sub(1).and_then(|index| self.get_mut(index))
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            drop(self.pop_back());
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.head = 0;
+    }
+
+    // Returns the contents as two slices: the elements from `head` up to
+    // the end of the buffer, followed by the ones that wrapped around.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        unsafe {
+            if self.is_contiguous() {
+                (slice::from_raw_parts(self.ptr().add(self.head), self.len), &[])
+            } else {
+                let head_len = self.cap() - self.head;
+                (
+                    slice::from_raw_parts(self.ptr().add(self.head), head_len),
+                    slice::from_raw_parts(self.ptr(), self.len - head_len),
+                )
+            }
+        }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        unsafe {
+            if self.is_contiguous() {
+                (slice::from_raw_parts_mut(self.ptr().add(self.head), self.len), &mut [])
+            } else {
+                let head_len = self.cap() - self.head;
+                (
+                    slice::from_raw_parts_mut(self.ptr().add(self.head), head_len),
+                    slice::from_raw_parts_mut(self.ptr(), self.len - head_len),
+                )
+            }
+        }
+    }
+
+    // Rearranges the buffer so all elements are in one slice, in order.
+    // Runs in O(len) without allocating.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if !self.is_contiguous() {
+            let cap = self.cap();
+            let head_len = cap - self.head;
+            let tail_len = self.len - head_len;
+            let free = cap - self.len;
+
+            unsafe {
+                /* [T T . . H H H] -> [. . T T H H H] -> [. . H H H T T] */
+                ptr::copy(self.ptr(), self.ptr().add(free), tail_len);
+                let joined = slice::from_raw_parts_mut(self.ptr().add(free), self.len);
+                joined.rotate_left(tail_len);
+            }
+            self.head = free;
+        }
+
+        unsafe { slice::from_raw_parts_mut(self.ptr().add(self.head), self.len) }
+    }
+
+    // Removes `range` from the deque and yields the removed elements. The
+    // buffer is made contiguous first, so this never allocates.
+    pub fn drain<R>(&mut self, range: R) -> Result<Drain<'_, T>, ZenError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let (start, end) = check_range(range, len)?;
+        self.make_contiguous();
+
+        /* Leaking the Drain leaks the drained and tail elements, never double drops them. */
+        self.len = start;
+        Ok(Drain {
+            deque: NonNull::from(self),
+            idx: start,
+            end,
+            drain_start: start,
+            tail_start: end,
+            tail_len: len - end,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            deque: self,
+            front: 0,
+            back: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            ptr: self.ptr(),
+            head: self.head,
+            cap: self.cap(),
+            front: 0,
+            back: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for ZenVecDeque<T> {
+    fn drop(&mut self) {
+        let (front, back) = self.as_mut_slices();
+        let (front, back) = (front as *mut [T], back as *mut [T]);
+        self.len = 0;
+        unsafe {
+            ptr::drop_in_place(front);
+            ptr::drop_in_place(back);
+        }
+    }
+}
+
+impl<T> Default for ZenVecDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: TryClone> TryClone for ZenVecDeque<T> {
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut deque = ZenVecDeque::with_capacity(self.len)?;
+        for elem in self.iter() {
+            deque.try_push_back(elem.try_clone()?)?;
+        }
+        Ok(deque)
+    }
+}
+
+impl<T> TryFromIterator<T> for ZenVecDeque<T> {
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, AllocError> {
+        let mut deque = ZenVecDeque::new();
+        deque.try_extend(iter)?;
+        Ok(deque)
+    }
+}
+
+impl<T> TryExtend<T> for ZenVecDeque<T> {
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let iter = iter.into_iter();
+        let original_len = self.len;
+
+        self.try_reserve(iter.size_hint().0)?;
+        for elem in iter {
+            if let Err(err) = self.try_push_back(elem) {
+                self.truncate(original_len);
+                return Err(err.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: PartialEq> PartialEq for ZenVecDeque<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: PartialEq> PartialEq<[T]> for ZenVecDeque<T> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.len == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq<[T; N]> for ZenVecDeque<T> {
+    fn eq(&self, other: &[T; N]) -> bool {
+        self == other.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for ZenVecDeque<T> {}
+
+impl<T: PartialOrd> PartialOrd for ZenVecDeque<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for ZenVecDeque<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+// Hashes element by element, since equal deques can wrap at different
+// points.
+impl<T: Hash> Hash for ZenVecDeque<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        self.iter().for_each(|elem| elem.hash(state));
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ZenVecDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> IntoIterator for ZenVecDeque<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { deque: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ZenVecDeque<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut ZenVecDeque<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+unsafe impl<T: Send> Send for ZenVecDeque<T> {}
+unsafe impl<T: Sync> Sync for ZenVecDeque<T> {}
+
+pub struct Iter<'a, T> {
+    deque: &'a ZenVecDeque<T>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.front < self.back {
+            let item = self.deque.get(self.front);
+            self.front += 1;
+            item
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.front < self.back {
+            self.back -= 1;
+            self.deque.get(self.back)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    ptr: *mut T,
+    head: usize,
+    cap: usize,
+    front: usize,
+    back: usize,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<T> IterMut<'_, T> {
+    #[inline]
+    fn slot(&self, index: usize) -> *mut T {
+        let offset = self.cap - self.head;
+        let physical = if index >= offset {
+            index - offset
+        } else {
+            self.head + index
+        };
+        unsafe { self.ptr.add(physical) }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.front < self.back {
+            let item = unsafe { &mut *self.slot(self.front) };
+            self.front += 1;
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.front < self.back {
+            self.back -= 1;
+            unsafe { Some(&mut *self.slot(self.back)) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+// Owning iterator returned by `ZenVecDeque::into_iter`.
+pub struct IntoIter<T> {
+    deque: ZenVecDeque<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.len, Some(self.deque.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.deque.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+// Iterator returned by `ZenVecDeque::drain`. The elements after the
+// drained range are moved back into place when it is dropped.
+pub struct Drain<'a, T> {
+    deque: NonNull<ZenVecDeque<T>>,
+    idx: usize,
+    end: usize,
+    drain_start: usize,
+    tail_start: usize,
+    tail_len: usize,
+    marker: PhantomData<&'a mut ZenVecDeque<T>>,
+}
+
+impl<T> Drain<'_, T> {
+    #[inline]
+    fn slot(&self, index: usize) -> *mut T {
+        /* The deque was made contiguous, so logical indices are offsets from `head`. */
+        unsafe {
+            let deque = self.deque.as_ref();
+            deque.ptr().add(deque.head + index)
+        }
+    }
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.idx < self.end {
+            let item = unsafe { ptr::read(self.slot(self.idx)) };
+            self.idx += 1;
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.idx;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.idx < self.end {
+            self.end -= 1;
+            unsafe { Some(ptr::read(self.slot(self.end))) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        /* Moves the tail back even if dropping a remaining element panics. */
+        struct MoveTail<'r, 'a, T>(&'r mut Drain<'a, T>);
+
+        impl<T> Drop for MoveTail<'_, '_, T> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                unsafe {
+                    ptr::copy(
+                        drain.slot(drain.tail_start),
+                        drain.slot(drain.drain_start),
+                        drain.tail_len,
+                    );
+                    drain.deque.as_mut().len = drain.drain_start + drain.tail_len;
+                }
+            }
+        }
+
+        let (idx, end) = (self.idx, self.end);
+        self.idx = self.end;
+        let guard = MoveTail(self);
+        unsafe {
+            let remaining = ptr::slice_from_raw_parts_mut(guard.0.slot(idx), end - idx);
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for Drain<'_, T> {}
+unsafe impl<T: Sync> Sync for Drain<'_, T> {}
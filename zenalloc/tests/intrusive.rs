@@ -0,0 +1,24 @@
+// `intrusive_adapter!` implements a crate trait for a type defined in the
+// caller's crate, so it has to name that trait through a public path.
+
+use zenalloc::{LinkedList, LinkedListLink, ZenArc};
+
+struct Task {
+    id: u32,
+    link: LinkedListLink,
+}
+
+zenalloc::intrusive_adapter!(TaskAdapter = ZenArc<Task>: Task { link: LinkedListLink });
+
+#[test]
+fn test_intrusive_adapter_from_another_crate() {
+    let mut list = LinkedList::new(TaskAdapter);
+    for id in 0..3 {
+        let task = ZenArc::new(Task { id, link: LinkedListLink::new() }).unwrap();
+        assert!(list.push_back(task).is_ok());
+    }
+
+    let ids: Vec<u32> = list.iter().map(|task| task.id).collect();
+    assert_eq!(ids, [0, 1, 2]);
+    assert_eq!(list.pop_front().map(|task| task.id), Some(0));
+}
@@ -0,0 +1,27 @@
+// The exported macros must expand to paths a downstream crate can reach, so
+// these are used from outside the crate with nothing but `zenalloc` in scope.
+
+#[test]
+fn test_try_macros_from_another_crate() {
+    let list = zenalloc::try_vec![1u32, 2, 3].unwrap();
+    assert!(list == [1, 2, 3]);
+    let repeated = zenalloc::try_vec![7u8; 4].unwrap();
+    assert!(repeated == [7, 7, 7, 7]);
+    let empty: zenalloc::ZenVec<u8> = zenalloc::try_vec![].unwrap();
+    assert!(empty.is_empty());
+
+    let boxed = zenalloc::try_box!(40 + 2).unwrap();
+    assert_eq!(*boxed, 42);
+
+    let ascii = zenalloc::try_ascii!("hello").unwrap();
+    assert_eq!(ascii.len(), 5);
+
+    let formatted = zenalloc::try_format!("{}-{}", 4, 2).unwrap();
+    assert_eq!(formatted.as_str(), "4-2");
+
+    let err: zenalloc::ZenError = zenalloc::try_vec![0u64; usize::MAX].unwrap_err();
+    assert_eq!(
+        err,
+        zenalloc::ZenError::TryReserve(zenalloc::TryReserveError::CapacityOverflow)
+    );
+}